@@ -0,0 +1,9 @@
+-- CreateTable
+CREATE TABLE "SharedServer" (
+    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "name" TEXT NOT NULL,
+    "ip" TEXT NOT NULL,
+    "icon" TEXT,
+    "acceptTextures" BOOLEAN,
+    "position" INTEGER NOT NULL
+);
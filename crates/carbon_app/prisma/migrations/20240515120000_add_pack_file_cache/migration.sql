@@ -0,0 +1,17 @@
+-- CreateTable
+CREATE TABLE "PackFileCache" (
+    "id" TEXT NOT NULL PRIMARY KEY,
+    "lastUpdatedAt" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "instanceId" INTEGER NOT NULL,
+    "packType" TEXT NOT NULL,
+    "filename" TEXT NOT NULL,
+    "filesize" INTEGER NOT NULL,
+    "enabled" BOOLEAN NOT NULL,
+    "packFormat" INTEGER,
+    "metadataId" TEXT NOT NULL,
+    CONSTRAINT "PackFileCache_instanceId_fkey" FOREIGN KEY ("instanceId") REFERENCES "Instance" ("id") ON DELETE CASCADE ON UPDATE CASCADE,
+    CONSTRAINT "PackFileCache_metadataId_fkey" FOREIGN KEY ("metadataId") REFERENCES "ModMetadata" ("id") ON DELETE RESTRICT ON UPDATE CASCADE
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "PackFileCache_instanceId_packType_filename_key" ON "PackFileCache"("instanceId", "packType", "filename");
@@ -0,0 +1,5 @@
+-- CreateTable
+CREATE TABLE "SharedGameOption" (
+    "key" TEXT NOT NULL PRIMARY KEY,
+    "value" TEXT NOT NULL
+);
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::Infallible;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -66,7 +66,7 @@ pub(super) fn mount() -> RouterBuilder<App> {
                 return Err(anyhow::anyhow!("instance name cannot be empty"));
             }
 
-            app.instance_manager()
+            let id = app.instance_manager()
                 .create_instance(
                     details.group.into(),
                     details.name,
@@ -74,8 +74,18 @@ pub(super) fn mount() -> RouterBuilder<App> {
                     details.version.try_into()?,
                     details.notes,
                 )
-                .await
-                .map(FEInstanceId::from)
+                .await?;
+
+            if let Some(kind) = details.kind {
+                app.instance_manager()
+                    .update_instance(domain::InstanceSettingsUpdate {
+                        kind: Some(kind.into()),
+                        ..domain::InstanceSettingsUpdate::new(id)
+                    })
+                    .await?;
+            }
+
+            Ok(FEInstanceId::from(id))
         }
 
         mutation CHANGE_MODPACK[app, details: ChangeModpack] {
@@ -206,7 +216,7 @@ pub(super) fn mount() -> RouterBuilder<App> {
 
         mutation PREPARE_INSTANCE[app, id: FEInstanceId] {
             let (_, vtask_id) = app.instance_manager()
-                .prepare_game(id.into(), None, None, true)
+                .prepare_game(id.into(), None, None, None, true)
                 .await?;
 
             Ok(FETaskId::from(vtask_id))
@@ -222,7 +232,29 @@ pub(super) fn mount() -> RouterBuilder<App> {
             };
 
             app.instance_manager()
-                .prepare_game(id.into(), Some(account), None, false)
+                .prepare_game(id.into(), Some(account), None, None, false)
+                .await?;
+
+            Ok(())
+        }
+
+        mutation QUICK_PLAY_INSTANCE[app, args: QuickPlayInstance] {
+            let account = app.account_manager()
+                .get_active_account()
+                .await?;
+
+            let Some(account) = account else {
+                return Err(anyhow::anyhow!("attempted to launch instance without an account"));
+            };
+
+            app.instance_manager()
+                .prepare_game(
+                    args.instance_id.into(),
+                    Some(account),
+                    Some(args.quick_play.into()),
+                    None,
+                    false,
+                )
                 .await?;
 
             Ok(())
@@ -234,6 +266,12 @@ pub(super) fn mount() -> RouterBuilder<App> {
                 .await
         }
 
+        mutation SEND_INSTANCE_COMMAND[app, args: SendInstanceCommand] {
+            app.instance_manager()
+                .send_instance_command(args.instance_id.into(), args.command)
+                .await
+        }
+
         query GET_LOGS[app, id: FEInstanceId] {
             Ok(app.instance_manager()
                .get_logs(id.into())
@@ -331,6 +369,152 @@ pub(super) fn mount() -> RouterBuilder<App> {
                 .map(super::modplatforms::ModSources::from)
         }
 
+        query INSTANCE_RESOURCE_PACKS[app, id: Option<FEInstanceId>] {
+            let Some(id) = id else {
+                return Ok(None);
+            };
+
+            app.meta_cache_manager()
+                .watch_and_prioritize(Some(id.into()))
+                .await;
+
+            let result = app.instance_manager()
+                .list_packs(id.into(), domain::PackType::ResourcePack)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<Pack>>();
+
+            Ok(Some(result))
+        }
+
+        mutation ENABLE_RESOURCE_PACK[app, args: InstancePack] {
+            app.instance_manager()
+                .enable_pack(
+                    args.instance_id.into(),
+                    args.pack_id,
+                    true,
+                )
+                .await
+        }
+
+        mutation DISABLE_RESOURCE_PACK[app, args: InstancePack] {
+            app.instance_manager()
+                .enable_pack(
+                    args.instance_id.into(),
+                    args.pack_id,
+                    false,
+                )
+                .await
+        }
+
+        mutation DELETE_RESOURCE_PACK[app, args: InstancePack] {
+            app.instance_manager()
+                .delete_pack(
+                    args.instance_id.into(),
+                    args.pack_id,
+                )
+                .await
+        }
+
+        mutation INSTALL_RESOURCE_PACK[app, args: InstallPack] {
+            let task = args.install(&app, domain::PackType::ResourcePack).await?;
+            Ok(super::vtask::FETaskId::from(task))
+        }
+
+        mutation UPDATE_RESOURCE_PACK[app, args: InstancePack] {
+            let task = app.instance_manager().update_pack(
+                args.instance_id.into(),
+                args.pack_id,
+            ).await?;
+
+            Ok(super::vtask::FETaskId::from(task))
+        }
+
+        query FIND_RESOURCE_PACK_UPDATE[app, args: InstancePack] {
+            app.instance_manager().find_pack_update(
+                args.instance_id.into(),
+                args.pack_id,
+            ).await
+            .map(|v| v.map(RemoteVersion::from))
+        }
+
+        query INSTANCE_SHADER_PACKS[app, id: Option<FEInstanceId>] {
+            let Some(id) = id else {
+                return Ok(None);
+            };
+
+            app.meta_cache_manager()
+                .watch_and_prioritize(Some(id.into()))
+                .await;
+
+            let result = app.instance_manager()
+                .list_packs(id.into(), domain::PackType::ShaderPack)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<Pack>>();
+
+            Ok(Some(result))
+        }
+
+        mutation ENABLE_SHADER_PACK[app, args: InstancePack] {
+            app.instance_manager()
+                .enable_pack(
+                    args.instance_id.into(),
+                    args.pack_id,
+                    true,
+                )
+                .await
+        }
+
+        mutation DISABLE_SHADER_PACK[app, args: InstancePack] {
+            app.instance_manager()
+                .enable_pack(
+                    args.instance_id.into(),
+                    args.pack_id,
+                    false,
+                )
+                .await
+        }
+
+        mutation DELETE_SHADER_PACK[app, args: InstancePack] {
+            app.instance_manager()
+                .delete_pack(
+                    args.instance_id.into(),
+                    args.pack_id,
+                )
+                .await
+        }
+
+        mutation INSTALL_SHADER_PACK[app, args: InstallPack] {
+            let task = args.install(&app, domain::PackType::ShaderPack).await?;
+            Ok(super::vtask::FETaskId::from(task))
+        }
+
+        mutation UPDATE_SHADER_PACK[app, args: InstancePack] {
+            let task = app.instance_manager().update_pack(
+                args.instance_id.into(),
+                args.pack_id,
+            ).await?;
+
+            Ok(super::vtask::FETaskId::from(task))
+        }
+
+        query FIND_SHADER_PACK_UPDATE[app, args: InstancePack] {
+            app.instance_manager().find_pack_update(
+                args.instance_id.into(),
+                args.pack_id,
+            ).await
+            .map(|v| v.map(RemoteVersion::from))
+        }
+
+        query MISSING_SHADER_LOADER[app, instance_id: FEInstanceId] {
+            app.instance_manager()
+                .missing_shader_loader(instance_id.into())
+                .await
+        }
+
         mutation INSTALL_LATEST_MOD[app, imod: InstallLatestMod] {
             let task = match imod.mod_source {
                 LatestModSource::Curseforge(cf_mod) => {
@@ -414,6 +598,254 @@ pub(super) fn mount() -> RouterBuilder<App> {
                 .map(|entries| entries.into_iter().map(ExploreEntry::from).collect::<Vec<_>>())
         }
 
+        query GET_WORLDS[app, instance_id: FEInstanceId] {
+            app.instance_manager()
+                .list_worlds(instance_id.into())
+                .await
+                .map(|worlds| worlds.into_iter().map(FEWorld::from).collect::<Vec<_>>())
+        }
+
+        mutation RENAME_WORLD[app, args: RenameWorld] {
+            app.instance_manager()
+                .rename_world(args.instance_id.into(), args.folder, args.name)
+                .await
+        }
+
+        mutation DUPLICATE_WORLD[app, args: WorldRef] {
+            app.instance_manager()
+                .duplicate_world(args.instance_id.into(), args.folder)
+                .await
+        }
+
+        mutation DELETE_WORLD[app, args: WorldRef] {
+            app.instance_manager()
+                .delete_world(args.instance_id.into(), args.folder)
+                .await
+        }
+
+        mutation IMPORT_WORLD[app, args: ImportWorld] {
+            app.instance_manager()
+                .import_world(args.instance_id.into(), PathBuf::from(args.path))
+                .await
+        }
+
+        query GET_WORLD_BACKUPS[app, args: GetWorldBackups] {
+            app.instance_manager()
+                .list_world_backups(args.instance_id.into(), args.folder)
+                .await
+                .map(|backups| backups.into_iter().map(FEWorldBackup::from).collect::<Vec<_>>())
+        }
+
+        mutation BACKUP_WORLD[app, args: WorldRef] {
+            app.instance_manager()
+                .backup_world(args.instance_id.into(), args.folder)
+                .await
+                .map(FETaskId::from)
+        }
+
+        mutation RESTORE_WORLD_BACKUP[app, args: WorldBackupRef] {
+            app.instance_manager()
+                .restore_world_backup(args.instance_id.into(), args.folder, args.backup_id)
+                .await
+                .map(FETaskId::from)
+        }
+
+        mutation DELETE_WORLD_BACKUP[app, args: WorldBackupRef] {
+            app.instance_manager()
+                .delete_world_backup(args.instance_id.into(), args.folder, args.backup_id)
+                .await
+        }
+
+        query GET_DATAPACKS[app, args: WorldRef] {
+            app.instance_manager()
+                .list_datapacks(args.instance_id.into(), args.folder)
+                .await
+                .map(|datapacks| datapacks.into_iter().map(FEDatapack::from).collect::<Vec<_>>())
+        }
+
+        mutation ADD_DATAPACK[app, args: AddDatapack] {
+            app.instance_manager()
+                .add_datapack(args.instance_id.into(), args.folder, PathBuf::from(args.path))
+                .await
+        }
+
+        mutation ENABLE_DATAPACK[app, args: DatapackRef] {
+            app.instance_manager()
+                .enable_datapack(args.instance_id.into(), args.folder, args.name, true)
+                .await
+        }
+
+        mutation DISABLE_DATAPACK[app, args: DatapackRef] {
+            app.instance_manager()
+                .enable_datapack(args.instance_id.into(), args.folder, args.name, false)
+                .await
+        }
+
+        mutation REMOVE_DATAPACK[app, args: DatapackRef] {
+            app.instance_manager()
+                .remove_datapack(args.instance_id.into(), args.folder, args.name)
+                .await
+        }
+
+        mutation INSTALL_DATAPACK[app, args: InstallDatapack] {
+            app.instance_manager()
+                .install_modrinth_datapack(
+                    args.instance_id.into(),
+                    args.folder,
+                    args.project_id,
+                    args.version_id,
+                )
+                .await
+                .map(FETaskId::from)
+        }
+
+        query GET_SCREENSHOTS[app, instance_id: Option<FEInstanceId>] {
+            app.instance_manager()
+                .list_screenshots(instance_id.map(Into::into))
+                .await
+                .map(|screenshots| screenshots.into_iter().map(FEScreenshot::from).collect::<Vec<_>>())
+        }
+
+        query GET_SCREENSHOT_PATH[app, args: ScreenshotRef] {
+            app.instance_manager()
+                .get_screenshot_path(args.instance_id.into(), args.file_name)
+                .await
+                .map(|path| path.to_string_lossy().to_string())
+        }
+
+        mutation DELETE_SCREENSHOT[app, args: ScreenshotRef] {
+            app.instance_manager()
+                .delete_screenshot(args.instance_id.into(), args.file_name)
+                .await
+        }
+
+        query GET_SNAPSHOTS[app, instance_id: FEInstanceId] {
+            app.instance_manager()
+                .list_snapshots(instance_id.into())
+                .await
+                .map(|snapshots| snapshots.into_iter().map(FESnapshot::from).collect::<Vec<_>>())
+        }
+
+        mutation CREATE_SNAPSHOT[app, instance_id: FEInstanceId] {
+            app.instance_manager()
+                .create_snapshot(instance_id.into())
+                .await
+                .map(FETaskId::from)
+        }
+
+        mutation RESTORE_SNAPSHOT[app, args: SnapshotRef] {
+            app.instance_manager()
+                .restore_snapshot(args.instance_id.into(), args.snapshot_id)
+                .await
+                .map(FETaskId::from)
+        }
+
+        mutation DELETE_SNAPSHOT[app, args: SnapshotRef] {
+            app.instance_manager()
+                .delete_snapshot(args.instance_id.into(), args.snapshot_id)
+                .await
+        }
+
+        query GET_SERVERS[app, instance_id: FEInstanceId] {
+            app.instance_manager()
+                .list_servers(instance_id.into())
+                .await
+                .map(|servers| servers.into_iter().map(FEServerEntry::from).collect::<Vec<_>>())
+        }
+
+        mutation ADD_SERVER[app, args: AddServer] {
+            app.instance_manager()
+                .add_server(args.instance_id.into(), args.server.into())
+                .await
+        }
+
+        mutation UPDATE_SERVER[app, args: UpdateServer] {
+            app.instance_manager()
+                .update_server(args.instance_id.into(), args.index as usize, args.server.into())
+                .await
+        }
+
+        mutation REMOVE_SERVER[app, args: RemoveServer] {
+            app.instance_manager()
+                .remove_server(args.instance_id.into(), args.index as usize)
+                .await
+        }
+
+        mutation MOVE_SERVER[app, args: MoveServer] {
+            app.instance_manager()
+                .move_server(args.instance_id.into(), args.from as usize, args.to as usize)
+                .await
+        }
+
+        query GET_SHARED_SERVERS[app, args: ()] {
+            app.instance_manager()
+                .get_shared_servers()
+                .await
+                .map(|servers| servers.into_iter().map(FEServerEntry::from).collect::<Vec<_>>())
+        }
+
+        mutation SET_SHARED_SERVERS[app, servers: Vec<FEServerEntry>] {
+            app.instance_manager()
+                .set_shared_servers(servers.into_iter().map(Into::into).collect())
+                .await
+        }
+
+        query GET_GAME_OPTIONS[app, instance_id: FEInstanceId] {
+            app.instance_manager()
+                .get_game_options(instance_id.into())
+                .await
+                .map(FEGameOptions::from)
+        }
+
+        mutation SET_GAME_OPTIONS[app, args: SetGameOptions] {
+            app.instance_manager()
+                .set_game_options(args.instance_id.into(), args.options.into())
+                .await
+        }
+
+        query GET_SHARED_GAME_OPTIONS[app, args: ()] {
+            app.instance_manager()
+                .get_shared_game_options()
+                .await
+                .map(FEGameOptions::from)
+        }
+
+        mutation SET_SHARED_GAME_OPTIONS[app, options: FEGameOptions] {
+            app.instance_manager()
+                .set_shared_game_options(options.into())
+                .await
+        }
+
+        query PREVIEW_CONFIG_SYNC[app, args: PreviewConfigSync] {
+            app.instance_manager()
+                .preview_config_sync(
+                    args.source.into(),
+                    args.targets.into_iter().map(Into::into).collect(),
+                    args.paths,
+                )
+                .await
+                .map(|changes| changes.into_iter().map(FEConfigSyncChange::from).collect::<Vec<_>>())
+        }
+
+        mutation SYNC_CONFIGS[app, args: SyncConfigs] {
+            app.instance_manager()
+                .sync_configs(
+                    args.source.into(),
+                    args.targets.into_iter().map(Into::into).collect(),
+                    args.paths,
+                    args.mode.into(),
+                )
+                .await
+                .map(|changes| changes.into_iter().map(FEConfigSyncChange::from).collect::<Vec<_>>())
+        }
+
+        query DIFF_INSTANCES[app, args: DiffInstances] {
+            app.instance_manager()
+                .diff_instances(args.base.into(), args.other.into())
+                .await
+                .map(FEInstanceDiff::from)
+        }
+
         mutation EXPORT[app, args: ExportArgs] {
             let task = app.instance_manager()
                 .export_manager()
@@ -449,6 +881,25 @@ pub(super) fn mount_axum_router() -> axum::Router<Arc<AppInner>> {
         platform: String,
     }
 
+    #[derive(Deserialize)]
+    struct PackIconQuery {
+        instance_id: i32,
+        pack_id: String,
+        platform: String,
+    }
+
+    #[derive(Deserialize)]
+    struct WorldIconQuery {
+        instance_id: i32,
+        folder: String,
+    }
+
+    #[derive(Deserialize)]
+    struct ScreenshotQuery {
+        instance_id: i32,
+        file_name: String,
+    }
+
     #[derive(Deserialize)]
     struct IconPathQuery {
         path: String,
@@ -511,6 +962,31 @@ pub(super) fn mount_axum_router() -> axum::Router<Arc<AppInner>> {
                 }
             )
         )
+        .route(
+            "/packIcon",
+            axum::routing::get(
+                |State(app): State<Arc<AppInner>>, Query(query): Query<PackIconQuery>| async move {
+                    let platformid = match &query.platform as &str {
+                        "metadata" => 0,
+                        "curseforge" => 1,
+                        "modrinth" => 2,
+                        _ => return Err(FeError::from_anyhow(&anyhow::anyhow!("unsupported platform")).make_axum()),
+                    };
+
+                    let icon = app.instance_manager()
+                        .get_pack_icon(domain::InstanceId(query.instance_id), query.pack_id, platformid)
+                        .await
+                        .map_err(|e| FeError::from_anyhow(&e).make_axum())?;
+
+                    let res = match icon {
+                        Some(icon) => (StatusCode::OK, icon),
+                        None => (StatusCode::NO_CONTENT, Vec::new()),
+                    };
+
+                    Ok(res)
+                }
+            )
+        )
         .route(
             "/modpackIcon",
             axum::routing::get(
@@ -548,6 +1024,46 @@ pub(super) fn mount_axum_router() -> axum::Router<Arc<AppInner>> {
                 }
             )
         )
+        .route(
+            "/worldIcon",
+            axum::routing::get(
+                |State(app): State<Arc<AppInner>>, Query(query): Query<WorldIconQuery>| async move {
+                    let icon = app.instance_manager()
+                        .get_world_icon(domain::InstanceId(query.instance_id), query.folder)
+                        .await
+                        .map_err(|e| FeError::from_anyhow(&e).make_axum())?;
+
+                    let res = match icon {
+                        Some(icon) => (StatusCode::OK, icon),
+                        None => (StatusCode::NO_CONTENT, Vec::new()),
+                    };
+
+                    Ok::<_, AxumError>(res)
+                }
+            )
+        )
+        .route(
+            "/screenshot",
+            axum::routing::get(
+                |State(app): State<Arc<AppInner>>, Query(query): Query<ScreenshotQuery>| async move {
+                    app.instance_manager()
+                        .get_screenshot(domain::InstanceId(query.instance_id), query.file_name)
+                        .await
+                        .map_err(|e| FeError::from_anyhow(&e).make_axum())
+                }
+            )
+        )
+        .route(
+            "/screenshotThumbnail",
+            axum::routing::get(
+                |State(app): State<Arc<AppInner>>, Query(query): Query<ScreenshotQuery>| async move {
+                    app.instance_manager()
+                        .get_screenshot_thumbnail(domain::InstanceId(query.instance_id), query.file_name)
+                        .await
+                        .map_err(|e| FeError::from_anyhow(&e).make_axum())
+                }
+            )
+        )
         .route("/log", axum::routing::get(log::log_handler))
 }
 
@@ -647,6 +1163,63 @@ struct CreateInstance {
     use_loaded_icon: bool,
     version: CreateInstanceVersion,
     notes: String,
+    #[specta(optional)]
+    #[serde(default)]
+    kind: Option<FEInstanceKind>,
+}
+
+#[derive(Type, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "value")]
+enum FEInstanceKind {
+    Client,
+    Server { eula_accepted: bool },
+}
+
+impl From<domain::info::InstanceKind> for FEInstanceKind {
+    fn from(value: domain::info::InstanceKind) -> Self {
+        match value {
+            domain::info::InstanceKind::Client => Self::Client,
+            domain::info::InstanceKind::Server { eula_accepted } => Self::Server { eula_accepted },
+        }
+    }
+}
+
+impl From<FEInstanceKind> for domain::info::InstanceKind {
+    fn from(value: FEInstanceKind) -> Self {
+        match value {
+            FEInstanceKind::Client => Self::Client,
+            FEInstanceKind::Server { eula_accepted } => Self::Server { eula_accepted },
+        }
+    }
+}
+
+#[derive(Type, Debug, Deserialize)]
+struct QuickPlayInstance {
+    instance_id: FEInstanceId,
+    quick_play: FEQuickPlay,
+}
+
+#[derive(Type, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum FEQuickPlay {
+    Singleplayer(String),
+    Multiplayer(String),
+}
+
+impl From<FEQuickPlay> for domain::QuickPlay {
+    fn from(value: FEQuickPlay) -> Self {
+        match value {
+            FEQuickPlay::Singleplayer(world) => Self::Singleplayer(world),
+            FEQuickPlay::Multiplayer(address) => Self::Multiplayer(address),
+        }
+    }
+}
+
+#[derive(Type, Debug, Deserialize)]
+struct SendInstanceCommand {
+    instance_id: FEInstanceId,
+    command: String,
 }
 
 #[derive(Type, Debug, Deserialize)]
@@ -713,6 +1286,14 @@ struct FEUpdateInstance {
     mod_sources: Option<Set<Option<super::modplatforms::ModSources>>>,
     #[specta(optional)]
     modpack_locked: Option<Set<Option<bool>>>,
+    #[specta(optional)]
+    kind: Option<Set<FEInstanceKind>>,
+    #[specta(optional)]
+    shared_servers: Option<Set<bool>>,
+    #[specta(optional)]
+    world_backups: Option<Set<Option<FEWorldBackups>>>,
+    #[specta(optional)]
+    shared_options: Option<Set<bool>>,
 }
 
 #[derive(Type, Debug, Deserialize)]
@@ -771,6 +1352,52 @@ struct UpdateMod {
     mod_id: String,
 }
 
+#[derive(Type, Debug, Deserialize)]
+struct InstancePack {
+    instance_id: FEInstanceId,
+    pack_id: String,
+}
+
+#[derive(Type, Debug, Deserialize)]
+struct InstallPack {
+    instance_id: FEInstanceId,
+    pack_source: ModSource,
+    replaces_pack: Option<String>,
+}
+
+impl InstallPack {
+    async fn install(
+        self,
+        app: &App,
+        pack_type: domain::PackType,
+    ) -> anyhow::Result<crate::domain::vtask::VisualTaskId> {
+        match self.pack_source {
+            ModSource::Curseforge(cf_mod) => {
+                app.instance_manager()
+                    .install_curseforge_pack(
+                        self.instance_id.into(),
+                        pack_type,
+                        cf_mod.project_id,
+                        cf_mod.file_id,
+                        self.replaces_pack,
+                    )
+                    .await
+            }
+            ModSource::Modrinth(mdr_mod) => {
+                app.instance_manager()
+                    .install_modrinth_pack(
+                        self.instance_id.into(),
+                        pack_type,
+                        mdr_mod.project_id,
+                        mdr_mod.version_id,
+                        self.replaces_pack,
+                    )
+                    .await
+            }
+        }
+    }
+}
+
 #[derive(Type, Debug, Deserialize)]
 struct InstallLatestMod {
     instance_id: FEInstanceId,
@@ -901,6 +1528,10 @@ struct InstanceDetails {
     post_exit_hook: Option<String>,
     wrapper_command: Option<String>,
     notes: String,
+    kind: FEInstanceKind,
+    shared_servers: bool,
+    world_backups: Option<FEWorldBackups>,
+    shared_options: bool,
     state: LaunchState,
     icon_revision: Option<u32>,
     has_pack_update: bool,
@@ -952,103 +1583,444 @@ enum InstanceFolder {
     ShaderPacks,
 }
 
-#[derive(Type, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
-struct ModLoader {
-    type_: FEInstanceModloaderType,
-    version: String,
+#[derive(Type, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+struct ModLoader {
+    type_: FEInstanceModloaderType,
+    version: String,
+}
+
+#[derive(Type, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+enum FEInstanceModloaderType {
+    Neoforge,
+    Forge,
+    Fabric,
+    Quilt,
+}
+
+#[derive(Type, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "state", content = "value")]
+enum LaunchState {
+    Inactive {
+        failed_task: Option<FETaskId>,
+    },
+    Preparing(FETaskId),
+    Running {
+        start_time: DateTime<Utc>,
+        log_id: i32,
+    },
+    Deleting,
+}
+
+#[derive(Type, Debug, Serialize)]
+struct Mod {
+    id: String,
+    filename: String,
+    enabled: bool,
+    metadata: Option<ModFileMetadata>,
+    curseforge: Option<CurseForgeModMetadata>,
+    modrinth: Option<ModrinthModMetadata>,
+    has_update: bool,
+}
+
+#[derive(Type, Debug, Serialize)]
+struct Pack {
+    id: String,
+    filename: String,
+    enabled: bool,
+    pack_format: Option<i32>,
+    metadata: Option<ModFileMetadata>,
+    curseforge: Option<CurseForgeModMetadata>,
+    modrinth: Option<ModrinthModMetadata>,
+}
+
+#[derive(Type, Debug, Serialize)]
+struct ModFileMetadata {
+    id: String,
+    modid: Option<String>,
+    name: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    authors: Option<String>,
+    modloaders: Vec<FEInstanceModloaderType>,
+    sha_1: String,
+    sha_512: String,
+    murmur_2: String,
+    has_image: bool,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct CurseForgeModMetadata {
+    project_id: u32,
+    file_id: u32,
+    name: String,
+    version: String,
+    urlslug: String,
+    summary: String,
+    authors: String,
+    has_image: bool,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct ModrinthModMetadata {
+    project_id: String,
+    version_id: String,
+    title: String,
+    version: String,
+    urlslug: String,
+    description: String,
+    authors: String,
+    has_image: bool,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct ExploreQuery {
+    instance_id: FEInstanceId,
+    path: Vec<String>,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct ExploreEntry {
+    name: String,
+    #[serde(rename = "type")]
+    type_: ExploreEntryType,
+}
+
+#[derive(Type, Serialize, Debug)]
+enum ExploreEntryType {
+    File { size: u32 },
+    Directory,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct FEWorld {
+    folder: String,
+    name: String,
+    game_mode: Option<FEGameMode>,
+    hardcore: bool,
+    difficulty: Option<FEDifficulty>,
+    last_played: Option<DateTime<Utc>>,
+    data_version: Option<i32>,
+    version_name: Option<String>,
+    has_icon: bool,
+}
+
+#[derive(Type, Serialize, Debug)]
+enum FEGameMode {
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+#[derive(Type, Serialize, Debug)]
+enum FEDifficulty {
+    Peaceful,
+    Easy,
+    Normal,
+    Hard,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct WorldRef {
+    instance_id: FEInstanceId,
+    folder: String,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct RenameWorld {
+    instance_id: FEInstanceId,
+    folder: String,
+    name: String,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct ImportWorld {
+    instance_id: FEInstanceId,
+    /// Path to a world zip
+    path: String,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct GetWorldBackups {
+    instance_id: FEInstanceId,
+    /// Only list backups of this world
+    folder: Option<String>,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct WorldBackupRef {
+    instance_id: FEInstanceId,
+    folder: String,
+    backup_id: String,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct DatapackRef {
+    instance_id: FEInstanceId,
+    /// World folder
+    folder: String,
+    name: String,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct AddDatapack {
+    instance_id: FEInstanceId,
+    /// World folder
+    folder: String,
+    /// Path to a datapack zip or folder
+    path: String,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct InstallDatapack {
+    instance_id: FEInstanceId,
+    /// World folder
+    folder: String,
+    project_id: String,
+    version_id: String,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct FEDatapack {
+    name: String,
+    enabled: bool,
+    is_folder: bool,
+    pack_format: Option<i32>,
+    description: Option<String>,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct FEWorldBackup {
+    /// World folder the backup was taken of
+    folder: String,
+    id: String,
+    created_at: DateTime<Utc>,
+    size: u64,
+}
+
+#[derive(Type, Serialize, Deserialize, Debug)]
+struct FEWorldBackups {
+    /// Minutes between scheduled backups
+    interval: Option<u32>,
+    after_exit: bool,
+    keep: u32,
+    max_age_days: Option<u32>,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct FEScreenshot {
+    instance_id: FEInstanceId,
+    file_name: String,
+    taken_at: DateTime<Utc>,
+    width: u32,
+    height: u32,
+    size: u64,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct ScreenshotRef {
+    instance_id: FEInstanceId,
+    file_name: String,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct FESnapshot {
+    id: String,
+    created_at: DateTime<Utc>,
+    reason: FESnapshotReason,
+    files: u32,
+    size: u64,
+}
+
+#[derive(Type, Serialize, Debug)]
+enum FESnapshotReason {
+    Manual,
+    VersionChange,
+    ModpackUpdate,
+    Restore,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct SnapshotRef {
+    instance_id: FEInstanceId,
+    snapshot_id: String,
+}
+
+#[derive(Type, Serialize, Deserialize, Debug)]
+struct FEServerEntry {
+    name: String,
+    ip: String,
+    /// Base64 encoded png
+    icon: Option<String>,
+    accept_textures: Option<bool>,
+}
+
+#[derive(Type, Serialize, Deserialize, Debug)]
+struct FEGameOptions {
+    /// Data version of the game that wrote the options
+    data_version: Option<i32>,
+    /// Bound keys by key mapping, like `key.jump` to `key.keyboard.space`
+    keybinds: BTreeMap<String, String>,
+    /// Volumes from 0 to 1 by sound category
+    volumes: BTreeMap<String, f32>,
+    video: FEVideoOptions,
+}
+
+#[derive(Type, Serialize, Deserialize, Debug)]
+struct FEVideoOptions {
+    render_distance: Option<i32>,
+    simulation_distance: Option<i32>,
+    max_fps: Option<i32>,
+    gui_scale: Option<i32>,
+    /// 0 to 1, mapping to a field of view of 70 to 110 degrees
+    fov: Option<f32>,
+    gamma: Option<f32>,
+    /// 0 for fast, 1 for fancy and 2 for fabulous
+    graphics_mode: Option<i32>,
+    fullscreen: Option<bool>,
+    vsync: Option<bool>,
+    entity_shadows: Option<bool>,
+    view_bobbing: Option<bool>,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct SetGameOptions {
+    instance_id: FEInstanceId,
+    options: FEGameOptions,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct PreviewConfigSync {
+    source: FEInstanceId,
+    targets: Vec<FEInstanceId>,
+    /// Files or folders relative to the data folder, like `config/jei`
+    paths: Vec<String>,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct SyncConfigs {
+    source: FEInstanceId,
+    targets: Vec<FEInstanceId>,
+    /// Files or folders relative to the data folder, like `config/jei`
+    paths: Vec<String>,
+    mode: FEConfigSyncMode,
+}
+
+#[derive(Type, Deserialize, Debug)]
+enum FEConfigSyncMode {
+    Copy,
+    /// Hard links the files, so later edits show up in every instance
+    Link,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct FEConfigSyncChange {
+    instance_id: FEInstanceId,
+    path: String,
+    kind: FEConfigSyncChangeKind,
+}
+
+#[derive(Type, Serialize, Debug)]
+enum FEConfigSyncChangeKind {
+    Create,
+    /// The replaced file is kept in the instance's config sync backups
+    Overwrite,
+    Unchanged,
 }
 
-#[derive(Type, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "camelCase")]
-enum FEInstanceModloaderType {
-    Neoforge,
-    Forge,
-    Fabric,
-    Quilt,
+#[derive(Type, Deserialize, Debug)]
+struct DiffInstances {
+    base: FEInstanceId,
+    other: FEInstanceId,
 }
 
-#[derive(Type, Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-#[serde(tag = "state", content = "value")]
-enum LaunchState {
-    Inactive {
-        failed_task: Option<FETaskId>,
-    },
-    Preparing(FETaskId),
-    Running {
-        start_time: DateTime<Utc>,
-        log_id: i32,
-    },
-    Deleting,
+#[derive(Type, Serialize, Debug)]
+struct FEInstanceDiff {
+    game_version: Option<FEValueDiff>,
+    modloaders: Vec<FEModLoaderDiff>,
+    mods: Vec<FEModDiff>,
+    config_files: Vec<FEConfigFileDiff>,
+    jvm: Vec<FEJvmSettingDiff>,
 }
 
-#[derive(Type, Debug, Serialize)]
-struct Mod {
-    id: String,
+/// The value in each instance, null where it is unset or missing
+#[derive(Type, Serialize, Debug)]
+struct FEValueDiff {
+    base: Option<String>,
+    other: Option<String>,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct FEModLoaderDiff {
+    loader: FEInstanceModloaderType,
+    version: FEValueDiff,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct FEModDiff {
+    /// Mod id, or the platform project or file name for mods without one
+    key: String,
+    base: Option<FEModDiffEntry>,
+    other: Option<FEModDiffEntry>,
+}
+
+#[derive(Type, Serialize, Debug)]
+struct FEModDiffEntry {
     filename: String,
     enabled: bool,
-    metadata: Option<ModFileMetadata>,
-    curseforge: Option<CurseForgeModMetadata>,
-    modrinth: Option<ModrinthModMetadata>,
-    has_update: bool,
+    version: Option<String>,
+    curseforge_project_id: Option<u32>,
+    curseforge_file_id: Option<u32>,
+    modrinth_project_id: Option<String>,
+    modrinth_version_id: Option<String>,
 }
 
-#[derive(Type, Debug, Serialize)]
-struct ModFileMetadata {
-    id: String,
-    modid: Option<String>,
-    name: Option<String>,
-    version: Option<String>,
-    description: Option<String>,
-    authors: Option<String>,
-    modloaders: Vec<FEInstanceModloaderType>,
-    sha_1: String,
-    sha_512: String,
-    murmur_2: String,
-    has_image: bool,
+#[derive(Type, Serialize, Debug)]
+struct FEConfigFileDiff {
+    path: String,
+    sha256: FEValueDiff,
 }
 
 #[derive(Type, Serialize, Debug)]
-struct CurseForgeModMetadata {
-    project_id: u32,
-    file_id: u32,
-    name: String,
-    version: String,
-    urlslug: String,
-    summary: String,
-    authors: String,
-    has_image: bool,
+struct FEJvmSettingDiff {
+    setting: FEJvmSetting,
+    value: FEValueDiff,
 }
 
 #[derive(Type, Serialize, Debug)]
-struct ModrinthModMetadata {
-    project_id: String,
-    version_id: String,
-    title: String,
-    version: String,
-    urlslug: String,
-    description: String,
-    authors: String,
-    has_image: bool,
+enum FEJvmSetting {
+    Memory,
+    GlobalJavaArgs,
+    ExtraJavaArgs,
+    JavaOverride,
 }
 
 #[derive(Type, Deserialize, Debug)]
-struct ExploreQuery {
+struct AddServer {
     instance_id: FEInstanceId,
-    path: Vec<String>,
+    server: FEServerEntry,
 }
 
-#[derive(Type, Serialize, Debug)]
-struct ExploreEntry {
-    name: String,
-    #[serde(rename = "type")]
-    type_: ExploreEntryType,
+#[derive(Type, Deserialize, Debug)]
+struct UpdateServer {
+    instance_id: FEInstanceId,
+    index: u32,
+    server: FEServerEntry,
 }
 
-#[derive(Type, Serialize, Debug)]
-enum ExploreEntryType {
-    File { size: u32 },
-    Directory,
+#[derive(Type, Deserialize, Debug)]
+struct RemoveServer {
+    instance_id: FEInstanceId,
+    index: u32,
+}
+
+#[derive(Type, Deserialize, Debug)]
+struct MoveServer {
+    instance_id: FEInstanceId,
+    from: u32,
+    to: u32,
 }
 
 #[derive(Type, Deserialize, Debug)]
@@ -1061,6 +2033,10 @@ struct ExportEntry {
 enum ExportTarget {
     Curseforge,
     Modrinth,
+    MultiMC,
+    Packwiz,
+    GDLInstance,
+    ServerPack,
 }
 
 #[derive(Type, Deserialize, Debug)]
@@ -1084,6 +2060,9 @@ pub enum ImportEntity {
     FTB,
     MultiMC,
     PrismLauncher,
+    MinecraftLauncher,
+    GDLInstance,
+    Packwiz,
 }
 
 #[derive(Type, Debug, Serialize)]
@@ -1163,6 +2142,10 @@ impl From<domain::InstanceDetails> for InstanceDetails {
             java_override: value.java_override.map(Into::into),
             required_java_profile: value.required_java_profile,
             notes: value.notes,
+            kind: value.kind.into(),
+            shared_servers: value.shared_servers,
+            world_backups: value.world_backups.map(Into::into),
+            shared_options: value.shared_options,
             state: value.state.into(),
             icon_revision: value.icon_revision,
             has_pack_update: value.has_pack_update,
@@ -1445,6 +2428,20 @@ impl From<domain::Mod> for Mod {
     }
 }
 
+impl From<domain::Pack> for Pack {
+    fn from(value: domain::Pack) -> Self {
+        Self {
+            id: value.id,
+            filename: value.filename,
+            enabled: value.enabled,
+            pack_format: value.pack_format,
+            metadata: value.metadata.map(Into::into),
+            curseforge: value.curseforge.map(Into::into),
+            modrinth: value.modrinth.map(Into::into),
+        }
+    }
+}
+
 impl From<domain::ModFileMetadata> for ModFileMetadata {
     fn from(value: domain::ModFileMetadata) -> Self {
         Self {
@@ -1571,6 +2568,10 @@ impl TryFrom<FEUpdateInstance> for domain::InstanceSettingsUpdate {
             game_resolution: value.game_resolution.map(|x| x.inner().map(Into::into)),
             mod_sources: value.mod_sources.map(|x| x.inner().map(Into::into)),
             modpack_locked: value.modpack_locked.map(|x| x.inner()),
+            kind: value.kind.map(|x| x.inner().into()),
+            shared_servers: value.shared_servers.map(|x| x.inner()),
+            world_backups: value.world_backups.map(|x| x.inner().map(Into::into)),
+            shared_options: value.shared_options.map(|x| x.inner()),
         })
     }
 }
@@ -1597,6 +2598,9 @@ impl From<ImportEntity> for importer::Entity {
             ImportEntity::FTB => Self::FTB,
             ImportEntity::MultiMC => Self::MultiMC,
             ImportEntity::PrismLauncher => Self::PrismLauncher,
+            ImportEntity::MinecraftLauncher => Self::MinecraftLauncher,
+            ImportEntity::GDLInstance => Self::GDLInstance,
+            ImportEntity::Packwiz => Self::Packwiz,
         }
     }
 }
@@ -1616,6 +2620,9 @@ impl From<importer::Entity> for ImportEntity {
             backend::FTB => Self::FTB,
             backend::MultiMC => Self::MultiMC,
             backend::PrismLauncher => Self::PrismLauncher,
+            backend::MinecraftLauncher => Self::MinecraftLauncher,
+            backend::GDLInstance => Self::GDLInstance,
+            backend::Packwiz => Self::Packwiz,
         }
     }
 }
@@ -1638,6 +2645,305 @@ impl From<importer::InvalidImportEntry> for InvalidImportEntry {
     }
 }
 
+impl From<domain::World> for FEWorld {
+    fn from(value: domain::World) -> Self {
+        Self {
+            folder: value.folder,
+            name: value.name,
+            game_mode: value.game_mode.map(Into::into),
+            hardcore: value.hardcore,
+            difficulty: value.difficulty.map(Into::into),
+            last_played: value.last_played,
+            data_version: value.data_version,
+            version_name: value.version_name,
+            has_icon: value.has_icon,
+        }
+    }
+}
+
+impl From<domain::GameMode> for FEGameMode {
+    fn from(value: domain::GameMode) -> Self {
+        match value {
+            domain::GameMode::Survival => Self::Survival,
+            domain::GameMode::Creative => Self::Creative,
+            domain::GameMode::Adventure => Self::Adventure,
+            domain::GameMode::Spectator => Self::Spectator,
+        }
+    }
+}
+
+impl From<domain::Difficulty> for FEDifficulty {
+    fn from(value: domain::Difficulty) -> Self {
+        match value {
+            domain::Difficulty::Peaceful => Self::Peaceful,
+            domain::Difficulty::Easy => Self::Easy,
+            domain::Difficulty::Normal => Self::Normal,
+            domain::Difficulty::Hard => Self::Hard,
+        }
+    }
+}
+
+impl From<domain::Datapack> for FEDatapack {
+    fn from(value: domain::Datapack) -> Self {
+        Self {
+            name: value.name,
+            enabled: value.enabled,
+            is_folder: value.is_folder,
+            pack_format: value.pack_format,
+            description: value.description,
+        }
+    }
+}
+
+impl From<domain::WorldBackup> for FEWorldBackup {
+    fn from(value: domain::WorldBackup) -> Self {
+        Self {
+            folder: value.world,
+            id: value.id,
+            created_at: value.created_at,
+            size: value.size,
+        }
+    }
+}
+
+impl From<domain::info::WorldBackups> for FEWorldBackups {
+    fn from(value: domain::info::WorldBackups) -> Self {
+        Self {
+            interval: value.interval,
+            after_exit: value.after_exit,
+            keep: value.keep,
+            max_age_days: value.max_age_days,
+        }
+    }
+}
+
+impl From<FEWorldBackups> for domain::info::WorldBackups {
+    fn from(value: FEWorldBackups) -> Self {
+        Self {
+            interval: value.interval,
+            after_exit: value.after_exit,
+            keep: value.keep,
+            max_age_days: value.max_age_days,
+        }
+    }
+}
+
+impl From<domain::Screenshot> for FEScreenshot {
+    fn from(value: domain::Screenshot) -> Self {
+        Self {
+            instance_id: value.instance_id.into(),
+            file_name: value.file_name,
+            taken_at: value.taken_at,
+            width: value.width,
+            height: value.height,
+            size: value.size,
+        }
+    }
+}
+
+impl From<domain::Snapshot> for FESnapshot {
+    fn from(value: domain::Snapshot) -> Self {
+        Self {
+            id: value.id,
+            created_at: value.created_at,
+            reason: value.reason.into(),
+            files: value.files,
+            size: value.size,
+        }
+    }
+}
+
+impl From<domain::SnapshotReason> for FESnapshotReason {
+    fn from(value: domain::SnapshotReason) -> Self {
+        match value {
+            domain::SnapshotReason::Manual => Self::Manual,
+            domain::SnapshotReason::VersionChange => Self::VersionChange,
+            domain::SnapshotReason::ModpackUpdate => Self::ModpackUpdate,
+            domain::SnapshotReason::Restore => Self::Restore,
+        }
+    }
+}
+
+impl From<domain::ServerEntry> for FEServerEntry {
+    fn from(value: domain::ServerEntry) -> Self {
+        Self {
+            name: value.name,
+            ip: value.ip,
+            icon: value.icon,
+            accept_textures: value.accept_textures,
+        }
+    }
+}
+
+impl From<FEServerEntry> for domain::ServerEntry {
+    fn from(value: FEServerEntry) -> Self {
+        Self {
+            name: value.name,
+            ip: value.ip,
+            icon: value.icon,
+            accept_textures: value.accept_textures,
+        }
+    }
+}
+
+impl From<domain::GameOptions> for FEGameOptions {
+    fn from(value: domain::GameOptions) -> Self {
+        Self {
+            data_version: value.data_version,
+            keybinds: value.keybinds,
+            volumes: value.volumes,
+            video: value.video.into(),
+        }
+    }
+}
+
+impl From<FEGameOptions> for domain::GameOptions {
+    fn from(value: FEGameOptions) -> Self {
+        Self {
+            data_version: value.data_version,
+            keybinds: value.keybinds,
+            volumes: value.volumes,
+            video: value.video.into(),
+        }
+    }
+}
+
+impl From<domain::VideoOptions> for FEVideoOptions {
+    fn from(value: domain::VideoOptions) -> Self {
+        Self {
+            render_distance: value.render_distance,
+            simulation_distance: value.simulation_distance,
+            max_fps: value.max_fps,
+            gui_scale: value.gui_scale,
+            fov: value.fov,
+            gamma: value.gamma,
+            graphics_mode: value.graphics_mode,
+            fullscreen: value.fullscreen,
+            vsync: value.vsync,
+            entity_shadows: value.entity_shadows,
+            view_bobbing: value.view_bobbing,
+        }
+    }
+}
+
+impl From<FEVideoOptions> for domain::VideoOptions {
+    fn from(value: FEVideoOptions) -> Self {
+        Self {
+            render_distance: value.render_distance,
+            simulation_distance: value.simulation_distance,
+            max_fps: value.max_fps,
+            gui_scale: value.gui_scale,
+            fov: value.fov,
+            gamma: value.gamma,
+            graphics_mode: value.graphics_mode,
+            fullscreen: value.fullscreen,
+            vsync: value.vsync,
+            entity_shadows: value.entity_shadows,
+            view_bobbing: value.view_bobbing,
+        }
+    }
+}
+
+impl From<FEConfigSyncMode> for domain::ConfigSyncMode {
+    fn from(value: FEConfigSyncMode) -> Self {
+        match value {
+            FEConfigSyncMode::Copy => Self::Copy,
+            FEConfigSyncMode::Link => Self::Link,
+        }
+    }
+}
+
+impl From<domain::ConfigSyncChange> for FEConfigSyncChange {
+    fn from(value: domain::ConfigSyncChange) -> Self {
+        Self {
+            instance_id: value.instance_id.into(),
+            path: value.path,
+            kind: match value.kind {
+                domain::ConfigSyncChangeKind::Create => FEConfigSyncChangeKind::Create,
+                domain::ConfigSyncChangeKind::Overwrite => FEConfigSyncChangeKind::Overwrite,
+                domain::ConfigSyncChangeKind::Unchanged => FEConfigSyncChangeKind::Unchanged,
+            },
+        }
+    }
+}
+
+impl From<domain::InstanceDiff> for FEInstanceDiff {
+    fn from(value: domain::InstanceDiff) -> Self {
+        Self {
+            game_version: value.game_version.map(Into::into),
+            modloaders: value.modloaders.into_iter().map(Into::into).collect(),
+            mods: value.mods.into_iter().map(Into::into).collect(),
+            config_files: value.config_files.into_iter().map(Into::into).collect(),
+            jvm: value.jvm.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<domain::ValueDiff> for FEValueDiff {
+    fn from(value: domain::ValueDiff) -> Self {
+        Self {
+            base: value.base,
+            other: value.other,
+        }
+    }
+}
+
+impl From<domain::ModLoaderDiff> for FEModLoaderDiff {
+    fn from(value: domain::ModLoaderDiff) -> Self {
+        Self {
+            loader: value.loader.into(),
+            version: value.version.into(),
+        }
+    }
+}
+
+impl From<domain::ModDiff> for FEModDiff {
+    fn from(value: domain::ModDiff) -> Self {
+        Self {
+            key: value.key,
+            base: value.base.map(Into::into),
+            other: value.other.map(Into::into),
+        }
+    }
+}
+
+impl From<domain::ModDiffEntry> for FEModDiffEntry {
+    fn from(value: domain::ModDiffEntry) -> Self {
+        Self {
+            filename: value.filename,
+            enabled: value.enabled,
+            version: value.version,
+            curseforge_project_id: value.curseforge_project_id,
+            curseforge_file_id: value.curseforge_file_id,
+            modrinth_project_id: value.modrinth_project_id,
+            modrinth_version_id: value.modrinth_version_id,
+        }
+    }
+}
+
+impl From<domain::ConfigFileDiff> for FEConfigFileDiff {
+    fn from(value: domain::ConfigFileDiff) -> Self {
+        Self {
+            path: value.path,
+            sha256: value.sha256.into(),
+        }
+    }
+}
+
+impl From<domain::JvmSettingDiff> for FEJvmSettingDiff {
+    fn from(value: domain::JvmSettingDiff) -> Self {
+        Self {
+            setting: match value.setting {
+                domain::JvmSetting::Memory => FEJvmSetting::Memory,
+                domain::JvmSetting::GlobalJavaArgs => FEJvmSetting::GlobalJavaArgs,
+                domain::JvmSetting::ExtraJavaArgs => FEJvmSetting::ExtraJavaArgs,
+                domain::JvmSetting::JavaOverride => FEJvmSetting::JavaOverride,
+            },
+            value: value.value.into(),
+        }
+    }
+}
+
 impl From<domain::ExploreEntryType> for ExploreEntryType {
     fn from(value: domain::ExploreEntryType) -> Self {
         match value {
@@ -1661,6 +2967,10 @@ impl From<ExportTarget> for domain::ExportTarget {
         match value {
             ExportTarget::Curseforge => Self::Curseforge,
             ExportTarget::Modrinth => Self::Modrinth,
+            ExportTarget::MultiMC => Self::MultiMC,
+            ExportTarget::Packwiz => Self::Packwiz,
+            ExportTarget::GDLInstance => Self::GDLInstance,
+            ExportTarget::ServerPack => Self::ServerPack,
         }
     }
 }
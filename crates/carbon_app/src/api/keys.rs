@@ -93,7 +93,9 @@ keys! {
         INSTANCE_MODS                               = "getInstanceMods";
         PREPARE_INSTANCE                            = "prepareInstance";
         LAUNCH_INSTANCE                             = "launchInstance";
+        QUICK_PLAY_INSTANCE                         = "quickPlayInstance";
         KILL_INSTANCE                               = "killInstance";
+        SEND_INSTANCE_COMMAND                       = "sendInstanceCommand";
         GET_LOGS                                    = "getLogs";
         DELETE_LOG                                  = "deleteLog";
         OPEN_INSTANCE_FOLDER                        = "openInstanceFolder";
@@ -105,6 +107,21 @@ keys! {
         UPDATE_MOD                                  = "updateMod";
         FIND_MOD_UPDATE                             = "findModUpdate";
         GET_MOD_SOURCES                             = "getModSources";
+        INSTANCE_RESOURCE_PACKS                     = "getInstanceResourcePacks";
+        ENABLE_RESOURCE_PACK                        = "enableResourcePack";
+        DISABLE_RESOURCE_PACK                       = "disableResourcePack";
+        DELETE_RESOURCE_PACK                        = "deleteResourcePack";
+        INSTALL_RESOURCE_PACK                       = "installResourcePack";
+        UPDATE_RESOURCE_PACK                        = "updateResourcePack";
+        FIND_RESOURCE_PACK_UPDATE                   = "findResourcePackUpdate";
+        INSTANCE_SHADER_PACKS                       = "getInstanceShaderPacks";
+        ENABLE_SHADER_PACK                          = "enableShaderPack";
+        DISABLE_SHADER_PACK                         = "disableShaderPack";
+        DELETE_SHADER_PACK                          = "deleteShaderPack";
+        INSTALL_SHADER_PACK                         = "installShaderPack";
+        UPDATE_SHADER_PACK                          = "updateShaderPack";
+        FIND_SHADER_PACK_UPDATE                     = "findShaderPackUpdate";
+        MISSING_SHADER_LOADER                       = "getMissingShaderLoader";
         GET_IMPORTABLE_ENTITIES                     = "getImportableEntities";
         GET_IMPORT_ENTITY_DEFAULT_PATH              = "getImportEntityDefaultPath";
         SET_IMPORT_SCAN_TARGET                      = "setImportScanTarget";
@@ -112,6 +129,42 @@ keys! {
         GET_IMPORT_SCAN_STATUS                      = "getImportScanStatus";
         IMPORT_INSTANCE                             = "importInstance";
         EXPLORE                                     = "explore";
+        GET_WORLDS                                  = "getWorlds";
+        RENAME_WORLD                                = "renameWorld";
+        DUPLICATE_WORLD                             = "duplicateWorld";
+        DELETE_WORLD                                = "deleteWorld";
+        IMPORT_WORLD                                = "importWorld";
+        GET_WORLD_BACKUPS                           = "getWorldBackups";
+        BACKUP_WORLD                                = "backupWorld";
+        RESTORE_WORLD_BACKUP                        = "restoreWorldBackup";
+        DELETE_WORLD_BACKUP                         = "deleteWorldBackup";
+        GET_DATAPACKS                               = "getDatapacks";
+        ADD_DATAPACK                                = "addDatapack";
+        ENABLE_DATAPACK                             = "enableDatapack";
+        DISABLE_DATAPACK                            = "disableDatapack";
+        REMOVE_DATAPACK                             = "removeDatapack";
+        INSTALL_DATAPACK                            = "installDatapack";
+        GET_SNAPSHOTS                               = "getSnapshots";
+        CREATE_SNAPSHOT                             = "createSnapshot";
+        RESTORE_SNAPSHOT                            = "restoreSnapshot";
+        DELETE_SNAPSHOT                             = "deleteSnapshot";
+        GET_SCREENSHOTS                             = "getScreenshots";
+        GET_SCREENSHOT_PATH                         = "getScreenshotPath";
+        DELETE_SCREENSHOT                           = "deleteScreenshot";
+        GET_SERVERS                                 = "getServers";
+        ADD_SERVER                                  = "addServer";
+        UPDATE_SERVER                               = "updateServer";
+        REMOVE_SERVER                               = "removeServer";
+        MOVE_SERVER                                 = "moveServer";
+        GET_SHARED_SERVERS                          = "getSharedServers";
+        SET_SHARED_SERVERS                          = "setSharedServers";
+        GET_GAME_OPTIONS                            = "getGameOptions";
+        SET_GAME_OPTIONS                            = "setGameOptions";
+        GET_SHARED_GAME_OPTIONS                     = "getSharedGameOptions";
+        SET_SHARED_GAME_OPTIONS                     = "setSharedGameOptions";
+        PREVIEW_CONFIG_SYNC                         = "previewConfigSync";
+        SYNC_CONFIGS                                = "syncConfigs";
+        DIFF_INSTANCES                              = "diffInstances";
         EXPORT                                      = "export";
         GET_MODPACK_INFO                            = "getModpackInfo";
     }
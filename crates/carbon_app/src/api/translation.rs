@@ -31,11 +31,38 @@ pub enum Translation {
     InstanceTaskReconstructAssets,
     InstanceTaskLaunchRunForgeProcessors,
     InstanceTaskLaunchRunNeoforgeProcessors,
+    InstanceTaskLaunchInstallServer,
     InstanceTaskInstallMod {
         mod_name: String,
         instance_name: String,
     },
     InstanceTaskInstallModDownloadFile,
+    InstanceTaskInstallPack {
+        pack_name: String,
+        instance_name: String,
+    },
+    InstanceTaskBackupWorlds {
+        name: String,
+    },
+    InstanceTaskBackupWorld {
+        world: String,
+    },
+    InstanceTaskRestoreWorld {
+        world: String,
+    },
+    InstanceTaskExtractBackup,
+    InstanceTaskInstallDatapack {
+        datapack_name: String,
+        world: String,
+    },
+    InstanceTaskCreateSnapshot {
+        name: String,
+    },
+    InstanceTaskRestoreSnapshot {
+        name: String,
+    },
+    InstanceTaskSnapshotStoreFiles,
+    InstanceTaskSnapshotRestoreFiles,
     FinalizingImport,
     InstanceImportLegacyBadConfigFile,
     InstanceImportCfZipMalformed,
@@ -45,9 +72,40 @@ pub enum Translation {
     InstanceImportMrpackMalformed,
     InstanceImportMrpackMissingManifest,
     InstanceImportMrpackMalformedManifest,
+    InstanceImportMmcBadConfigFile,
+    InstanceImportMmcBadPackFile,
+    InstanceImportMmcUnsupportedComponent {
+        uid: String,
+    },
+    InstanceImportAtlauncherBadConfigFile,
+    InstanceImportAtlauncherUnsupportedLoader {
+        loader: String,
+    },
+    InstanceImportTechnicMalformedModpackJar,
+    InstanceImportTechnicMalformedVersionJson,
+    InstanceImportTechnicUnsupportedJarmod,
+    InstanceImportFtbBadConfigFile,
+    InstanceImportFtbUnsupportedLoader {
+        loader: String,
+    },
+    InstanceImportModrinthBadProfile,
+    InstanceImportModrinthUnsupportedLoader {
+        loader: String,
+    },
+    InstanceImportMinecraftLauncherBadVersion {
+        version: String,
+    },
+    InstanceImportGdlinstanceMalformed,
+    InstanceImportGdlinstanceMissingConfig,
+    InstanceImportGdlinstanceMalformedConfig,
+    InstanceImportPackwizMalformedPack,
+    InstanceImportPackwizUnsupportedLoader {
+        loader: String,
+    },
     InstanceExport,
     InstanceExportScanningMods,
     InstanceExportCacheMods,
     InstanceExportCalculateSize,
     InstanceExportCreatingBundle,
+    InstanceExportDownloadServerFiles,
 }
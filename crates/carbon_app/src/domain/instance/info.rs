@@ -22,6 +22,33 @@ pub struct Instance {
     pub wrapper_command: Option<String>,
     pub mod_sources: Option<ModSources>,
     pub notes: String,
+    pub kind: InstanceKind,
+    /// Write the launcher's shared server list into `servers.dat` before launch
+    pub shared_servers: bool,
+    /// Automatic backups of the worlds in `saves`, disabled when unset
+    pub world_backups: Option<WorldBackups>,
+    /// Merge the launcher's shared options profile into `options.txt` before launch
+    pub shared_options: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldBackups {
+    /// Minutes between scheduled backups of worlds that changed
+    pub interval: Option<u32>,
+    pub after_exit: bool,
+    /// Backups kept per world
+    pub keep: u32,
+    /// Backups older than this are removed, except for the latest one
+    pub max_age_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstanceKind {
+    Client,
+    /// A dedicated server, launched with `nogui` and accepting console input
+    Server {
+        eula_accepted: bool,
+    },
 }
 
 #[derive(Debug, Clone)]
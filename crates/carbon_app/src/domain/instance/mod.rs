@@ -1,8 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+use crate::domain::runtime_path::InstancePath;
 use crate::domain::vtask::VisualTaskId;
 
 use super::modplatforms::ModSources;
@@ -48,6 +50,10 @@ pub struct InstanceDetails {
     pub required_java_profile: Option<String>,
     pub state: LaunchState,
     pub notes: String,
+    pub kind: info::InstanceKind,
+    pub shared_servers: bool,
+    pub world_backups: Option<info::WorldBackups>,
+    pub shared_options: bool,
     pub icon_revision: Option<u32>,
     pub has_pack_update: bool,
 }
@@ -78,6 +84,47 @@ pub struct InstanceSettingsUpdate {
     pub game_resolution: Option<Option<info::GameResolution>>,
     pub mod_sources: Option<Option<ModSources>>,
     pub modpack_locked: Option<Option<bool>>,
+    pub kind: Option<info::InstanceKind>,
+    pub shared_servers: Option<bool>,
+    pub world_backups: Option<Option<info::WorldBackups>>,
+    pub shared_options: Option<bool>,
+}
+
+impl InstanceSettingsUpdate {
+    /// An update for `instance_id` that leaves every setting unchanged.
+    pub fn new(instance_id: InstanceId) -> Self {
+        Self {
+            instance_id,
+            name: None,
+            use_loaded_icon: None,
+            notes: None,
+            version: None,
+            modloader: None,
+            java_override: None,
+            global_java_args: None,
+            extra_java_args: None,
+            memory: None,
+            pre_launch_hook: None,
+            post_exit_hook: None,
+            wrapper_command: None,
+            game_resolution: None,
+            mod_sources: None,
+            modpack_locked: None,
+            kind: None,
+            shared_servers: None,
+            world_backups: None,
+            shared_options: None,
+        }
+    }
+}
+
+/// A world or server to join as soon as the game has started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuickPlay {
+    /// Name of the world folder inside `saves`
+    Singleplayer(String),
+    /// Server address, optionally with a port
+    Multiplayer(String),
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -143,6 +190,58 @@ pub struct ModrinthModMetadata {
     pub has_image: bool,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PackType {
+    ResourcePack,
+    ShaderPack,
+}
+
+impl PackType {
+    pub const ALL: [Self; 2] = [Self::ResourcePack, Self::ShaderPack];
+
+    /// Folder of an instance the packs are loaded from.
+    pub fn path(self, instance_path: &InstancePath) -> PathBuf {
+        match self {
+            Self::ResourcePack => instance_path.get_resourcepacks_path(),
+            Self::ShaderPack => instance_path.get_shaderpacks_path(),
+        }
+    }
+}
+
+impl ToString for PackType {
+    fn to_string(&self) -> String {
+        match self {
+            Self::ResourcePack => "resourcepack",
+            Self::ShaderPack => "shaderpack",
+        }
+        .to_string()
+    }
+}
+
+impl TryFrom<&str> for PackType {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "resourcepack" => Ok(Self::ResourcePack),
+            "shaderpack" => Ok(Self::ShaderPack),
+            _ => anyhow::bail!("unknown pack type {s}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pack {
+    pub id: String,
+    pub pack_type: PackType,
+    pub filename: String,
+    pub enabled: bool,
+    pub pack_format: Option<i32>,
+    pub metadata: Option<ModFileMetadata>,
+    pub curseforge: Option<CurseForgeModMetadata>,
+    pub modrinth: Option<ModrinthModMetadata>,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum InstanceFolder {
     Root,
@@ -170,10 +269,220 @@ pub enum ExploreEntryType {
     Directory,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct World {
+    /// Folder name inside the saves directory
+    pub folder: String,
+    pub name: String,
+    pub game_mode: Option<GameMode>,
+    pub hardcore: bool,
+    pub difficulty: Option<Difficulty>,
+    pub last_played: Option<DateTime<Utc>>,
+    pub data_version: Option<i32>,
+    pub version_name: Option<String>,
+    pub has_icon: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Datapack {
+    /// File or folder name inside the world's datapacks folder
+    pub name: String,
+    pub enabled: bool,
+    pub is_folder: bool,
+    pub pack_format: Option<i32>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameMode {
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Difficulty {
+    Peaceful,
+    Easy,
+    Normal,
+    Hard,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldBackup {
+    /// Folder name of the world inside the saves directory
+    pub world: String,
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screenshot {
+    pub instance_id: InstanceId,
+    pub file_name: String,
+    pub taken_at: DateTime<Utc>,
+    pub width: u32,
+    pub height: u32,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub reason: SnapshotReason,
+    pub files: u32,
+    /// Size of the instance when the snapshot was taken, not of the stored data
+    pub size: u64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SnapshotReason {
+    Manual,
+    VersionChange,
+    ModpackUpdate,
+    /// State of the instance before another snapshot was restored
+    Restore,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerEntry {
+    pub name: String,
+    pub ip: String,
+    /// Base64 encoded png
+    pub icon: Option<String>,
+    /// Unset means the player is asked when joining
+    pub accept_textures: Option<bool>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConfigSyncMode {
+    Copy,
+    /// Hard links, so later edits show up in every instance until a program replaces the file
+    Link,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigSyncChange {
+    pub instance_id: InstanceId,
+    /// Path relative to the data folder, separated by forward slashes
+    pub path: String,
+    pub kind: ConfigSyncChangeKind,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConfigSyncChangeKind {
+    Create,
+    /// The existing file is backed up before it is replaced
+    Overwrite,
+    Unchanged,
+}
+
+/// Everything that differs between a base instance and another one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceDiff {
+    pub game_version: Option<ValueDiff>,
+    pub modloaders: Vec<ModLoaderDiff>,
+    pub mods: Vec<ModDiff>,
+    pub config_files: Vec<ConfigFileDiff>,
+    pub jvm: Vec<JvmSettingDiff>,
+}
+
+/// A value on each side, `None` where it is unset or missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueDiff {
+    pub base: Option<String>,
+    pub other: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModLoaderDiff {
+    pub loader: info::ModLoaderType,
+    pub version: ValueDiff,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModDiff {
+    /// Mod id, or the platform project or file name for mods without one
+    pub key: String,
+    pub base: Option<ModDiffEntry>,
+    pub other: Option<ModDiffEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModDiffEntry {
+    pub filename: String,
+    pub enabled: bool,
+    pub version: Option<String>,
+    pub curseforge_project_id: Option<u32>,
+    pub curseforge_file_id: Option<u32>,
+    pub modrinth_project_id: Option<String>,
+    pub modrinth_version_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigFileDiff {
+    /// Path relative to the data folder, separated by forward slashes
+    pub path: String,
+    /// Hex encoded sha256 of the file on each side
+    pub sha256: ValueDiff,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JvmSettingDiff {
+    pub setting: JvmSetting,
+    pub value: ValueDiff,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum JvmSetting {
+    Memory,
+    GlobalJavaArgs,
+    ExtraJavaArgs,
+    JavaOverride,
+}
+
+/// Settings read from or written to `options.txt`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameOptions {
+    /// Data version of the game that wrote the options
+    pub data_version: Option<i32>,
+    /// Bound keys by key mapping, like `key.jump` to `key.keyboard.space`
+    pub keybinds: BTreeMap<String, String>,
+    /// Volumes from 0 to 1 by sound category, like `master`
+    pub volumes: BTreeMap<String, f32>,
+    pub video: VideoOptions,
+}
+
+/// Video settings, unset when missing from the file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VideoOptions {
+    pub render_distance: Option<i32>,
+    pub simulation_distance: Option<i32>,
+    pub max_fps: Option<i32>,
+    pub gui_scale: Option<i32>,
+    /// 0 to 1, mapping to a field of view of 70 to 110 degrees
+    pub fov: Option<f32>,
+    /// Brightness from 0 to 1
+    pub gamma: Option<f32>,
+    /// 0 for fast, 1 for fancy and 2 for fabulous
+    pub graphics_mode: Option<i32>,
+    pub fullscreen: Option<bool>,
+    pub vsync: Option<bool>,
+    pub entity_shadows: Option<bool>,
+    pub view_bobbing: Option<bool>,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ExportTarget {
     Curseforge,
     Modrinth,
+    MultiMC,
+    Packwiz,
+    GDLInstance,
+    ServerPack,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -6,6 +6,7 @@ use serde::{de::Visitor, Deserialize, Serialize};
 
 pub mod curseforge;
 pub mod modrinth;
+pub mod packwiz;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(i32)]
@@ -68,6 +69,13 @@ impl ModPlatform {
     }
 }
 
+/// Platform file a mod is known to be installed from, like the records of another launcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModFileSource {
+    Curseforge { project_id: u32, file_id: u32 },
+    Modrinth { version_id: String },
+}
+
 impl FromStr for ModPlatform {
     type Err = anyhow::Error;
 
@@ -0,0 +1,100 @@
+//! Files of a [packwiz](https://packwiz.infra.link) modpack
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+pub const PACK_FORMAT: &str = "packwiz:1.1.0";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackToml {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_format: Option<String>,
+    pub index: PackIndexRef,
+    /// `minecraft` and loader versions, keyed by packwiz loader name
+    pub versions: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackIndexRef {
+    pub file: String,
+    pub hash_format: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexToml {
+    pub hash_format: String,
+    #[serde(default)]
+    pub files: Vec<IndexFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexFile {
+    pub file: String,
+    pub hash: String,
+    /// Overrides the index hash format for this file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub metafile: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub preserve: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModToml {
+    pub name: String,
+    pub filename: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub side: Option<String>,
+    pub download: ModDownload,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update: Option<ModUpdate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModDownload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub hash_format: String,
+    pub hash: String,
+    /// `metadata:curseforge` for files that must be resolved through the curseforge api
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curseforge: Option<CurseforgeUpdate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modrinth: Option<ModrinthUpdate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CurseforgeUpdate {
+    pub file_id: u32,
+    pub project_id: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModrinthUpdate {
+    pub mod_id: String,
+    pub version: String,
+}
@@ -117,6 +117,10 @@ impl InstancePath {
         self.0.join("instance")
     }
 
+    pub fn get_custom_version_path(&self) -> PathBuf {
+        self.0.join("custom_version.json")
+    }
+
     pub fn get_resources_path(&self) -> PathBuf {
         self.get_data_path().join("resources")
     }
@@ -165,6 +169,41 @@ impl InstancePath {
     }
 }
 
+pub struct BackupsPath(PathBuf);
+
+impl BackupsPath {
+    /// Holds a folder of backups for each world of the instance
+    pub fn get_world_backups_path(&self, instance_shortpath: &str) -> PathBuf {
+        self.0.join("worlds").join(instance_shortpath)
+    }
+
+    /// Holds the snapshot manifests of the instance
+    pub fn get_snapshots_path(&self, instance_shortpath: &str) -> PathBuf {
+        self.0
+            .join("snapshots")
+            .join("instances")
+            .join(instance_shortpath)
+    }
+
+    /// Holds a folder of replaced files for each config sync into the instance
+    pub fn get_config_sync_backups_path(&self, instance_shortpath: &str) -> PathBuf {
+        self.0.join("config_sync").join(instance_shortpath)
+    }
+
+    /// Content addressed store shared by the snapshots of every instance
+    pub fn get_snapshot_objects_path(&self) -> PathBuf {
+        self.0.join("snapshots").join("objects")
+    }
+}
+
+pub struct CachePath(PathBuf);
+
+impl CachePath {
+    pub fn get_thumbnails_path(&self) -> PathBuf {
+        self.0.join("thumbnails")
+    }
+}
+
 #[derive(Debug)]
 pub struct TempPath(PathBuf);
 
@@ -362,6 +401,14 @@ impl RuntimePath {
         LoggingConfigsPath(self.0.join("logging_configs"))
     }
 
+    pub fn get_backups(&self) -> BackupsPath {
+        BackupsPath(self.0.join("backups"))
+    }
+
+    pub fn get_cache(&self) -> CachePath {
+        CachePath(self.0.join("cache"))
+    }
+
     pub fn get_temp(&self) -> TempPath {
         TempPath(self.0.join("temp"))
     }
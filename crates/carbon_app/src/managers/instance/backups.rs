@@ -0,0 +1,663 @@
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::bail;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use tokio::task::JoinHandle;
+use tracing::error;
+use zip::{write::FileOptions, ZipWriter};
+
+use crate::api::keys::instance::{GET_WORLDS, GET_WORLD_BACKUPS};
+use crate::api::translation::Translation;
+use crate::domain::instance::info::{InstanceKind, WorldBackups};
+use crate::domain::instance::{InstanceId, WorldBackup};
+use crate::domain::vtask::VisualTaskId;
+use crate::managers::vtask::{Subtask, TaskState, VisualTask};
+use crate::managers::{App, ManagerRef};
+use crate::util::NormalizedWalkdir;
+
+use super::worlds::{check_file_name, extract_world_zip, world_path};
+use super::{InstanceManager, InvalidInstanceIdError};
+
+/// Backup ids are their creation time, which keeps them sortable by name.
+pub(super) const BACKUP_ID_FORMAT: &str = "%Y-%m-%d_%H-%M-%S%.3f";
+
+/// How often instances are checked for worlds due for a scheduled backup.
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+struct BackupTarget {
+    instance_id: InstanceId,
+    name: String,
+    saves_path: PathBuf,
+    backups_path: PathBuf,
+    policy: Option<WorldBackups>,
+    // server worlds live outside of `saves`
+    client: bool,
+}
+
+impl ManagerRef<'_, InstanceManager> {
+    async fn backup_target(self, instance_id: InstanceId) -> anyhow::Result<BackupTarget> {
+        let instances = self.instances.read().await;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or(InvalidInstanceIdError(instance_id))?;
+
+        let runtime_path = &self.app.settings_manager().runtime_path;
+        let config = &instance.data()?.config;
+
+        Ok(BackupTarget {
+            instance_id,
+            name: config.name.clone(),
+            saves_path: runtime_path
+                .get_instances()
+                .get_instance_path(&instance.shortpath)
+                .get_saves_path(),
+            backups_path: runtime_path
+                .get_backups()
+                .get_world_backups_path(&instance.shortpath),
+            policy: config.world_backups.clone(),
+            client: config.kind == InstanceKind::Client,
+        })
+    }
+
+    /// Lists the backups of one world, or of every world when unset, newest first.
+    pub async fn list_world_backups(
+        self,
+        instance_id: InstanceId,
+        world: Option<String>,
+    ) -> anyhow::Result<Vec<WorldBackup>> {
+        let target = self.backup_target(instance_id).await?;
+
+        let worlds = match world {
+            Some(world) => {
+                check_file_name(&world)?;
+                vec![world]
+            }
+            None => {
+                let mut worlds = Vec::new();
+
+                if target.backups_path.is_dir() {
+                    let mut dir = tokio::fs::read_dir(&target.backups_path).await?;
+                    while let Some(entry) = dir.next_entry().await? {
+                        if entry.file_type().await?.is_dir() {
+                            worlds.push(entry.file_name().to_string_lossy().to_string());
+                        }
+                    }
+                }
+
+                worlds
+            }
+        };
+
+        let mut backups = Vec::new();
+        for world in worlds {
+            backups.extend(read_backups(&target.backups_path, &world).await?);
+        }
+
+        backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        Ok(backups)
+    }
+
+    pub async fn backup_world(
+        self,
+        instance_id: InstanceId,
+        world: String,
+    ) -> anyhow::Result<VisualTaskId> {
+        let target = self.backup_target(instance_id).await?;
+        world_path(&target.saves_path, &world)?;
+
+        let (task_id, _) = self.start_world_backups(target, vec![world]).await;
+        Ok(task_id)
+    }
+
+    /// Replaces a world with one of its backups.
+    ///
+    /// The current state of the world is backed up first, so a restore can be undone.
+    pub async fn restore_world_backup(
+        self,
+        instance_id: InstanceId,
+        world: String,
+        backup_id: String,
+    ) -> anyhow::Result<VisualTaskId> {
+        // fails while the game is running
+        self.saves_path(instance_id, true).await?;
+
+        let target = self.backup_target(instance_id).await?;
+        check_file_name(&world)?;
+        check_file_name(&backup_id)?;
+
+        let backup_path = target
+            .backups_path
+            .join(&world)
+            .join(format!("{backup_id}.zip"));
+
+        if !backup_path.is_file() {
+            bail!("backup {backup_id} of world {world} does not exist");
+        }
+
+        let vtask = VisualTask::new(Translation::InstanceTaskRestoreWorld {
+            world: world.clone(),
+        });
+
+        let task_id = self.app.task_manager().spawn_task(&vtask).await;
+        let app = self.app.clone();
+
+        tokio::spawn(async move {
+            let instance_manager = app.instance_manager();
+            let world_path = target.saves_path.join(&world);
+
+            let try_result: anyhow::Result<_> = async {
+                let _lock = instance_manager.backup_lock.lock().await;
+
+                let t_backup = world_path.is_dir().then(|| {
+                    vtask.subtask(Translation::InstanceTaskBackupWorld {
+                        world: world.clone(),
+                    })
+                });
+                let t_extract = vtask.subtask(Translation::InstanceTaskExtractBackup);
+
+                vtask
+                    .edit(|data| data.state = TaskState::KnownProgress)
+                    .await;
+
+                if let Some(t_backup) = t_backup {
+                    create_backup(
+                        &app,
+                        &world_path,
+                        &target.backups_path.join(&world),
+                        t_backup,
+                    )
+                    .await?;
+                }
+
+                t_extract.start_opaque();
+
+                let tmpdir = app
+                    .settings_manager()
+                    .runtime_path
+                    .get_temp()
+                    .maketmpdir()
+                    .await?;
+
+                let extract_path = tmpdir.join("world");
+                let send_path = extract_path.clone();
+                tokio::task::spawn_blocking(move || extract_world_zip(&backup_path, &send_path))
+                    .await??;
+
+                // keep the current world until the backup is in place
+                let old_path = tmpdir.join("old");
+                if world_path.is_dir() {
+                    tokio::fs::rename(&world_path, &old_path).await?;
+                }
+
+                if let Err(e) = tokio::fs::rename(&extract_path, &world_path).await {
+                    if old_path.is_dir() {
+                        tokio::fs::rename(&old_path, &world_path).await?;
+                    }
+
+                    return Err(e.into());
+                }
+
+                t_extract.complete_opaque();
+
+                Ok(())
+            }
+            .await;
+
+            app.invalidate(GET_WORLDS, Some(instance_id.0.into()));
+            app.invalidate(GET_WORLD_BACKUPS, None);
+
+            if let Err(e) = try_result {
+                vtask.fail(e).await;
+            }
+        });
+
+        Ok(task_id)
+    }
+
+    pub async fn delete_world_backup(
+        self,
+        instance_id: InstanceId,
+        world: String,
+        backup_id: String,
+    ) -> anyhow::Result<()> {
+        let target = self.backup_target(instance_id).await?;
+        check_file_name(&world)?;
+        check_file_name(&backup_id)?;
+
+        let _lock = self.backup_lock.lock().await;
+
+        tokio::fs::remove_file(
+            target
+                .backups_path
+                .join(&world)
+                .join(format!("{backup_id}.zip")),
+        )
+        .await?;
+
+        self.app.invalidate(GET_WORLD_BACKUPS, None);
+        Ok(())
+    }
+
+    /// Backs up the worlds that changed while the game was running, if enabled for the instance.
+    pub(super) async fn backup_worlds_after_exit(
+        self,
+        instance_id: InstanceId,
+    ) -> anyhow::Result<()> {
+        let target = self.backup_target(instance_id).await?;
+
+        if !target.client
+            || !target
+                .policy
+                .as_ref()
+                .is_some_and(|policy| policy.after_exit)
+        {
+            return Ok(());
+        }
+
+        let worlds = changed_worlds(&target, chrono::Duration::zero()).await?;
+
+        if !worlds.is_empty() {
+            let (_, handle) = self.start_world_backups(target, worlds).await;
+            handle.await?;
+        }
+
+        Ok(())
+    }
+
+    /// Keeps world backups and snapshots with an instance whose folder was renamed.
+    pub(super) async fn move_backups(
+        self,
+        old_shortpath: &str,
+        new_shortpath: &str,
+    ) -> anyhow::Result<()> {
+        let backups = self.app.settings_manager().runtime_path.get_backups();
+
+        for (old_path, new_path) in [
+            (
+                backups.get_world_backups_path(old_shortpath),
+                backups.get_world_backups_path(new_shortpath),
+            ),
+            (
+                backups.get_snapshots_path(old_shortpath),
+                backups.get_snapshots_path(new_shortpath),
+            ),
+            (
+                backups.get_config_sync_backups_path(old_shortpath),
+                backups.get_config_sync_backups_path(new_shortpath),
+            ),
+        ] {
+            if old_path.exists() {
+                tokio::fs::rename(old_path, new_path).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Periodically backs up changed worlds of instances with a backup interval.
+    pub(super) fn launch_backup_scheduler(self) {
+        let app = self.app.clone();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(SCHEDULE_CHECK_INTERVAL).await;
+
+                let instance_manager = app.instance_manager();
+                let scheduled = instance_manager
+                    .instances
+                    .read()
+                    .await
+                    .iter()
+                    .filter_map(|(id, instance)| {
+                        let config = &instance.data().ok()?.config;
+
+                        match config.kind {
+                            InstanceKind::Client => config
+                                .world_backups
+                                .as_ref()
+                                .and_then(|policy| policy.interval)
+                                .map(|interval| (*id, interval)),
+                            InstanceKind::Server { .. } => None,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                for (instance_id, interval) in scheduled {
+                    if let Err(e) = instance_manager
+                        .run_scheduled_backup(instance_id, interval)
+                        .await
+                    {
+                        error!({ error = ?e }, "Scheduled world backup failed for instance {}", *instance_id);
+                    }
+                }
+            }
+        });
+    }
+
+    async fn run_scheduled_backup(
+        self,
+        instance_id: InstanceId,
+        interval: u32,
+    ) -> anyhow::Result<()> {
+        let target = self.backup_target(instance_id).await?;
+        let interval = chrono::Duration::minutes(interval as i64);
+
+        let worlds = changed_worlds(&target, interval).await?;
+
+        if !worlds.is_empty() {
+            let (_, handle) = self.start_world_backups(target, worlds).await;
+            handle.await?;
+        }
+
+        Ok(())
+    }
+
+    /// Backs up `worlds` in a visual task, then prunes old backups according to the instance's policy.
+    async fn start_world_backups(
+        self,
+        target: BackupTarget,
+        worlds: Vec<String>,
+    ) -> (VisualTaskId, JoinHandle<()>) {
+        let vtask = VisualTask::new(Translation::InstanceTaskBackupWorlds {
+            name: target.name.clone(),
+        });
+
+        let task_id = self.app.task_manager().spawn_task(&vtask).await;
+        let app = self.app.clone();
+
+        let handle = tokio::spawn(async move {
+            let instance_manager = app.instance_manager();
+
+            let try_result: anyhow::Result<_> = async {
+                let _lock = instance_manager.backup_lock.lock().await;
+
+                let subtasks = worlds
+                    .iter()
+                    .map(|world| {
+                        vtask.subtask(Translation::InstanceTaskBackupWorld {
+                            world: world.clone(),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                vtask
+                    .edit(|data| data.state = TaskState::KnownProgress)
+                    .await;
+
+                for (world, subtask) in worlds.iter().zip(subtasks) {
+                    let backup_dir = target.backups_path.join(world);
+
+                    create_backup(&app, &target.saves_path.join(world), &backup_dir, subtask)
+                        .await?;
+
+                    if let Some(policy) = &target.policy {
+                        let backups = read_backups(&target.backups_path, world).await?;
+
+                        for backup in backups_to_prune(&backups, policy, Utc::now()) {
+                            tokio::fs::remove_file(backup_dir.join(format!("{}.zip", backup.id)))
+                                .await?;
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+            .await;
+
+            app.invalidate(GET_WORLD_BACKUPS, None);
+
+            if let Err(e) = try_result {
+                vtask.fail(e).await;
+            }
+        });
+
+        (task_id, handle)
+    }
+}
+
+async fn create_backup(
+    app: &App,
+    world_path: &Path,
+    backup_dir: &Path,
+    subtask: Subtask,
+) -> anyhow::Result<()> {
+    tokio::fs::create_dir_all(backup_dir).await?;
+
+    let tmpfile = app
+        .settings_manager()
+        .runtime_path
+        .get_temp()
+        .maketmpfile()
+        .await?;
+
+    let zip_path = tmpfile.to_path_buf();
+    let world_path = world_path.to_path_buf();
+
+    let subtask = tokio::task::spawn_blocking(move || {
+        zip_world(&world_path, &zip_path, &subtask)?;
+        Ok::<_, anyhow::Error>(subtask)
+    })
+    .await??;
+
+    let id = Utc::now().format(BACKUP_ID_FORMAT).to_string();
+    tmpfile
+        .try_rename_or_move(backup_dir.join(format!("{id}.zip")))
+        .await?;
+
+    subtask.complete_items();
+
+    Ok(())
+}
+
+fn zip_world(world_path: &Path, zip_path: &Path, subtask: &Subtask) -> anyhow::Result<()> {
+    let mut files = Vec::new();
+    let mut walker = NormalizedWalkdir::new(world_path)?;
+
+    while let Some(entry) = walker.next()? {
+        let name = entry.relative_path.trim_start_matches('/');
+
+        // the lock belongs to the game, and is held while it is running
+        if !entry.is_dir && name != "session.lock" {
+            files.push((name.to_string(), entry.entry.path()));
+        }
+    }
+
+    let total = files.len() as u32;
+    let mut zip = ZipWriter::new(File::create(zip_path)?);
+    let options = FileOptions::default().large_file(true);
+
+    for (i, (name, path)) in files.into_iter().enumerate() {
+        zip.start_file(name, options)?;
+        io::copy(&mut File::open(path)?, &mut zip)?;
+        subtask.update_items(i as u32 + 1, total);
+    }
+
+    zip.finish()?;
+
+    Ok(())
+}
+
+/// Reads the backups of a single world, in no particular order.
+async fn read_backups(backups_path: &Path, world: &str) -> anyhow::Result<Vec<WorldBackup>> {
+    let world_backups_path = backups_path.join(world);
+
+    if !world_backups_path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    let mut dir = tokio::fs::read_dir(&world_backups_path).await?;
+
+    while let Some(entry) = dir.next_entry().await? {
+        let file_name = entry.file_name();
+        let Some(id) = file_name
+            .to_str()
+            .and_then(|name| name.strip_suffix(".zip"))
+        else {
+            continue;
+        };
+
+        let Ok(created_at) = NaiveDateTime::parse_from_str(id, BACKUP_ID_FORMAT) else {
+            continue;
+        };
+
+        backups.push(WorldBackup {
+            world: world.to_string(),
+            id: id.to_string(),
+            created_at: created_at.and_utc(),
+            size: entry.metadata().await?.len(),
+        });
+    }
+
+    Ok(backups)
+}
+
+/// Worlds in `saves` modified since their last backup, if that is at least `interval` old.
+async fn changed_worlds(
+    target: &BackupTarget,
+    interval: chrono::Duration,
+) -> anyhow::Result<Vec<String>> {
+    if !target.saves_path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let now = Utc::now();
+    let mut worlds = Vec::new();
+    let mut dir = tokio::fs::read_dir(&target.saves_path).await?;
+
+    while let Some(entry) = dir.next_entry().await? {
+        // the game rewrites level.dat on every save
+        let Ok(metadata) = tokio::fs::metadata(entry.path().join("level.dat")).await else {
+            continue;
+        };
+
+        let modified = DateTime::<Utc>::from(metadata.modified()?);
+        let world = entry.file_name().to_string_lossy().to_string();
+
+        let latest = read_backups(&target.backups_path, &world)
+            .await?
+            .into_iter()
+            .map(|backup| backup.created_at)
+            .max();
+
+        if backup_due(modified, latest, interval, now) {
+            worlds.push(world);
+        }
+    }
+
+    Ok(worlds)
+}
+
+fn backup_due(
+    modified: DateTime<Utc>,
+    latest_backup: Option<DateTime<Utc>>,
+    interval: chrono::Duration,
+    now: DateTime<Utc>,
+) -> bool {
+    match latest_backup {
+        Some(latest) => modified > latest && now - latest >= interval,
+        None => true,
+    }
+}
+
+/// Picks the backups removed by the retention policy, given backups sorted newest first.
+///
+/// The newest backup is always kept.
+fn backups_to_prune<'a>(
+    backups: &'a [WorldBackup],
+    policy: &WorldBackups,
+    now: DateTime<Utc>,
+) -> Vec<&'a WorldBackup> {
+    let keep = policy.keep.max(1) as usize;
+    let max_age = policy
+        .max_age_days
+        .map(|days| chrono::Duration::days(days as i64));
+
+    let mut sorted = backups.iter().collect::<Vec<_>>();
+    sorted.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    sorted
+        .into_iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(i, backup)| {
+            *i >= keep || max_age.is_some_and(|max_age| now - backup.created_at > max_age)
+        })
+        .map(|(_, backup)| backup)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use crate::domain::instance::info::WorldBackups;
+    use crate::domain::instance::WorldBackup;
+
+    use super::{backup_due, backups_to_prune};
+
+    #[test]
+    fn retention() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+
+        let backups = [0, 40, 1, 3, 2]
+            .into_iter()
+            .map(|days| WorldBackup {
+                world: String::from("world"),
+                id: days.to_string(),
+                created_at: now - Duration::days(days),
+                size: 0,
+            })
+            .collect::<Vec<_>>();
+
+        let prune = |keep, max_age_days| {
+            let policy = WorldBackups {
+                interval: None,
+                after_exit: true,
+                keep,
+                max_age_days,
+            };
+
+            backups_to_prune(&backups, &policy, now)
+                .into_iter()
+                .map(|backup| backup.id.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(prune(3, None), ["3", "40"]);
+        assert_eq!(prune(10, Some(30)), ["40"]);
+        assert_eq!(prune(10, Some(1)), ["2", "3", "40"]);
+        assert_eq!(prune(0, None), ["1", "2", "3", "40"]);
+
+        let old_backups = &backups[1..2];
+        assert_eq!(
+            backups_to_prune(
+                old_backups,
+                &WorldBackups {
+                    interval: None,
+                    after_exit: false,
+                    keep: 1,
+                    max_age_days: Some(1),
+                },
+                now
+            ),
+            Vec::<&WorldBackup>::new()
+        );
+
+        let hour = Duration::hours(1);
+        assert!(backup_due(now, None, hour, now));
+        assert!(!backup_due(now - hour, Some(now - hour), hour, now));
+        assert!(!backup_due(
+            now,
+            Some(now - Duration::minutes(30)),
+            hour,
+            now
+        ));
+        assert!(backup_due(now, Some(now - hour), hour, now));
+        assert!(!backup_due(now, Some(now), Duration::zero(), now));
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::bail;
+use chrono::Utc;
+
+use crate::api::keys::instance::GET_GAME_OPTIONS;
+use crate::domain::instance::{ConfigSyncChange, ConfigSyncChangeKind, ConfigSyncMode, InstanceId};
+use crate::managers::ManagerRef;
+use crate::util::NormalizedWalkdir;
+
+use super::backups::BACKUP_ID_FORMAT;
+use super::run::LaunchState;
+use super::{InstanceManager, InvalidInstanceIdError};
+
+struct SyncTarget {
+    instance_id: InstanceId,
+    data_path: PathBuf,
+    backups_path: PathBuf,
+}
+
+impl ManagerRef<'_, InstanceManager> {
+    /// Data folder of the source and every target, failing if a target is running.
+    async fn config_sync_instances(
+        self,
+        source: InstanceId,
+        targets: &[InstanceId],
+    ) -> anyhow::Result<(PathBuf, Vec<SyncTarget>)> {
+        let instances = self.instances.read().await;
+        let runtime_path = &self.app.settings_manager().runtime_path;
+
+        let source_instance = instances
+            .get(&source)
+            .ok_or(InvalidInstanceIdError(source))?;
+
+        let source_path = runtime_path
+            .get_instances()
+            .get_instance_path(&source_instance.shortpath)
+            .get_data_path();
+
+        let mut sync_targets = Vec::<SyncTarget>::new();
+
+        for &instance_id in targets {
+            if instance_id == source {
+                bail!("cannot sync configs of an instance into itself");
+            }
+
+            if sync_targets.iter().any(|t| t.instance_id == instance_id) {
+                continue;
+            }
+
+            let instance = instances
+                .get(&instance_id)
+                .ok_or(InvalidInstanceIdError(instance_id))?;
+
+            // the game and mods write their configs back on exit
+            if matches!(instance.data()?.state, LaunchState::Running(_)) {
+                bail!(
+                    "cannot sync configs into {} while it is running",
+                    instance.name
+                );
+            }
+
+            sync_targets.push(SyncTarget {
+                instance_id,
+                data_path: runtime_path
+                    .get_instances()
+                    .get_instance_path(&instance.shortpath)
+                    .get_data_path(),
+                backups_path: runtime_path
+                    .get_backups()
+                    .get_config_sync_backups_path(&instance.shortpath),
+            });
+        }
+
+        Ok((source_path, sync_targets))
+    }
+
+    /// Lists what syncing `paths` from `source` would do to every target, without changing anything.
+    pub async fn preview_config_sync(
+        self,
+        source: InstanceId,
+        targets: Vec<InstanceId>,
+        paths: Vec<String>,
+    ) -> anyhow::Result<Vec<ConfigSyncChange>> {
+        let (source_path, targets) = self.config_sync_instances(source, &targets).await?;
+
+        tokio::task::spawn_blocking(move || {
+            let files = collect_files(&source_path, &paths)?;
+
+            let mut changes = Vec::new();
+            for target in &targets {
+                changes.extend(plan_target(&source_path, target, &files)?);
+            }
+
+            anyhow::Ok(changes)
+        })
+        .await?
+    }
+
+    /// Copies or links `paths` of the source data folder into every target.
+    ///
+    /// Replaced files are moved into a backup folder of the target first.
+    pub async fn sync_configs(
+        self,
+        source: InstanceId,
+        targets: Vec<InstanceId>,
+        paths: Vec<String>,
+        mode: ConfigSyncMode,
+    ) -> anyhow::Result<Vec<ConfigSyncChange>> {
+        let (source_path, targets) = self.config_sync_instances(source, &targets).await?;
+
+        let changes = tokio::task::spawn_blocking(move || {
+            let files = collect_files(&source_path, &paths)?;
+            let backup_id = Utc::now().format(BACKUP_ID_FORMAT).to_string();
+
+            let mut changes = Vec::new();
+            for target in &targets {
+                let target_changes = plan_target(&source_path, target, &files)?;
+
+                apply_changes(
+                    &source_path,
+                    &target.data_path,
+                    &target.backups_path.join(&backup_id),
+                    &target_changes,
+                    mode,
+                )?;
+
+                changes.extend(target_changes);
+            }
+
+            anyhow::Ok(changes)
+        })
+        .await??;
+
+        // options.txt may be among the synced files
+        self.app.invalidate(GET_GAME_OPTIONS, None);
+
+        Ok(changes)
+    }
+}
+
+/// Expands `paths` into the files below them, relative to `source`.
+fn collect_files(source: &Path, paths: &[String]) -> anyhow::Result<BTreeSet<String>> {
+    let mut files = BTreeSet::new();
+
+    for path in paths {
+        let path = path.trim_end_matches('/');
+        check_relative_path(path)?;
+
+        let full_path = source.join(path);
+
+        if full_path.is_dir() {
+            let mut walker = NormalizedWalkdir::new(&full_path)?;
+
+            while let Some(entry) = walker.next()? {
+                if !entry.is_dir {
+                    files.insert(format!("{path}{}", entry.relative_path));
+                }
+            }
+        } else if full_path.is_file() {
+            files.insert(path.to_string());
+        } else {
+            bail!("'{path}' does not exist in the source instance");
+        }
+    }
+
+    Ok(files)
+}
+
+/// Only allows paths that stay inside the data folder.
+fn check_relative_path(path: &str) -> anyhow::Result<()> {
+    let components = Path::new(path).components().collect::<Vec<_>>();
+
+    if components.is_empty() || !components.iter().all(|c| matches!(c, Component::Normal(_))) {
+        bail!("'{path}' is not a path inside the instance");
+    }
+
+    Ok(())
+}
+
+fn plan_target(
+    source: &Path,
+    target: &SyncTarget,
+    files: &BTreeSet<String>,
+) -> anyhow::Result<Vec<ConfigSyncChange>> {
+    files
+        .iter()
+        .map(|path| {
+            let source_file = source.join(path);
+            let target_file = target.data_path.join(path);
+
+            let kind = if target_file.is_dir() {
+                bail!("'{path}' is a folder in the target instance");
+            } else if !target_file.exists() {
+                ConfigSyncChangeKind::Create
+            } else if same_content(&source_file, &target_file)? {
+                ConfigSyncChangeKind::Unchanged
+            } else {
+                ConfigSyncChangeKind::Overwrite
+            };
+
+            Ok(ConfigSyncChange {
+                instance_id: target.instance_id,
+                path: path.clone(),
+                kind,
+            })
+        })
+        .collect()
+}
+
+fn same_content(a: &Path, b: &Path) -> anyhow::Result<bool> {
+    if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+
+    Ok(fs::read(a)? == fs::read(b)?)
+}
+
+fn apply_changes(
+    source: &Path,
+    target: &Path,
+    backup: &Path,
+    changes: &[ConfigSyncChange],
+    mode: ConfigSyncMode,
+) -> anyhow::Result<()> {
+    for change in changes {
+        let source_file = source.join(&change.path);
+        let target_file = target.join(&change.path);
+
+        match change.kind {
+            ConfigSyncChangeKind::Unchanged => {
+                // an equal copy still has to become a link to follow later source changes
+                if mode == ConfigSyncMode::Copy
+                    || same_file::is_same_file(&source_file, &target_file)?
+                {
+                    continue;
+                }
+
+                fs::remove_file(&target_file)?;
+            }
+            ConfigSyncChangeKind::Create => {}
+            ConfigSyncChangeKind::Overwrite => {
+                let backup_file = backup.join(&change.path);
+                if let Some(parent) = backup_file.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
+                // a copy, since the old file may be a link shared with other instances
+                fs::copy(&target_file, backup_file)?;
+                fs::remove_file(&target_file)?;
+            }
+        }
+
+        if let Some(parent) = target_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        match mode {
+            ConfigSyncMode::Copy => {
+                fs::copy(source_file, target_file)?;
+            }
+            ConfigSyncMode::Link => fs::hard_link(source_file, target_file)?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+    use std::fs;
+    use std::path::Path;
+
+    use crate::domain::instance::{ConfigSyncChangeKind, ConfigSyncMode, InstanceId};
+
+    use super::{apply_changes, check_relative_path, collect_files, plan_target, SyncTarget};
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn sync_config_files() {
+        let dir = tempdir::TempDir::new("carbon_app_test").unwrap();
+        let source = dir.path().join("source");
+        let target = SyncTarget {
+            instance_id: InstanceId(1),
+            data_path: dir.path().join("target"),
+            backups_path: dir.path().join("backups"),
+        };
+
+        write(&source.join("config/same.toml"), "a");
+        write(&source.join("config/changed.toml"), "new");
+        write(&source.join("config/sub/created.json"), "{}");
+        write(&source.join("options.txt"), "fov:0.5");
+        write(&target.data_path.join("config/same.toml"), "a");
+        write(&target.data_path.join("config/changed.toml"), "old");
+
+        assert!(check_relative_path("../config").is_err());
+        assert!(check_relative_path("/config").is_err());
+        assert!(check_relative_path("").is_err());
+        assert!(collect_files(&source, &[String::from("missing")]).is_err());
+
+        let files = collect_files(
+            &source,
+            &[String::from("config/"), String::from("options.txt")],
+        )
+        .unwrap();
+        assert_eq!(
+            files,
+            BTreeSet::from(
+                [
+                    "config/changed.toml",
+                    "config/same.toml",
+                    "config/sub/created.json",
+                    "options.txt",
+                ]
+                .map(String::from)
+            )
+        );
+
+        let changes = plan_target(&source, &target, &files).unwrap();
+        let kinds = changes
+            .iter()
+            .map(|change| (&change.path as &str, change.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                ("config/changed.toml", ConfigSyncChangeKind::Overwrite),
+                ("config/same.toml", ConfigSyncChangeKind::Unchanged),
+                ("config/sub/created.json", ConfigSyncChangeKind::Create),
+                ("options.txt", ConfigSyncChangeKind::Create),
+            ]
+        );
+
+        apply_changes(
+            &source,
+            &target.data_path,
+            &target.backups_path,
+            &changes,
+            ConfigSyncMode::Copy,
+        )
+        .unwrap();
+
+        for file in &files {
+            assert_eq!(
+                fs::read(source.join(file)).unwrap(),
+                fs::read(target.data_path.join(file)).unwrap()
+            );
+        }
+
+        assert_eq!(
+            fs::read_to_string(target.backups_path.join("config/changed.toml")).unwrap(),
+            "old"
+        );
+        assert!(!target.backups_path.join("config/same.toml").exists());
+
+        let changes = plan_target(&source, &target, &files).unwrap();
+        assert!(changes
+            .iter()
+            .all(|change| change.kind == ConfigSyncChangeKind::Unchanged));
+    }
+
+    #[test]
+    fn link_config_files() {
+        let dir = tempdir::TempDir::new("carbon_app_test").unwrap();
+        let source = dir.path().join("source");
+        let target = SyncTarget {
+            instance_id: InstanceId(1),
+            data_path: dir.path().join("target"),
+            backups_path: dir.path().join("backups"),
+        };
+
+        write(&source.join("config/same.toml"), "a");
+        write(&source.join("config/changed.toml"), "new");
+        write(&source.join("options.txt"), "fov:0.5");
+        write(&target.data_path.join("config/same.toml"), "a");
+        write(&target.data_path.join("config/changed.toml"), "old");
+
+        let files = collect_files(
+            &source,
+            &[String::from("config"), String::from("options.txt")],
+        )
+        .unwrap();
+
+        let changes = plan_target(&source, &target, &files).unwrap();
+        apply_changes(
+            &source,
+            &target.data_path,
+            &target.backups_path,
+            &changes,
+            ConfigSyncMode::Link,
+        )
+        .unwrap();
+
+        // the equal copy is linked as well, without a backup
+        for file in &files {
+            assert!(
+                same_file::is_same_file(source.join(file), target.data_path.join(file)).unwrap()
+            );
+        }
+
+        assert_eq!(
+            fs::read_to_string(target.backups_path.join("config/changed.toml")).unwrap(),
+            "old"
+        );
+        assert!(!target.backups_path.join("config/same.toml").exists());
+
+        // linking again leaves existing links alone
+        let changes = plan_target(&source, &target, &files).unwrap();
+        apply_changes(
+            &source,
+            &target.data_path,
+            &target.backups_path,
+            &changes,
+            ConfigSyncMode::Link,
+        )
+        .unwrap();
+
+        fs::write(source.join("options.txt"), "fov:1.0").unwrap();
+        assert_eq!(
+            fs::read_to_string(target.data_path.join("options.txt")).unwrap(),
+            "fov:1.0"
+        );
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail};
+use carbon_net::{Checksum, Downloadable};
+use carbon_parsing::nbt::{self, Compound, List, Tag, TagKind};
+use tracing::warn;
+
+use crate::api::keys::instance::GET_DATAPACKS;
+use crate::api::translation::Translation;
+use crate::domain::instance::{Datapack, InstanceId};
+use crate::domain::modplatforms::modrinth::project::ProjectType;
+use crate::domain::modplatforms::modrinth::search::{ProjectID, VersionID};
+use crate::domain::runtime_path::copy_dir_filter;
+use crate::domain::vtask::VisualTaskId;
+use crate::managers::metadata::packs::{parse_pack_mcmeta, parse_pack_metadata};
+use crate::managers::vtask::{TaskState, VisualTask};
+use crate::managers::ManagerRef;
+
+use super::packs::download_with_progress;
+use super::worlds::{check_file_name, world_path};
+use super::InstanceManager;
+
+impl ManagerRef<'_, InstanceManager> {
+    pub async fn list_datapacks(
+        self,
+        instance_id: InstanceId,
+        world: String,
+    ) -> anyhow::Result<Vec<Datapack>> {
+        let saves_path = self.saves_path(instance_id, false).await?;
+        let world_path = world_path(&saves_path, &world)?;
+
+        tokio::task::spawn_blocking(move || read_datapacks(&world_path)).await?
+    }
+
+    /// Copies a datapack zip or folder into a world, returning its name.
+    pub async fn add_datapack(
+        self,
+        instance_id: InstanceId,
+        world: String,
+        path: PathBuf,
+    ) -> anyhow::Result<String> {
+        let saves_path = self.saves_path(instance_id, false).await?;
+        let datapacks_path = world_path(&saves_path, &world)?.join("datapacks");
+
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("invalid datapack path {path:?}"))?
+            .to_string();
+
+        check_file_name(&name)?;
+
+        let target = datapacks_path.join(&name);
+
+        if target.exists() {
+            bail!("datapack {name} already exists");
+        }
+
+        if path.is_dir() {
+            if !path.join("pack.mcmeta").is_file() {
+                bail!("{name} is not a datapack");
+            }
+
+            copy_dir_filter(&path, &target, |_| true).await?;
+        } else {
+            let source = path.clone();
+            let is_pack = name.ends_with(".zip")
+                && tokio::task::spawn_blocking(move || {
+                    parse_pack_metadata(&mut std::fs::File::open(source)?)
+                })
+                .await??
+                .is_some();
+
+            if !is_pack {
+                bail!("{name} is not a datapack");
+            }
+
+            tokio::fs::create_dir_all(&datapacks_path).await?;
+            tokio::fs::copy(&path, &target).await?;
+        }
+
+        self.app.invalidate(GET_DATAPACKS, None);
+        Ok(name)
+    }
+
+    /// Enables or disables a datapack through the world's level.dat, the same way the game does.
+    pub async fn enable_datapack(
+        self,
+        instance_id: InstanceId,
+        world: String,
+        name: String,
+        enabled: bool,
+    ) -> anyhow::Result<()> {
+        let saves_path = self.saves_path(instance_id, true).await?;
+        let world_path = world_path(&saves_path, &world)?;
+
+        check_file_name(&name)?;
+
+        if !world_path.join("datapacks").join(&name).exists() {
+            bail!("datapack {name} does not exist");
+        }
+
+        self.edit_level_data(&world_path, |data| {
+            set_datapack_enabled(data, &datapack_id(&name), enabled);
+            Ok(())
+        })
+        .await?;
+
+        self.app.invalidate(GET_DATAPACKS, None);
+        Ok(())
+    }
+
+    pub async fn remove_datapack(
+        self,
+        instance_id: InstanceId,
+        world: String,
+        name: String,
+    ) -> anyhow::Result<()> {
+        let saves_path = self.saves_path(instance_id, true).await?;
+        let world_path = world_path(&saves_path, &world)?;
+
+        check_file_name(&name)?;
+
+        let path = world_path.join("datapacks").join(&name);
+
+        if !path.exists() {
+            bail!("datapack {name} does not exist");
+        }
+
+        let should_go_to_trash = self
+            .app
+            .settings_manager()
+            .get_settings()
+            .await?
+            .deletion_through_recycle_bin;
+
+        tokio::task::spawn_blocking(move || {
+            if should_go_to_trash {
+                trash::delete(&path)?;
+            } else if path.is_dir() {
+                std::fs::remove_dir_all(&path)?;
+            } else {
+                std::fs::remove_file(&path)?;
+            }
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .await??;
+
+        self.app.invalidate(GET_DATAPACKS, None);
+        Ok(())
+    }
+
+    pub async fn install_modrinth_datapack(
+        self,
+        instance_id: InstanceId,
+        world: String,
+        project_id: String,
+        version_id: String,
+    ) -> anyhow::Result<VisualTaskId> {
+        let saves_path = self.saves_path(instance_id, false).await?;
+        let datapacks_path = world_path(&saves_path, &world)?.join("datapacks");
+
+        let modrinth = &self.app.modplatforms_manager().modrinth;
+        let project = modrinth.get_project(ProjectID(project_id.clone())).await?;
+        let version = modrinth.get_version(VersionID(version_id.clone())).await?;
+
+        if version.project_id != project.id {
+            bail!("modrinth version {version_id} does not belong to project {project_id}");
+        }
+
+        // most datapacks are listed as mods with a datapack loader
+        let is_datapack = project.project_type == ProjectType::DataPack
+            || version.loaders.iter().any(|loader| loader == "datapack");
+
+        if !is_datapack {
+            bail!("modrinth project {project_id} is not a datapack");
+        }
+
+        // versions may ship a mod jar next to the datapack zip
+        let file = version
+            .files
+            .iter()
+            .filter(|file| file.filename.ends_with(".zip"))
+            .reduce(|a, b| if b.primary { b } else { a })
+            .ok_or_else(|| {
+                anyhow!(
+                    "Modrinth project '{project_id}' version '{version_id}' does not have a datapack file"
+                )
+            })?;
+
+        check_file_name(&file.filename)?;
+
+        let path = datapacks_path.join(&file.filename);
+
+        if path.exists() {
+            bail!("datapack {} is already installed", file.filename);
+        }
+
+        let downloadable = Downloadable::new(&file.url, path)
+            .with_checksum(Some(Checksum::Sha1(file.hashes.sha1.clone())))
+            .with_size(file.size as u64);
+
+        let vtask = VisualTask::new(Translation::InstanceTaskInstallDatapack {
+            datapack_name: project.title,
+            world,
+        });
+
+        let t_download_file = vtask.subtask(Translation::InstanceTaskInstallModDownloadFile);
+
+        let task_id = self.app.task_manager().spawn_task(&vtask).await;
+        let app = self.app.clone();
+
+        tokio::spawn(async move {
+            let try_result: anyhow::Result<_> = async {
+                vtask
+                    .edit(|data| data.state = TaskState::KnownProgress)
+                    .await;
+
+                download_with_progress(&downloadable, t_download_file).await
+            }
+            .await;
+
+            app.invalidate(GET_DATAPACKS, None);
+
+            if let Err(e) = try_result {
+                vtask.fail(e).await;
+            }
+        });
+
+        Ok(task_id)
+    }
+}
+
+/// Id the game gives datapacks loaded from the world's datapacks folder.
+fn datapack_id(name: &str) -> String {
+    format!("file/{name}")
+}
+
+fn read_datapacks(world_path: &Path) -> anyhow::Result<Vec<Datapack>> {
+    let datapacks_path = world_path.join("datapacks");
+
+    if !datapacks_path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let disabled = match std::fs::read(world_path.join("level.dat")) {
+        Ok(level_dat) => disabled_datapacks(&level_dat).unwrap_or_else(|e| {
+            warn!({ error = ?e }, "Could not read datapack state of {world_path:?}");
+            HashSet::new()
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut datapacks = Vec::new();
+
+    for entry in std::fs::read_dir(&datapacks_path)? {
+        let entry = entry?;
+
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+
+        let is_folder = entry.file_type()?.is_dir();
+
+        // the game skips folders without pack.mcmeta and files that aren't zips
+        let meta = if is_folder {
+            let mcmeta_path = entry.path().join("pack.mcmeta");
+
+            if !mcmeta_path.is_file() {
+                continue;
+            }
+
+            std::fs::read(mcmeta_path)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| parse_pack_mcmeta(&content))
+                .map(Some)
+        } else if name.ends_with(".zip") {
+            std::fs::File::open(entry.path())
+                .map_err(anyhow::Error::from)
+                .and_then(|mut file| parse_pack_metadata(&mut file))
+        } else {
+            continue;
+        };
+
+        let meta = meta.unwrap_or_else(|e| {
+            warn!({ error = ?e }, "Could not read pack.mcmeta of datapack {:?}", entry.path());
+            None
+        });
+
+        datapacks.push(Datapack {
+            enabled: !disabled.contains(&datapack_id(&name)),
+            name,
+            is_folder,
+            pack_format: meta.as_ref().and_then(|meta| meta.pack_format),
+            description: meta.and_then(|meta| meta.description),
+        });
+    }
+
+    datapacks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(datapacks)
+}
+
+/// Ids of the datapacks disabled in a level.dat.
+fn disabled_datapacks(level_dat: &[u8]) -> anyhow::Result<HashSet<String>> {
+    let raw = nbt::decompress(level_dat)?;
+    let (_, (_, root)) = nbt::parse_nbt(&raw).map_err(|_| anyhow!("level.dat is not valid NBT"))?;
+
+    let disabled = root
+        .as_compound()
+        .and_then(|root| root.get("Data"))
+        .and_then(Tag::as_compound)
+        .and_then(|data| data.get("DataPacks"))
+        .and_then(Tag::as_compound)
+        .and_then(|datapacks| datapacks.get("Disabled"))
+        .and_then(Tag::as_list)
+        .map(|list| {
+            list.items
+                .iter()
+                .filter_map(Tag::as_str)
+                .map(ToString::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(disabled)
+}
+
+/// Moves a datapack id between the `Enabled` and `Disabled` lists of a level.dat `Data` compound.
+fn set_datapack_enabled(data: &mut Compound<'static>, id: &str, enabled: bool) {
+    let Tag::Compound(datapacks) = child_mut(data, "DataPacks", Tag::Compound(Compound::new()))
+    else {
+        unreachable!("child_mut returns a tag of the default's kind");
+    };
+
+    let (add_to, remove_from) = match enabled {
+        true => ("Enabled", "Disabled"),
+        false => ("Disabled", "Enabled"),
+    };
+
+    if let Some(Tag::List(list)) = datapacks.get_mut(remove_from) {
+        list.items.retain(|item| item.as_str() != Some(id));
+    }
+
+    let empty_list = Tag::List(List {
+        kind: TagKind::String,
+        items: Vec::new(),
+    });
+
+    let Tag::List(list) = child_mut(datapacks, add_to, empty_list) else {
+        unreachable!("child_mut returns a tag of the default's kind");
+    };
+
+    if !list.items.iter().any(|item| item.as_str() == Some(id)) {
+        list.kind = TagKind::String;
+        list.items.push(Tag::String(id.to_string().into()));
+    }
+}
+
+/// Gets a child tag, replacing it with `default` when it is missing or of another kind.
+fn child_mut<'a>(
+    compound: &'a mut Compound<'static>,
+    name: &'static str,
+    default: Tag<'static>,
+) -> &'a mut Tag<'static> {
+    if compound.get(name).map(Tag::kind) != Some(default.kind()) {
+        compound.insert(name, default);
+    }
+
+    compound
+        .get_mut(name)
+        .expect("the tag was inserted if missing")
+}
+
+#[cfg(test)]
+mod test {
+    use carbon_parsing::nbt::{self, Compound, Compression, Tag};
+
+    use super::{disabled_datapacks, set_datapack_enabled};
+
+    #[test]
+    fn toggle_datapacks() -> anyhow::Result<()> {
+        let mut data = Compound::new();
+        data.insert("LevelName", Tag::String("World".into()));
+
+        set_datapack_enabled(&mut data, "file/a.zip", false);
+        set_datapack_enabled(&mut data, "file/b", false);
+        set_datapack_enabled(&mut data, "file/b", true);
+
+        let mut root = Compound::new();
+        root.insert("Data", Tag::Compound(data));
+        let level_dat = nbt::to_bytes("", &Tag::Compound(root), Compression::Gzip)?;
+
+        let disabled = disabled_datapacks(&level_dat)?;
+        assert!(disabled.contains("file/a.zip"));
+        assert!(!disabled.contains("file/b"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,434 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::domain::instance::info::{GameConfig, GameVersion, JavaOverride};
+use crate::domain::instance::{
+    self as domain, ConfigFileDiff, InstanceDiff, InstanceId, JvmSetting, JvmSettingDiff, ModDiff,
+    ModDiffEntry, ModLoaderDiff, ValueDiff,
+};
+use crate::managers::ManagerRef;
+use crate::util::NormalizedWalkdir;
+
+use super::{InstanceManager, InvalidInstanceIdError};
+
+/// Folders of the data folder whose files are compared by content.
+const CONFIG_FOLDERS: &[&str] = &["config", "defaultconfigs"];
+
+impl ManagerRef<'_, InstanceManager> {
+    async fn diff_side(self, instance_id: InstanceId) -> anyhow::Result<(GameConfig, PathBuf)> {
+        let instances = self.instances.read().await;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or(InvalidInstanceIdError(instance_id))?;
+
+        let game_config = instance.data()?.config.game_configuration.clone();
+        let data_path = self
+            .app
+            .settings_manager()
+            .runtime_path
+            .get_instances()
+            .get_instance_path(&instance.shortpath)
+            .get_data_path();
+
+        Ok((game_config, data_path))
+    }
+
+    /// Compares `other` against `base`, listing only what differs between them.
+    pub async fn diff_instances(
+        self,
+        base: InstanceId,
+        other: InstanceId,
+    ) -> anyhow::Result<InstanceDiff> {
+        let (base_config, base_path) = self.diff_side(base).await?;
+        let (other_config, other_path) = self.diff_side(other).await?;
+
+        let base_mods = self.list_mods(base).await?;
+        let other_mods = self.list_mods(other).await?;
+
+        let (base_files, other_files) = tokio::task::spawn_blocking(move || {
+            anyhow::Ok((
+                hash_config_files(&base_path)?,
+                hash_config_files(&other_path)?,
+            ))
+        })
+        .await??;
+
+        Ok(InstanceDiff {
+            game_version: diff_value(game_version(&base_config), game_version(&other_config)),
+            modloaders: diff_modloaders(&base_config, &other_config),
+            mods: diff_mods(&base_mods, &other_mods),
+            config_files: diff_config_files(&base_files, &other_files),
+            jvm: diff_jvm(&base_config, &other_config),
+        })
+    }
+}
+
+fn diff_value(base: Option<String>, other: Option<String>) -> Option<ValueDiff> {
+    (base != other).then_some(ValueDiff { base, other })
+}
+
+fn game_version(config: &GameConfig) -> Option<String> {
+    match &config.version {
+        Some(GameVersion::Standard(version)) => Some(version.release.clone()),
+        Some(GameVersion::Custom(version)) => Some(version.clone()),
+        None => None,
+    }
+}
+
+fn modloader_versions(config: &GameConfig) -> BTreeMap<String, &domain::info::ModLoader> {
+    match &config.version {
+        Some(GameVersion::Standard(version)) => version
+            .modloaders
+            .iter()
+            .map(|loader| (loader.type_.to_string(), loader))
+            .collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+fn diff_modloaders(base: &GameConfig, other: &GameConfig) -> Vec<ModLoaderDiff> {
+    let base = modloader_versions(base);
+    let other = modloader_versions(other);
+
+    base.iter()
+        .chain(&other)
+        .map(|(key, loader)| (key, loader.type_))
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .filter_map(|(key, loader)| {
+            let version = diff_value(
+                base.get(key).map(|l| l.version.clone()),
+                other.get(key).map(|l| l.version.clone()),
+            )?;
+
+            Some(ModLoaderDiff { loader, version })
+        })
+        .collect()
+}
+
+/// Keys mods by mod id, falling back to platform projects and then file names.
+fn mod_entries(mods: &[domain::Mod]) -> BTreeMap<String, ModDiffEntry> {
+    let mut mods = mods.iter().collect::<Vec<_>>();
+    mods.sort_by(|a, b| a.filename.cmp(&b.filename));
+
+    let mut entries = BTreeMap::new();
+
+    for m in mods {
+        let key = m
+            .metadata
+            .as_ref()
+            .and_then(|meta| meta.modid.clone())
+            .or_else(|| {
+                m.curseforge
+                    .as_ref()
+                    .map(|cf| format!("curseforge:{}", cf.project_id))
+            })
+            .or_else(|| {
+                m.modrinth
+                    .as_ref()
+                    .map(|mr| format!("modrinth:{}", mr.project_id))
+            })
+            .unwrap_or_else(|| m.filename.clone());
+
+        // a mod installed twice shows up once per file
+        let key = match entries.contains_key(&key) {
+            true => m.filename.clone(),
+            false => key,
+        };
+
+        entries.insert(
+            key,
+            ModDiffEntry {
+                filename: m.filename.clone(),
+                enabled: m.enabled,
+                version: m.metadata.as_ref().and_then(|meta| meta.version.clone()),
+                curseforge_project_id: m.curseforge.as_ref().map(|cf| cf.project_id),
+                curseforge_file_id: m.curseforge.as_ref().map(|cf| cf.file_id),
+                modrinth_project_id: m.modrinth.as_ref().map(|mr| mr.project_id.clone()),
+                modrinth_version_id: m.modrinth.as_ref().map(|mr| mr.version_id.clone()),
+            },
+        );
+    }
+
+    entries
+}
+
+/// Whether two entries describe the same mod file, regardless of its name.
+fn same_mod(a: &ModDiffEntry, b: &ModDiffEntry) -> bool {
+    a.enabled == b.enabled
+        && a.version == b.version
+        && a.curseforge_project_id == b.curseforge_project_id
+        && a.curseforge_file_id == b.curseforge_file_id
+        && a.modrinth_project_id == b.modrinth_project_id
+        && a.modrinth_version_id == b.modrinth_version_id
+}
+
+fn diff_mods(base: &[domain::Mod], other: &[domain::Mod]) -> Vec<ModDiff> {
+    let mut base = mod_entries(base);
+    let mut other = mod_entries(other);
+
+    let keys = base
+        .keys()
+        .chain(other.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let base = base.remove(&key);
+            let other = other.remove(&key);
+
+            match (&base, &other) {
+                (Some(a), Some(b)) if same_mod(a, b) => None,
+                _ => Some(ModDiff { key, base, other }),
+            }
+        })
+        .collect()
+}
+
+/// Hex encoded sha256 of every file in the config folders, by path relative to `data_path`.
+fn hash_config_files(data_path: &Path) -> anyhow::Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+
+    for folder in CONFIG_FOLDERS {
+        let path = data_path.join(folder);
+        if !path.is_dir() {
+            continue;
+        }
+
+        let mut walker = NormalizedWalkdir::new(&path)?;
+
+        while let Some(entry) = walker.next()? {
+            if entry.is_dir {
+                continue;
+            }
+
+            let mut hasher = Sha256::new();
+            io::copy(&mut File::open(entry.entry.path())?, &mut hasher)?;
+
+            files.insert(
+                format!("{folder}{}", entry.relative_path),
+                hex::encode(hasher.finalize()),
+            );
+        }
+    }
+
+    Ok(files)
+}
+
+fn diff_config_files(
+    base: &BTreeMap<String, String>,
+    other: &BTreeMap<String, String>,
+) -> Vec<ConfigFileDiff> {
+    base.keys()
+        .chain(other.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|path| {
+            let sha256 = diff_value(base.get(path).cloned(), other.get(path).cloned())?;
+
+            Some(ConfigFileDiff {
+                path: path.clone(),
+                sha256,
+            })
+        })
+        .collect()
+}
+
+fn jvm_settings(config: &GameConfig) -> [(JvmSetting, Option<String>); 4] {
+    [
+        (
+            JvmSetting::Memory,
+            config
+                .memory
+                .map(|(min, max)| format!("-Xms{min}M -Xmx{max}M")),
+        ),
+        (
+            JvmSetting::GlobalJavaArgs,
+            Some(config.global_java_args.to_string()),
+        ),
+        (JvmSetting::ExtraJavaArgs, config.extra_java_args.clone()),
+        (
+            JvmSetting::JavaOverride,
+            config.java_override.as_ref().map(|java| match java {
+                JavaOverride::Profile(profile) => {
+                    format!("profile:{}", profile.as_deref().unwrap_or_default())
+                }
+                JavaOverride::Path(path) => {
+                    format!("path:{}", path.as_deref().unwrap_or_default())
+                }
+            }),
+        ),
+    ]
+}
+
+fn diff_jvm(base: &GameConfig, other: &GameConfig) -> Vec<JvmSettingDiff> {
+    jvm_settings(base)
+        .into_iter()
+        .zip(jvm_settings(other))
+        .filter_map(|((setting, base), (_, other))| {
+            Some(JvmSettingDiff {
+                setting,
+                value: diff_value(base, other)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, HashSet};
+
+    use crate::domain::instance::info::{
+        GameConfig, GameVersion, ModLoader, ModLoaderType, StandardVersion,
+    };
+    use crate::domain::instance::{
+        CurseForgeModMetadata, JvmSetting, Mod, ModFileMetadata, ModLoaderDiff, ValueDiff,
+    };
+
+    use super::{diff_config_files, diff_jvm, diff_modloaders, diff_mods};
+
+    fn game_config(loaders: &[(ModLoaderType, &str)], memory: Option<(u16, u16)>) -> GameConfig {
+        GameConfig {
+            version: Some(GameVersion::Standard(StandardVersion {
+                release: String::from("1.20.1"),
+                modloaders: loaders
+                    .iter()
+                    .map(|(type_, version)| ModLoader {
+                        type_: *type_,
+                        version: version.to_string(),
+                    })
+                    .collect::<HashSet<_>>(),
+            })),
+            global_java_args: true,
+            extra_java_args: None,
+            java_override: None,
+            memory,
+            game_resolution: None,
+        }
+    }
+
+    fn test_mod(filename: &str, modid: Option<&str>, version: &str, file_id: u32) -> Mod {
+        Mod {
+            id: filename.to_string(),
+            filename: filename.to_string(),
+            enabled: true,
+            metadata: Some(ModFileMetadata {
+                id: filename.to_string(),
+                modid: modid.map(String::from),
+                name: None,
+                version: Some(version.to_string()),
+                description: None,
+                authors: None,
+                modloaders: vec![ModLoaderType::Forge],
+                sha_512: Vec::new(),
+                sha_1: Vec::new(),
+                murmur_2: 0,
+                has_image: false,
+            }),
+            curseforge: Some(CurseForgeModMetadata {
+                project_id: 1000 + file_id,
+                file_id,
+                name: String::new(),
+                version: version.to_string(),
+                urlslug: String::new(),
+                summary: String::new(),
+                authors: String::new(),
+                has_image: false,
+            }),
+            modrinth: None,
+            has_update: false,
+        }
+    }
+
+    #[test]
+    fn diff_game_config() {
+        let base = game_config(&[(ModLoaderType::Forge, "47.2.0")], None);
+        let other = game_config(
+            &[
+                (ModLoaderType::Forge, "47.3.0"),
+                (ModLoaderType::Fabric, "0.15.0"),
+            ],
+            Some((1024, 4096)),
+        );
+
+        assert_eq!(
+            diff_modloaders(&base, &other),
+            [
+                ModLoaderDiff {
+                    loader: ModLoaderType::Fabric,
+                    version: ValueDiff {
+                        base: None,
+                        other: Some(String::from("0.15.0")),
+                    },
+                },
+                ModLoaderDiff {
+                    loader: ModLoaderType::Forge,
+                    version: ValueDiff {
+                        base: Some(String::from("47.2.0")),
+                        other: Some(String::from("47.3.0")),
+                    },
+                },
+            ]
+        );
+
+        let jvm = diff_jvm(&base, &other);
+        assert_eq!(jvm.len(), 1);
+        assert_eq!(jvm[0].setting, JvmSetting::Memory);
+        assert_eq!(jvm[0].value.other.as_deref(), Some("-Xms1024M -Xmx4096M"));
+
+        assert!(diff_modloaders(&base, &base).is_empty());
+        assert!(diff_jvm(&base, &base).is_empty());
+    }
+
+    #[test]
+    fn diff_mod_lists() {
+        let mut disabled = test_mod("b.jar", Some("b"), "1.0", 2);
+        disabled.enabled = false;
+
+        let base = [
+            test_mod("a-1.0.jar", Some("a"), "1.0", 1),
+            test_mod("b.jar", Some("b"), "1.0", 2),
+            test_mod("c.jar", Some("c"), "1.0", 3),
+            test_mod("unknown.jar", None, "1.0", 4),
+        ];
+        let other = [
+            // renamed files of the same mod are not a difference
+            test_mod("a_1.0.jar", Some("a"), "1.0", 1),
+            disabled,
+            test_mod("c.jar", Some("c"), "2.0", 5),
+            test_mod("d.jar", Some("d"), "1.0", 6),
+        ];
+
+        let diff = diff_mods(&base, &other);
+        let keys = diff.iter().map(|m| &m.key as &str).collect::<Vec<_>>();
+        assert_eq!(keys, ["b", "c", "curseforge:1004", "d"]);
+
+        assert!(!diff[0].other.as_ref().unwrap().enabled);
+        assert_eq!(diff[1].other.as_ref().unwrap().curseforge_file_id, Some(5));
+        assert!(diff[2].other.is_none());
+        assert!(diff[3].base.is_none());
+    }
+
+    #[test]
+    fn diff_configs() {
+        let base = BTreeMap::from([
+            (String::from("config/a.toml"), String::from("aa")),
+            (String::from("config/b.toml"), String::from("bb")),
+        ]);
+        let other = BTreeMap::from([
+            (String::from("config/a.toml"), String::from("aa")),
+            (String::from("config/b.toml"), String::from("cc")),
+            (String::from("defaultconfigs/c.toml"), String::from("dd")),
+        ]);
+
+        let diff = diff_config_files(&base, &other);
+        let paths = diff.iter().map(|f| &f.path as &str).collect::<Vec<_>>();
+        assert_eq!(paths, ["config/b.toml", "defaultconfigs/c.toml"]);
+        assert_eq!(diff[1].sha256.base, None);
+    }
+}
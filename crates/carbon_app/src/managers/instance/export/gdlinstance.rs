@@ -0,0 +1,363 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use tokio::sync::mpsc;
+
+use crate::{
+    api::translation::Translation,
+    domain::{
+        instance::{info::InstanceIcon, ExportEntry, InstanceId},
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::{schema, InstanceType, InvalidInstanceIdError},
+        vtask::{TaskState, VisualTask},
+        AppInner,
+    },
+};
+
+use super::ZipMode;
+
+/// Path of the instance config inside the archive.
+pub const CONFIG_FILE: &str = "instance.json";
+/// Prefix of the instance data folder inside the archive.
+pub const DATA_PREFIX: &str = "instance";
+
+// files in the instance root that are restored next to the config
+const ROOT_FILES: [&str; 2] = ["packinfo.json", "custom_version.json"];
+
+/// Exports an instance as a `.gdlinstance` archive.
+///
+/// The archive mirrors the instance folder: the full config, icon, packinfo and
+/// custom version file at the root and the data folder under `instance/`, so
+/// importing it restores the instance exactly. Addons are always bundled.
+pub async fn export_gdlinstance(
+    app: Arc<AppInner>,
+    instance_id: InstanceId,
+    save_path: PathBuf,
+    filter: ExportEntry,
+) -> anyhow::Result<VisualTaskId> {
+    let instance_manager = app.instance_manager();
+    let instances = instance_manager.instances.read().await;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or(InvalidInstanceIdError(instance_id))?;
+
+    let instance_path = app
+        .settings_manager()
+        .runtime_path
+        .get_instances()
+        .get_instance_path(&instance.shortpath);
+
+    let InstanceType::Valid(data) = &instance.type_ else {
+        return Err(anyhow!("Instance {instance_id} is not in a valid state"));
+    };
+
+    let config = data.config.clone();
+
+    drop(instances);
+
+    let root = instance_path.get_root();
+    let mut root_files = ROOT_FILES.map(String::from).to_vec();
+    if let InstanceIcon::RelativePath(icon) = &config.icon {
+        root_files.push(icon.clone());
+    }
+
+    let root_files = root_files
+        .into_iter()
+        .map(|name| (root.join(&name), name))
+        .filter(|(path, _)| path.is_file())
+        .collect::<Vec<_>>();
+
+    let config = schema::make_instance_config(config)?;
+    let basepath = instance_path.get_data_path();
+
+    let vtask = VisualTask::new(Translation::InstanceExport);
+    let vtask_id = app.task_manager().spawn_task(&vtask).await;
+
+    tokio::spawn(async move {
+        let try_result: anyhow::Result<_> = async {
+            let t_calc_size = vtask.subtask(Translation::InstanceExportCalculateSize);
+            t_calc_size.set_weight(0.0);
+            let t_create_bundle = vtask.subtask(Translation::InstanceExportCreatingBundle);
+
+            vtask
+                .edit(|data| data.state = TaskState::KnownProgress)
+                .await;
+
+            t_calc_size.start_opaque();
+
+            let mut file_count = 0;
+            super::zip_excluding(
+                ZipMode::<File>::Count(&mut file_count),
+                &basepath,
+                DATA_PREFIX,
+                &filter,
+            )?;
+
+            t_calc_size.complete_opaque();
+            t_create_bundle.update_items(0, file_count);
+
+            let tmpfile = app
+                .settings_manager()
+                .runtime_path
+                .get_temp()
+                .maketmpfile()
+                .await?;
+
+            let send_path = tmpfile.to_path_buf();
+            let (notify_tx, mut notify_rx) = mpsc::channel::<()>(1);
+
+            let ziptask = tokio::task::spawn_blocking(move || {
+                let mut zip = zip::ZipWriter::new(File::create(send_path)?);
+                let options = zip::write::FileOptions::default();
+                zip.start_file(CONFIG_FILE, options)?;
+                zip.write_all(config.as_bytes())?;
+
+                for (path, name) in root_files {
+                    zip.start_file(name, options)?;
+                    io::copy(&mut File::open(path)?, &mut zip)?;
+                }
+
+                super::zip_excluding(
+                    ZipMode::Create(&mut zip, options, notify_tx),
+                    &basepath,
+                    DATA_PREFIX,
+                    &filter,
+                )?;
+
+                zip.finish()?;
+                Ok::<_, anyhow::Error>(())
+            });
+
+            tokio::select! {
+                r = ziptask => r??,
+                _ = async {
+                    let mut counter = 0;
+
+                    loop {
+                        if notify_rx.recv().await.is_some() {
+                            counter += 1;
+                            t_create_bundle.update_items(counter, file_count);
+                        } else {
+                            futures::future::pending().await
+                        }
+                    }
+                } => {},
+            }
+
+            tmpfile.try_rename_or_move(save_path).await?;
+
+            t_create_bundle.complete_items();
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = try_result {
+            vtask.fail(e).await;
+        }
+    });
+
+    Ok(vtask_id)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{HashMap, HashSet},
+        fs::File,
+        io::Read,
+    };
+
+    use zip::ZipArchive;
+
+    use crate::{
+        domain::instance::{info, ExportEntry, ExportTarget, InstanceSettingsUpdate},
+        managers::instance::{
+            importer::{Entity, ImportEntry, ImportScanStatus},
+            schema, InstanceVersionSource,
+        },
+    };
+
+    // files of the data folder, relative to it
+    const DATA_FILES: [(&str, &[u8]); 3] = [
+        ("mods/test.jar.disabled", b"mod"),
+        ("config/test.toml", b"enabled = true\n"),
+        ("options.txt", b"version:2586\nfov:0.5\n"),
+    ];
+
+    #[tokio::test]
+    async fn export_keeps_config_and_data() -> anyhow::Result<()> {
+        let app = crate::setup_managers_for_test().await;
+
+        let default_group_id = app.instance_manager().get_default_group().await?;
+        let instance_id = app
+            .instance_manager()
+            .create_instance(
+                default_group_id,
+                String::from("test"),
+                false,
+                InstanceVersionSource::Version(info::GameVersion::Standard(
+                    info::StandardVersion {
+                        release: String::from("1.16.5"),
+                        modloaders: HashSet::new(),
+                    },
+                )),
+                String::from("some notes"),
+            )
+            .await?;
+
+        app.instance_manager()
+            .set_loaded_icon((String::from("icon.png"), b"png".to_vec()))
+            .await;
+
+        app.instance_manager()
+            .update_instance(InstanceSettingsUpdate {
+                use_loaded_icon: Some(true),
+                pre_launch_hook: Some(Some(String::from("echo pre"))),
+                post_exit_hook: Some(Some(String::from("echo post"))),
+                wrapper_command: Some(Some(String::from("gamemoderun"))),
+                java_override: Some(Some(info::JavaOverride::Path(Some(String::from(
+                    "/usr/bin/java",
+                ))))),
+                memory: Some(Some((1024, 4096))),
+                ..InstanceSettingsUpdate::new(instance_id)
+            })
+            .await?;
+
+        app.instance_manager()
+            .update_playtime(instance_id, 3600)
+            .await?;
+
+        let shortpath = app.instance_manager().instances.read().await[&instance_id]
+            .shortpath
+            .clone();
+
+        let instance_path = app
+            .settings_manager()
+            .runtime_path
+            .get_instances()
+            .get_instance_path(&shortpath);
+
+        for (path, content) in DATA_FILES {
+            let path = instance_path.get_data_path().join(path);
+            tokio::fs::create_dir_all(path.parent().unwrap()).await?;
+            tokio::fs::write(path, content).await?;
+        }
+
+        tokio::fs::write(
+            instance_path.get_root().join("packinfo.json"),
+            br#"{"files":{}}"#,
+        )
+        .await?;
+
+        let target_file = app
+            .settings_manager()
+            .runtime_path
+            .get_root()
+            .to_path()
+            .join("test.gdlinstance");
+
+        let task = app
+            .instance_manager()
+            .export_manager()
+            .export_instance(
+                instance_id,
+                ExportTarget::GDLInstance,
+                target_file.clone(),
+                false,
+                ExportEntry(HashMap::from([
+                    (String::from("mods"), None),
+                    (String::from("config"), None),
+                    (String::from("options.txt"), None),
+                ])),
+            )
+            .await?;
+
+        app.task_manager().wait_with_log(task).await?;
+
+        let original = app.instance_manager().instances.read().await[&instance_id]
+            .data()?
+            .config
+            .clone();
+        let expected = schema::make_instance_config(original.clone())?;
+
+        let archive = target_file.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut zip = ZipArchive::new(File::open(archive)?)?;
+
+            let mut config = String::new();
+            zip.by_name("instance.json")?.read_to_string(&mut config)?;
+            assert_eq!(config, expected);
+
+            let mut mod_file = String::new();
+            zip.by_name("instance/mods/test.jar.disabled")?
+                .read_to_string(&mut mod_file)?;
+            assert_eq!(mod_file, "mod");
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .await??;
+
+        let importer = Entity::GDLInstance.create_importer();
+        importer.scan(&app, target_file).await?;
+
+        assert!(matches!(
+            importer.get_status().await,
+            ImportScanStatus::SingleResult(ImportEntry::Valid(_))
+        ));
+
+        importer
+            .begin_import(&app, 0, Some(String::from("imported")))
+            .await?;
+
+        let (imported, imported_shortpath) = app
+            .instance_manager()
+            .instances
+            .read()
+            .await
+            .values()
+            .find_map(|instance| {
+                let config = &instance.data().ok()?.config;
+                (config.name == "imported").then(|| (config.clone(), instance.shortpath.clone()))
+            })
+            .unwrap();
+
+        // everything but the name survives the round trip
+        assert_eq!(
+            schema::make_instance_config(info::Instance {
+                name: original.name.clone(),
+                ..imported
+            })?,
+            schema::make_instance_config(original)?
+        );
+
+        let imported_path = app
+            .settings_manager()
+            .runtime_path
+            .get_instances()
+            .get_instance_path(&imported_shortpath);
+
+        for (path, content) in DATA_FILES {
+            assert_eq!(
+                tokio::fs::read(imported_path.get_data_path().join(path)).await?,
+                content
+            );
+        }
+
+        for root_file in ["icon.png", "packinfo.json"] {
+            assert_eq!(
+                tokio::fs::read(imported_path.get_root().join(root_file)).await?,
+                tokio::fs::read(instance_path.get_root().join(root_file)).await?
+            );
+        }
+
+        Ok(())
+    }
+}
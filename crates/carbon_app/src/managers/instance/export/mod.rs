@@ -10,14 +10,21 @@ use zip::{write::FileOptions, ZipWriter};
 
 use crate::{
     domain::{
-        instance::{ExportEntry, ExportTarget, InstanceId},
+        instance::{
+            info::{ModLoader, ModLoaderType},
+            ExportEntry, ExportTarget, InstanceId,
+        },
         vtask::VisualTaskId,
     },
     managers::{vtask::Subtask, ManagerRef},
 };
 
 mod curseforge_archive;
+pub mod gdlinstance;
 mod modrinth_archive;
+mod multimc_archive;
+mod packwiz;
+mod server_pack;
 
 #[derive(Debug)]
 pub struct InstanceExportManager {}
@@ -58,12 +65,36 @@ impl ManagerRef<'_, InstanceExportManager> {
                 )
                 .await
             }
+            ExportTarget::MultiMC => {
+                multimc_archive::export_multimc(self.app.clone(), instance_id, save_path, filter)
+                    .await
+            }
+            ExportTarget::Packwiz => {
+                packwiz::export_packwiz(
+                    self.app.clone(),
+                    instance_id,
+                    save_path,
+                    self_contained_addons_bundling,
+                    filter,
+                )
+                .await
+            }
+            ExportTarget::GDLInstance => {
+                gdlinstance::export_gdlinstance(self.app.clone(), instance_id, save_path, filter)
+                    .await
+            }
+            ExportTarget::ServerPack => {
+                server_pack::export_server_pack(self.app.clone(), instance_id, save_path, filter)
+                    .await
+            }
         }
     }
 }
 
 enum ZipMode<'a, W: io::Write + io::Seek> {
     Count(&'a mut u32),
+    /// Collects the archive path and source path of every file instead of writing it.
+    List(&'a mut Vec<(String, PathBuf)>),
     Create(&'a mut ZipWriter<W>, FileOptions, mpsc::Sender<()>),
 }
 
@@ -93,8 +124,11 @@ fn zip_excluding<W: io::Write + io::Seek>(
                 continue;
             };
 
-            let pathstr =
-                String::from(prefix) + "/" + &relpath.iter().chain([&*name].iter()).join("/");
+            let pathstr = relpath.iter().chain([&*name].iter()).join("/");
+            let pathstr = match prefix {
+                "" => pathstr,
+                prefix => format!("{prefix}/{pathstr}"),
+            };
 
             if entry.metadata()?.is_dir() {
                 let relpath = &[relpath, &[&*name][..]].concat()[..];
@@ -104,6 +138,9 @@ fn zip_excluding<W: io::Write + io::Seek>(
                     ZipMode::Count(counter) => {
                         **counter += 1;
                     }
+                    ZipMode::List(files) => {
+                        files.push((pathstr, entry.path()));
+                    }
                     ZipMode::Create(zip, options, notify) => {
                         zip.start_file(pathstr, *options)?;
                         io::copy(&mut File::open(entry.path())?, zip)?;
@@ -118,3 +155,22 @@ fn zip_excluding<W: io::Write + io::Seek>(
 
     walk_recursive(&mut mode, base_path, prefix, &[], Some(filter))
 }
+
+/// Strips the minecraft version from forge and neoforge loader ids, which are
+/// stored as `<mc>-<forge>` or, for legacy versions, `<mc>-<forge>-<mc>`.
+pub(crate) fn short_loader_version<'a>(release: &str, modloader: &'a ModLoader) -> &'a str {
+    let version = modloader.version.as_str();
+
+    match modloader.type_ {
+        ModLoaderType::Forge | ModLoaderType::Neoforge => {
+            let version = version
+                .strip_prefix(&format!("{release}-"))
+                .unwrap_or(version);
+
+            version
+                .strip_suffix(&format!("-{release}"))
+                .unwrap_or(version)
+        }
+        ModLoaderType::Fabric | ModLoaderType::Quilt => version,
+    }
+}
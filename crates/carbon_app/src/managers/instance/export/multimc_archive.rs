@@ -0,0 +1,436 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::{
+    api::translation::Translation,
+    domain::{
+        instance::{
+            info::{self, GameResolution, GameVersion, InstanceIcon, JavaOverride, ModLoaderType},
+            ExportEntry, InstanceId,
+        },
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::{InstanceType, InvalidInstanceIdError},
+        vtask::{TaskState, VisualTask},
+        AppInner,
+    },
+};
+
+use super::ZipMode;
+
+/// Exports an instance as a zip that MultiMC and Prism Launcher can import.
+///
+/// Addons are always bundled, as MultiMC has no way to reference platform files.
+pub async fn export_multimc(
+    app: Arc<AppInner>,
+    instance_id: InstanceId,
+    save_path: PathBuf,
+    filter: ExportEntry,
+) -> anyhow::Result<VisualTaskId> {
+    let instance_manager = app.instance_manager();
+    let instances = instance_manager.instances.read().await;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or(InvalidInstanceIdError(instance_id))?;
+
+    let instance_path = app
+        .settings_manager()
+        .runtime_path
+        .get_instances()
+        .get_instance_path(&instance.shortpath);
+
+    let InstanceType::Valid(data) = &instance.type_ else {
+        return Err(anyhow!("Instance {instance_id} is not in a valid state"));
+    };
+
+    let config = data.config.clone();
+
+    drop(instances);
+
+    let Some(version) = &config.game_configuration.version else {
+        return Err(anyhow!(
+            "Instance {instance_id}'s game version is not known so it cannot be exported"
+        ));
+    };
+
+    let GameVersion::Standard(version) = version else {
+        return Err(anyhow!(
+            "Instance {instance_id} has a custom game version file so it cannot be exported"
+        ));
+    };
+
+    let pack = serde_json::to_vec_pretty(&make_mmc_pack(version))?;
+
+    // multimc imports `<iconKey>.png` from the instance root into its icon store
+    let icon = match &config.icon {
+        InstanceIcon::RelativePath(icon) if icon.ends_with(".png") => {
+            let key = config
+                .name
+                .chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_lowercase();
+
+            match key.is_empty() {
+                true => None,
+                false => Some((instance_path.get_root().join(icon), key)),
+            }
+        }
+        _ => None,
+    };
+
+    let instance_cfg = make_instance_cfg(&config, icon.as_ref().map(|(_, key)| key.as_str()));
+    let basepath = instance_path.get_data_path();
+
+    let vtask = VisualTask::new(Translation::InstanceExport);
+    let vtask_id = app.task_manager().spawn_task(&vtask).await;
+
+    tokio::spawn(async move {
+        let try_result: anyhow::Result<_> = async {
+            let t_calc_size = vtask.subtask(Translation::InstanceExportCalculateSize);
+            t_calc_size.set_weight(0.0);
+            let t_create_bundle = vtask.subtask(Translation::InstanceExportCreatingBundle);
+
+            vtask
+                .edit(|data| data.state = TaskState::KnownProgress)
+                .await;
+
+            t_calc_size.start_opaque();
+
+            let mut file_count = 0;
+            super::zip_excluding(
+                ZipMode::<File>::Count(&mut file_count),
+                &basepath,
+                ".minecraft",
+                &filter,
+            )?;
+
+            t_calc_size.complete_opaque();
+            t_create_bundle.update_items(0, file_count);
+
+            let tmpfile = app
+                .settings_manager()
+                .runtime_path
+                .get_temp()
+                .maketmpfile()
+                .await?;
+
+            let send_path = tmpfile.to_path_buf();
+            let (notify_tx, mut notify_rx) = mpsc::channel::<()>(1);
+
+            let ziptask = tokio::task::spawn_blocking(move || {
+                let mut zip = zip::ZipWriter::new(File::create(send_path)?);
+                let options = zip::write::FileOptions::default();
+                zip.start_file("instance.cfg", options)?;
+                zip.write_all(instance_cfg.as_bytes())?;
+                zip.start_file("mmc-pack.json", options)?;
+                zip.write_all(&pack)?;
+
+                if let Some((icon_path, key)) = icon {
+                    zip.start_file(format!("{key}.png"), options)?;
+                    io::copy(&mut File::open(icon_path)?, &mut zip)?;
+                }
+
+                super::zip_excluding(
+                    ZipMode::Create(&mut zip, options, notify_tx),
+                    &basepath,
+                    ".minecraft",
+                    &filter,
+                )?;
+
+                zip.finish()?;
+                Ok::<_, anyhow::Error>(())
+            });
+
+            tokio::select! {
+                r = ziptask => r??,
+                _ = async {
+                    let mut counter = 0;
+
+                    loop {
+                        if notify_rx.recv().await.is_some() {
+                            counter += 1;
+                            t_create_bundle.update_items(counter, file_count);
+                        } else {
+                            futures::future::pending().await
+                        }
+                    }
+                } => {},
+            }
+
+            tmpfile.try_rename_or_move(save_path).await?;
+
+            t_create_bundle.complete_items();
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = try_result {
+            vtask.fail(e).await;
+        }
+    });
+
+    Ok(vtask_id)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MmcPack {
+    format_version: u32,
+    components: Vec<MmcPackComponent>,
+}
+
+#[derive(Serialize)]
+struct MmcPackComponent {
+    uid: &'static str,
+    version: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    important: bool,
+}
+
+/// Builds `mmc-pack.json`. Components implied by the ones listed here, such as
+/// lwjgl, are resolved by the launcher when the instance is first loaded.
+fn make_mmc_pack(version: &info::StandardVersion) -> MmcPack {
+    let release = &version.release;
+
+    let mut components = vec![MmcPackComponent {
+        uid: "net.minecraft",
+        version: release.clone(),
+        important: true,
+    }];
+
+    for modloader in &version.modloaders {
+        let uid = match modloader.type_ {
+            ModLoaderType::Forge => "net.minecraftforge",
+            ModLoaderType::Neoforge => "net.neoforged",
+            ModLoaderType::Fabric => "net.fabricmc.fabric-loader",
+            ModLoaderType::Quilt => "org.quiltmc.quilt-loader",
+        };
+
+        let loader_version = super::short_loader_version(release, modloader);
+
+        if matches!(
+            modloader.type_,
+            ModLoaderType::Fabric | ModLoaderType::Quilt
+        ) {
+            components.push(MmcPackComponent {
+                uid: "net.fabricmc.intermediary",
+                version: release.clone(),
+                important: false,
+            });
+        }
+
+        components.push(MmcPackComponent {
+            uid,
+            version: loader_version.to_string(),
+            important: false,
+        });
+    }
+
+    MmcPack {
+        format_version: 1,
+        components,
+    }
+}
+
+/// Builds `instance.cfg`, only setting override flags for settings that
+/// differ from the launcher defaults.
+fn make_instance_cfg(config: &info::Instance, icon_key: Option<&str>) -> String {
+    let game_config = &config.game_configuration;
+    let mut entries = vec![
+        ("InstanceType", String::from("OneSix")),
+        ("name", config.name.clone()),
+        ("iconKey", icon_key.unwrap_or("default").to_string()),
+        ("notes", config.notes.clone()),
+        ("totalTimePlayed", config.seconds_played.to_string()),
+    ];
+
+    if let Some(last_played) = config.last_played {
+        entries.push(("lastLaunchTime", last_played.timestamp_millis().to_string()));
+    }
+
+    if let Some(args) = &game_config.extra_java_args {
+        entries.push(("OverrideJavaArgs", String::from("true")));
+        entries.push(("JvmArgs", args.clone()));
+    }
+
+    if let Some((min, max)) = game_config.memory {
+        entries.push(("OverrideMemory", String::from("true")));
+        entries.push(("MinMemAlloc", min.to_string()));
+        entries.push(("MaxMemAlloc", max.to_string()));
+    }
+
+    if let Some(GameResolution::Standard(width, height) | GameResolution::Custom(width, height)) =
+        game_config.game_resolution
+    {
+        entries.push(("OverrideWindow", String::from("true")));
+        entries.push(("LaunchMaximized", String::from("false")));
+        entries.push(("MinecraftWinWidth", width.to_string()));
+        entries.push(("MinecraftWinHeight", height.to_string()));
+    }
+
+    if let Some(JavaOverride::Path(Some(path))) = &game_config.java_override {
+        entries.push(("OverrideJavaLocation", String::from("true")));
+        entries.push(("JavaPath", path.clone()));
+    }
+
+    if config.pre_launch_hook.is_some()
+        || config.post_exit_hook.is_some()
+        || config.wrapper_command.is_some()
+    {
+        entries.push(("OverrideCommands", String::from("true")));
+        entries.push((
+            "PreLaunchCommand",
+            config.pre_launch_hook.clone().unwrap_or_default(),
+        ));
+        entries.push((
+            "PostExitCommand",
+            config.post_exit_hook.clone().unwrap_or_default(),
+        ));
+        entries.push((
+            "WrapperCommand",
+            config.wrapper_command.clone().unwrap_or_default(),
+        ));
+    }
+
+    if let Some(modpack) = &config.modpack {
+        let (type_, id, version_id) = match &modpack.modpack {
+            info::Modpack::Curseforge(cf) => {
+                ("flame", cf.project_id.to_string(), cf.file_id.to_string())
+            }
+            info::Modpack::Modrinth(mr) => {
+                ("modrinth", mr.project_id.clone(), mr.version_id.clone())
+            }
+        };
+
+        entries.push(("ManagedPack", String::from("true")));
+        entries.push(("ManagedPackType", type_.to_string()));
+        entries.push(("ManagedPackID", id));
+        entries.push(("ManagedPackVersionID", version_id));
+    }
+
+    let mut cfg = String::from("[General]\nConfigVersion=1.2\n");
+    for (key, value) in entries {
+        cfg.push_str(key);
+        cfg.push('=');
+        cfg.push_str(&quote_cfg_value(&value));
+        cfg.push('\n');
+    }
+
+    cfg
+}
+
+/// Quotes a value the way QSettings does for strings that would otherwise be
+/// read back as a list or lose whitespace.
+fn quote_cfg_value(value: &str) -> String {
+    let needs_quotes =
+        value.trim() != value || value.contains(['"', '\\', '\n', '\r', '\t', ',', ';', '=', '#']);
+
+    if !needs_quotes {
+        return value.to_string();
+    }
+
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::domain::instance::info::{ModLoader, ModLoaderType, StandardVersion};
+
+    use super::{make_mmc_pack, quote_cfg_value};
+
+    #[test]
+    fn quote_values() {
+        assert_eq!(quote_cfg_value("My Pack"), "My Pack");
+        assert_eq!(
+            quote_cfg_value("first line\nsecond \"quoted\" line"),
+            r#""first line\nsecond \"quoted\" line""#
+        );
+        assert_eq!(
+            quote_cfg_value("-XX:+UseG1GC -Dfoo=bar"),
+            r#""-XX:+UseG1GC -Dfoo=bar""#
+        );
+    }
+
+    #[test]
+    fn forge_pack_components() {
+        let pack = make_mmc_pack(&StandardVersion {
+            release: String::from("1.7.10"),
+            modloaders: HashSet::from([ModLoader {
+                type_: ModLoaderType::Forge,
+                version: String::from("1.7.10-10.13.4.1614-1.7.10"),
+            }]),
+        });
+
+        assert_eq!(
+            serde_json::to_string_pretty(&pack).unwrap(),
+            r#"{
+  "formatVersion": 1,
+  "components": [
+    {
+      "uid": "net.minecraft",
+      "version": "1.7.10",
+      "important": true
+    },
+    {
+      "uid": "net.minecraftforge",
+      "version": "10.13.4.1614"
+    }
+  ]
+}"#
+        );
+    }
+
+    #[test]
+    fn fabric_pack_components() {
+        let pack = make_mmc_pack(&StandardVersion {
+            release: String::from("1.20.1"),
+            modloaders: HashSet::from([ModLoader {
+                type_: ModLoaderType::Fabric,
+                version: String::from("0.14.21"),
+            }]),
+        });
+
+        let uids = pack
+            .components
+            .iter()
+            .map(|c| (c.uid, &*c.version))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            uids,
+            [
+                ("net.minecraft", "1.20.1"),
+                ("net.fabricmc.intermediary", "1.20.1"),
+                ("net.fabricmc.fabric-loader", "0.14.21"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,483 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::File,
+    io::{Read, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+
+use crate::{
+    api::translation::Translation,
+    domain::{
+        instance::{
+            info::{GameVersion, ModLoaderType, StandardVersion},
+            ExportEntry, InstanceId,
+        },
+        modplatforms::packwiz::{
+            CurseforgeUpdate, IndexFile, IndexToml, ModDownload, ModToml, ModUpdate,
+            ModrinthUpdate, PackIndexRef, PackToml, PACK_FORMAT,
+        },
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::{InstanceType, InvalidInstanceIdError},
+        vtask::{TaskState, VisualTask},
+        AppInner,
+    },
+};
+
+use crate::db::{mod_file_cache as fcdb, mod_metadata as metadb};
+
+use super::ZipMode;
+
+/// Exports an instance as a zipped packwiz pack.
+///
+/// Mods known to curseforge or modrinth are written as `.pw.toml` metafiles and
+/// every other file is bundled and listed in the index.
+pub async fn export_packwiz(
+    app: Arc<AppInner>,
+    instance_id: InstanceId,
+    save_path: PathBuf,
+    self_contained_addons_bundling: bool,
+    mut filter: ExportEntry,
+) -> anyhow::Result<VisualTaskId> {
+    let instance_manager = app.instance_manager();
+    let instances = instance_manager.instances.read().await;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or(InvalidInstanceIdError(instance_id))?;
+
+    let basepath = app
+        .settings_manager()
+        .runtime_path
+        .get_instances()
+        .get_instance_path(&instance.shortpath)
+        .get_data_path();
+
+    let InstanceType::Valid(data) = &instance.type_ else {
+        return Err(anyhow!("Instance {instance_id} is not in a valid state"));
+    };
+
+    let config = data.config.clone();
+
+    drop(instances);
+
+    let Some(version) = config.game_configuration.version else {
+        return Err(anyhow!(
+            "Instance {instance_id}'s game version is not known so it cannot be exported"
+        ));
+    };
+
+    let GameVersion::Standard(version) = version else {
+        return Err(anyhow!(
+            "Instance {instance_id} has a custom game version file so it cannot be exported"
+        ));
+    };
+
+    let vtask = VisualTask::new(Translation::InstanceExport);
+    let vtask_id = app.task_manager().spawn_task(&vtask).await;
+
+    tokio::spawn(async move {
+        let try_result: anyhow::Result<_> = async {
+            let mut mods = Vec::new();
+
+            let t_calc_size = vtask.subtask(Translation::InstanceExportCalculateSize);
+            t_calc_size.set_weight(0.0);
+            let t_create_bundle = vtask.subtask(Translation::InstanceExportCreatingBundle);
+
+            vtask
+                .edit(|data| data.state = TaskState::KnownProgress)
+                .await;
+
+            if !self_contained_addons_bundling {
+                let mods_filter = filter.0.get_mut("mods");
+                if let Some(mods_filter) = mods_filter {
+                    let t_scan = vtask.subtask(Translation::InstanceExportScanningMods);
+                    t_calc_size.set_weight(0.5);
+                    t_scan.start_opaque();
+
+                    if mods_filter.is_none() {
+                        let mut modsdir_entries = HashMap::new();
+
+                        let mut dir = tokio::fs::read_dir(basepath.join("mods")).await?;
+                        while let Some(next) = dir.next_entry().await? {
+                            let name = next.file_name();
+                            let Some(name) = name.to_str() else { continue };
+                            modsdir_entries.insert(name.to_string(), None);
+                        }
+
+                        *mods_filter = Some(ExportEntry(modsdir_entries));
+                    }
+
+                    let mods_filter = mods_filter.as_mut().map(|v| &mut v.0).unwrap();
+
+                    app.meta_cache_manager()
+                        .override_caching_and_wait(instance_id, true, true)
+                        .await?;
+
+                    let mods2 = app
+                        .prisma_client
+                        .mod_file_cache()
+                        .find_many(vec![
+                            fcdb::instance_id::equals(*instance_id),
+                            fcdb::enabled::equals(true),
+                        ])
+                        .with(
+                            fcdb::metadata::fetch()
+                                .with(metadb::curseforge::fetch())
+                                .with(metadb::modrinth::fetch()),
+                        )
+                        .exec()
+                        .await?
+                        .into_iter()
+                        .filter_map(|m| {
+                            let metadata = m.metadata?;
+
+                            let curseforge = metadata.curseforge.flatten();
+                            let modrinth = metadata.modrinth.flatten();
+
+                            let (name, slug) = match (&curseforge, &modrinth) {
+                                (_, Some(mr)) => (mr.title.clone(), mr.urlslug.clone()),
+                                (Some(cf), None) => (cf.name.clone(), cf.urlslug.clone()),
+                                (None, None) => return None,
+                            };
+
+                            mods_filter.remove(&m.filename)?;
+
+                            Some(LinkedMod {
+                                filename: m.filename,
+                                name,
+                                slug,
+                                sha512: metadata.sha_512,
+                                murmur2: metadata.murmur_2,
+                                curseforge: curseforge
+                                    .map(|cf| (cf.project_id as u32, cf.file_id as u32)),
+                                modrinth: modrinth
+                                    .map(|mr| (mr.project_id, mr.version_id, mr.file_url)),
+                            })
+                        });
+
+                    mods.extend(mods2);
+                    t_scan.complete_opaque();
+                }
+            }
+
+            t_calc_size.start_opaque();
+
+            let mut files = Vec::new();
+            super::zip_excluding(ZipMode::<File>::List(&mut files), &basepath, "", &filter)?;
+
+            t_calc_size.complete_opaque();
+
+            let file_count = (files.len() + mods.len()) as u32;
+            t_create_bundle.update_items(0, file_count);
+
+            let pack_name = config.name;
+            let tmpfile = app
+                .settings_manager()
+                .runtime_path
+                .get_temp()
+                .maketmpfile()
+                .await?;
+
+            let send_path = tmpfile.to_path_buf();
+            let (notify_tx, mut notify_rx) = mpsc::channel::<()>(1);
+
+            let ziptask = tokio::task::spawn_blocking(move || {
+                let mut zip = zip::ZipWriter::new(File::create(send_path)?);
+                let options = zip::write::FileOptions::default();
+                let mut index = IndexToml {
+                    hash_format: String::from("sha256"),
+                    files: Vec::new(),
+                };
+
+                for (path, source) in files {
+                    zip.start_file(&path, options)?;
+
+                    let mut file = File::open(source)?;
+                    let mut hasher = Sha256::new();
+                    let mut buf = vec![0; 64 * 1024];
+                    loop {
+                        let read = file.read(&mut buf)?;
+                        if read == 0 {
+                            break;
+                        }
+
+                        hasher.update(&buf[..read]);
+                        zip.write_all(&buf[..read])?;
+                    }
+
+                    index.files.push(IndexFile {
+                        file: path,
+                        hash: hex::encode(hasher.finalize()),
+                        hash_format: None,
+                        alias: None,
+                        metafile: false,
+                        preserve: false,
+                    });
+
+                    let _ = notify_tx.blocking_send(());
+                }
+
+                let mut metafile_names = HashSet::new();
+                for mod_ in mods {
+                    let path = metafile_path(&mod_.slug, &mut metafile_names);
+                    let metafile = toml::to_string(&make_mod_toml(mod_))?;
+
+                    zip.start_file(&path, options)?;
+                    zip.write_all(metafile.as_bytes())?;
+
+                    index.files.push(IndexFile {
+                        file: path,
+                        hash: hex::encode(Sha256::digest(metafile.as_bytes())),
+                        hash_format: None,
+                        alias: None,
+                        metafile: true,
+                        preserve: false,
+                    });
+
+                    let _ = notify_tx.blocking_send(());
+                }
+
+                let index = toml::to_string(&index)?;
+                zip.start_file("index.toml", options)?;
+                zip.write_all(index.as_bytes())?;
+
+                let pack = PackToml {
+                    name: pack_name,
+                    author: None,
+                    version: None,
+                    pack_format: Some(String::from(PACK_FORMAT)),
+                    index: PackIndexRef {
+                        file: String::from("index.toml"),
+                        hash_format: String::from("sha256"),
+                        hash: hex::encode(Sha256::digest(index.as_bytes())),
+                    },
+                    versions: make_pack_versions(&version),
+                };
+
+                zip.start_file("pack.toml", options)?;
+                zip.write_all(toml::to_string(&pack)?.as_bytes())?;
+
+                zip.finish()?;
+                Ok::<_, anyhow::Error>(())
+            });
+
+            tokio::select! {
+                r = ziptask => r??,
+                _ = async {
+                    let mut counter = 0;
+
+                    loop {
+                        if notify_rx.recv().await.is_some() {
+                            counter += 1;
+                            t_create_bundle.update_items(counter, file_count);
+                        } else {
+                            futures::future::pending().await
+                        }
+                    }
+                } => {},
+            }
+
+            tmpfile.try_rename_or_move(save_path).await?;
+
+            t_create_bundle.complete_items();
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = try_result {
+            vtask.fail(e).await;
+        }
+    });
+
+    Ok(vtask_id)
+}
+
+/// A mod file with curseforge or modrinth metadata, written as a `.pw.toml`.
+struct LinkedMod {
+    filename: String,
+    name: String,
+    slug: String,
+    sha512: Vec<u8>,
+    murmur2: i32,
+    /// project and file id
+    curseforge: Option<(u32, u32)>,
+    /// project id, version id and download url
+    modrinth: Option<(String, String, String)>,
+}
+
+/// Path of a mod's metafile, numbered when another mod already took its slug.
+fn metafile_path(slug: &str, taken: &mut HashSet<String>) -> String {
+    let mut name = slug.to_string();
+    let mut n = 1;
+
+    while !taken.insert(name.clone()) {
+        n += 1;
+        name = format!("{slug}-{n}");
+    }
+
+    format!("mods/{name}.pw.toml")
+}
+
+fn make_mod_toml(mod_: LinkedMod) -> ModToml {
+    // modrinth files can be downloaded directly, curseforge ones may need the api
+    let download = match &mod_.modrinth {
+        Some((_, _, url)) => ModDownload {
+            url: Some(url.clone()),
+            hash_format: String::from("sha512"),
+            hash: hex::encode(&mod_.sha512),
+            mode: None,
+        },
+        None => ModDownload {
+            url: None,
+            hash_format: String::from("murmur2"),
+            hash: (mod_.murmur2 as u32).to_string(),
+            mode: Some(String::from("metadata:curseforge")),
+        },
+    };
+
+    ModToml {
+        name: mod_.name,
+        filename: mod_.filename,
+        side: Some(String::from("both")),
+        download,
+        update: Some(ModUpdate {
+            curseforge: mod_
+                .curseforge
+                .map(|(project_id, file_id)| CurseforgeUpdate {
+                    file_id,
+                    project_id,
+                }),
+            modrinth: mod_
+                .modrinth
+                .map(|(mod_id, version, _)| ModrinthUpdate { mod_id, version }),
+        }),
+    }
+}
+
+fn make_pack_versions(version: &StandardVersion) -> BTreeMap<String, String> {
+    let mut versions = BTreeMap::from([(String::from("minecraft"), version.release.clone())]);
+
+    for modloader in &version.modloaders {
+        let loader = match modloader.type_ {
+            ModLoaderType::Forge => "forge",
+            ModLoaderType::Neoforge => "neoforge",
+            ModLoaderType::Fabric => "fabric",
+            ModLoaderType::Quilt => "quilt",
+        };
+
+        versions.insert(
+            loader.to_string(),
+            super::short_loader_version(&version.release, modloader).to_string(),
+        );
+    }
+
+    versions
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::domain::instance::info::{ModLoader, ModLoaderType, StandardVersion};
+
+    use super::{make_mod_toml, make_pack_versions, metafile_path, LinkedMod};
+
+    #[test]
+    fn curseforge_mod_toml() {
+        let metafile = make_mod_toml(LinkedMod {
+            filename: String::from("jei-1.20.1-forge-15.2.0.27.jar"),
+            name: String::from("Just Enough Items (JEI)"),
+            slug: String::from("jei"),
+            sha512: vec![0xab; 4],
+            murmur2: -1,
+            curseforge: Some((238222, 4712866)),
+            modrinth: None,
+        });
+
+        assert_eq!(
+            toml::to_string(&metafile).unwrap(),
+            r#"name = "Just Enough Items (JEI)"
+filename = "jei-1.20.1-forge-15.2.0.27.jar"
+side = "both"
+
+[download]
+hash-format = "murmur2"
+hash = "4294967295"
+mode = "metadata:curseforge"
+
+[update.curseforge]
+file-id = 4712866
+project-id = 238222
+"#
+        );
+    }
+
+    #[test]
+    fn modrinth_mod_toml() {
+        let metafile = make_mod_toml(LinkedMod {
+            filename: String::from("sodium.jar"),
+            name: String::from("Sodium"),
+            slug: String::from("sodium"),
+            sha512: vec![0xab, 0xcd],
+            murmur2: 1,
+            curseforge: Some((394468, 4605078)),
+            modrinth: Some((
+                String::from("AANobbMI"),
+                String::from("OihdIimA"),
+                String::from("https://cdn.modrinth.com/sodium.jar"),
+            )),
+        });
+
+        assert_eq!(
+            toml::to_string(&metafile).unwrap(),
+            r#"name = "Sodium"
+filename = "sodium.jar"
+side = "both"
+
+[download]
+url = "https://cdn.modrinth.com/sodium.jar"
+hash-format = "sha512"
+hash = "abcd"
+
+[update.curseforge]
+file-id = 4605078
+project-id = 394468
+
+[update.modrinth]
+mod-id = "AANobbMI"
+version = "OihdIimA"
+"#
+        );
+    }
+
+    #[test]
+    fn unique_metafile_paths() {
+        let mut taken = HashSet::new();
+
+        assert_eq!(metafile_path("jei", &mut taken), "mods/jei.pw.toml");
+        assert_eq!(metafile_path("sodium", &mut taken), "mods/sodium.pw.toml");
+        assert_eq!(metafile_path("jei", &mut taken), "mods/jei-2.pw.toml");
+        assert_eq!(metafile_path("jei", &mut taken), "mods/jei-3.pw.toml");
+    }
+
+    #[test]
+    fn pack_versions() {
+        let versions = make_pack_versions(&StandardVersion {
+            release: String::from("1.20.1"),
+            modloaders: HashSet::from([ModLoader {
+                type_: ModLoaderType::Forge,
+                version: String::from("1.20.1-47.1.0"),
+            }]),
+        });
+
+        assert_eq!(versions["minecraft"], "1.20.1");
+        assert_eq!(versions["forge"], "47.1.0");
+    }
+}
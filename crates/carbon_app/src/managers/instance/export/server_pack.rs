@@ -0,0 +1,462 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use carbon_net::{Checksum, Downloadable};
+use daedalus::minecraft::DownloadType;
+use tokio::sync::mpsc;
+use zip::{write::FileOptions, ZipWriter};
+
+use crate::{
+    api::translation::Translation,
+    domain::{
+        instance::{info::GameVersion, ExportEntry, InstanceId},
+        modplatforms::modrinth::{project::ProjectSupportRange, search::ProjectIDs},
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::{InstanceType, InvalidInstanceIdError},
+        metadata::mods::{parse_environment, ModEnvironment},
+        minecraft::server::{server_setup, ServerSetup},
+        vtask::{TaskState, VisualTask},
+        AppInner,
+    },
+};
+
+use crate::db::{mod_file_cache as fcdb, mod_metadata as metadb};
+
+use super::ZipMode;
+
+/// Exports an instance as a dedicated server, either as a folder or, when the
+/// save path ends in `.zip`, as a zip archive.
+///
+/// Client only mods are dropped, using the environment declared by the mod
+/// and the side support listed on modrinth. The loader installer (or vanilla
+/// server jar) is bundled with start scripts that install the server on the
+/// first start.
+pub async fn export_server_pack(
+    app: Arc<AppInner>,
+    instance_id: InstanceId,
+    save_path: PathBuf,
+    filter: ExportEntry,
+) -> anyhow::Result<VisualTaskId> {
+    let instance_manager = app.instance_manager();
+    let instances = instance_manager.instances.read().await;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or(InvalidInstanceIdError(instance_id))?;
+
+    let basepath = app
+        .settings_manager()
+        .runtime_path
+        .get_instances()
+        .get_instance_path(&instance.shortpath)
+        .get_data_path();
+
+    let InstanceType::Valid(data) = &instance.type_ else {
+        return Err(anyhow!("Instance {instance_id} is not in a valid state"));
+    };
+
+    let config = data.config.clone();
+
+    drop(instances);
+
+    let Some(version) = config.game_configuration.version else {
+        return Err(anyhow!(
+            "Instance {instance_id}'s game version is not known so it cannot be exported"
+        ));
+    };
+
+    let GameVersion::Standard(version) = version else {
+        return Err(anyhow!(
+            "Instance {instance_id} has a custom game version file so it cannot be exported"
+        ));
+    };
+
+    let setup = server_setup(&version.release, version.modloaders.iter().next(), true);
+    let java_args = match config.game_configuration.memory {
+        Some((min, max)) => format!("-Xms{min}M -Xmx{max}M"),
+        None => String::new(),
+    };
+
+    let vtask = VisualTask::new(Translation::InstanceExport);
+    let vtask_id = app.task_manager().spawn_task(&vtask).await;
+
+    tokio::spawn(async move {
+        let try_result: anyhow::Result<_> = async {
+            let t_scan = vtask.subtask(Translation::InstanceExportScanningMods);
+            let t_calc_size = vtask.subtask(Translation::InstanceExportCalculateSize);
+            t_calc_size.set_weight(0.0);
+            let t_download = vtask.subtask(Translation::InstanceExportDownloadServerFiles);
+            let t_create_bundle = vtask.subtask(Translation::InstanceExportCreatingBundle);
+
+            vtask
+                .edit(|data| data.state = TaskState::KnownProgress)
+                .await;
+
+            t_calc_size.start_opaque();
+
+            let mut files = Vec::new();
+            super::zip_excluding(ZipMode::<File>::List(&mut files), &basepath, "", &filter)?;
+
+            t_calc_size.complete_opaque();
+            t_scan.start_opaque();
+
+            app.meta_cache_manager()
+                .override_caching_and_wait(instance_id, true, true)
+                .await?;
+
+            let modrinth_projects = app
+                .prisma_client
+                .mod_file_cache()
+                .find_many(vec![fcdb::instance_id::equals(*instance_id)])
+                .with(fcdb::metadata::fetch().with(metadb::modrinth::fetch()))
+                .exec()
+                .await?
+                .into_iter()
+                .filter_map(|m| Some((m.metadata?.modrinth.flatten()?.project_id, m.filename)))
+                .collect::<HashMap<_, _>>();
+
+            // mods modrinth lists as unsupported on servers
+            let mut client_mods = HashSet::new();
+            if !modrinth_projects.is_empty() {
+                let projects = app
+                    .modplatforms_manager()
+                    .modrinth
+                    .get_projects(ProjectIDs {
+                        ids: modrinth_projects.keys().cloned().collect(),
+                    })
+                    .await?;
+
+                client_mods.extend(
+                    projects
+                        .0
+                        .into_iter()
+                        .filter(|project| project.server_side == ProjectSupportRange::Unsupported)
+                        .filter_map(|project| modrinth_projects.get(&project.id).cloned()),
+                );
+            }
+
+            let files = tokio::task::spawn_blocking(move || {
+                let mut kept = Vec::new();
+
+                for (path, source) in files {
+                    if let Some(name) = path.strip_prefix("mods/") {
+                        if name.ends_with(".disabled") || client_mods.contains(name) {
+                            continue;
+                        }
+
+                        if name.ends_with(".jar") {
+                            let environment =
+                                parse_environment(&mut File::open(&source)?).unwrap_or_else(|e| {
+                                    tracing::warn!({ error = ?e }, "could not read environment of mod {name}");
+                                    ModEnvironment::Both
+                                });
+
+                            if environment == ModEnvironment::Client {
+                                continue;
+                            }
+                        }
+                    }
+
+                    kept.push((path, source));
+                }
+
+                Ok::<_, anyhow::Error>(kept)
+            })
+            .await??;
+
+            t_scan.complete_opaque();
+            t_download.start_opaque();
+
+            let download_dir = app
+                .settings_manager()
+                .runtime_path
+                .get_temp()
+                .maketmpdir()
+                .await?;
+
+            let download = match &setup.installer {
+                Some(installer) => Downloadable::new(
+                    installer.resolve_url(&app.reqwest_client).await?,
+                    download_dir.join(&installer.jar),
+                ),
+                None => {
+                    let server = app
+                        .minecraft_manager()
+                        .get_minecraft_version(&version.release)
+                        .await?
+                        .downloads
+                        .remove(&DownloadType::Server)
+                        .ok_or_else(|| {
+                            anyhow!("Minecraft {} has no dedicated server", version.release)
+                        })?;
+
+                    Downloadable::new(server.url, download_dir.join(&setup.server_jar))
+                        .with_checksum(Some(Checksum::Sha1(server.sha1)))
+                        .with_size(server.size as u64)
+                }
+            };
+
+            let bundled_jar = download.path.clone();
+            carbon_net::download_multiple(&[download], None, 1, true, false).await?;
+
+            t_download.complete_opaque();
+
+            let file_count = files.len() as u32;
+            t_create_bundle.update_items(0, file_count);
+
+            let scripts = [
+                ("start.sh", start_script(&setup, &java_args, false)),
+                ("start.bat", start_script(&setup, &java_args, true)),
+            ];
+
+            let as_zip = save_path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+
+            let tmpfile = app
+                .settings_manager()
+                .runtime_path
+                .get_temp()
+                .maketmpfile()
+                .await?;
+
+            let send_path = tmpfile.to_path_buf();
+            let target = match as_zip {
+                true => send_path.clone(),
+                false => save_path.clone(),
+            };
+            let (notify_tx, mut notify_rx) = mpsc::channel::<()>(1);
+
+            let writetask = tokio::task::spawn_blocking(move || {
+                let mut writer = match as_zip {
+                    true => PackWriter::Zip(ZipWriter::new(File::create(&target)?)),
+                    false => {
+                        fs::create_dir_all(&target)?;
+                        PackWriter::Dir(target)
+                    }
+                };
+
+                let jar_name = bundled_jar
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .ok_or_else(|| anyhow!("bundled server jar has no name"))?;
+                writer.copy_file(&jar_name, &bundled_jar)?;
+
+                for (name, script) in scripts {
+                    writer.write_file(name, script.as_bytes(), true)?;
+                }
+
+                for (path, source) in files {
+                    writer.copy_file(&path, &source)?;
+                    let _ = notify_tx.blocking_send(());
+                }
+
+                writer.finish()
+            });
+
+            tokio::select! {
+                r = writetask => r??,
+                _ = async {
+                    let mut counter = 0;
+
+                    loop {
+                        if notify_rx.recv().await.is_some() {
+                            counter += 1;
+                            t_create_bundle.update_items(counter, file_count);
+                        } else {
+                            futures::future::pending().await
+                        }
+                    }
+                } => {},
+            }
+
+            if as_zip {
+                tmpfile.try_rename_or_move(save_path).await?;
+            }
+
+            t_create_bundle.complete_items();
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = try_result {
+            vtask.fail(e).await;
+        }
+    });
+
+    Ok(vtask_id)
+}
+
+enum PackWriter {
+    Zip(ZipWriter<File>),
+    Dir(PathBuf),
+}
+
+impl PackWriter {
+    fn write_file(&mut self, path: &str, data: &[u8], executable: bool) -> anyhow::Result<()> {
+        match self {
+            Self::Zip(zip) => {
+                let options = match executable {
+                    true => FileOptions::default().unix_permissions(0o755),
+                    false => FileOptions::default(),
+                };
+
+                zip.start_file(path, options)?;
+                zip.write_all(data)?;
+            }
+            Self::Dir(dir) => {
+                let target = dir.join(path);
+                fs::write(&target, data)?;
+
+                #[cfg(unix)]
+                if executable {
+                    use std::os::unix::fs::PermissionsExt;
+                    fs::set_permissions(&target, fs::Permissions::from_mode(0o755))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn copy_file(&mut self, path: &str, source: &Path) -> anyhow::Result<()> {
+        match self {
+            Self::Zip(zip) => {
+                zip.start_file(path, FileOptions::default())?;
+                io::copy(&mut File::open(source)?, zip)?;
+            }
+            Self::Dir(dir) => {
+                let target = dir.join(path);
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
+                fs::copy(source, target)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        if let Self::Zip(mut zip) = self {
+            zip.finish()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a start script that installs the server on its first run and then launches it.
+fn start_script(setup: &ServerSetup, java_args: &str, windows: bool) -> String {
+    let launch = [java_args, &setup.launch.args(windows), "nogui"]
+        .into_iter()
+        .filter(|arg| !arg.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut lines = Vec::new();
+
+    match windows {
+        false => {
+            lines.push(String::from("#!/usr/bin/env sh"));
+            lines.push(String::from("cd \"$(dirname \"$0\")\" || exit 1"));
+            lines.push(String::new());
+
+            if let Some(installer) = &setup.installer {
+                lines.push(format!(
+                    "if [ ! -f \"{}\" ]; then",
+                    setup.launch.marker(false)
+                ));
+                lines.push(format!(
+                    "    java -jar {} {} || exit 1",
+                    installer.jar, installer.args
+                ));
+                lines.push(String::from("fi"));
+                lines.push(String::new());
+            }
+
+            lines.push(format!("exec java {launch} \"$@\""));
+        }
+        true => {
+            lines.push(String::from("@echo off"));
+            lines.push(String::from("cd /d \"%~dp0\""));
+            lines.push(String::new());
+
+            if let Some(installer) = &setup.installer {
+                lines.push(format!(
+                    "if not exist \"{}\" (",
+                    setup.launch.marker(true).replace('/', "\\")
+                ));
+                lines.push(format!(
+                    "    java -jar {} {} || exit /b 1",
+                    installer.jar, installer.args
+                ));
+                lines.push(String::from(")"));
+                lines.push(String::new());
+            }
+
+            lines.push(format!("java {launch} %*"));
+            lines.push(String::from("pause"));
+        }
+    }
+
+    let newline = match windows {
+        true => "\r\n",
+        false => "\n",
+    };
+
+    lines.join(newline) + newline
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        domain::instance::info::{ModLoader, ModLoaderType},
+        managers::minecraft::server::server_setup,
+    };
+
+    use super::start_script;
+
+    #[test]
+    fn fabric_start_script() {
+        let setup = server_setup(
+            "1.20.1",
+            Some(&ModLoader {
+                type_: ModLoaderType::Fabric,
+                version: String::from("0.15.7"),
+            }),
+            true,
+        );
+
+        assert_eq!(
+            start_script(&setup, "-Xms1024M -Xmx4096M", false),
+            "#!/usr/bin/env sh\n\
+             cd \"$(dirname \"$0\")\" || exit 1\n\
+             \n\
+             if [ ! -f \"fabric-server-launch.jar\" ]; then\n    \
+             java -jar fabric-installer.jar server -mcversion 1.20.1 -loader 0.15.7 -downloadMinecraft || exit 1\n\
+             fi\n\
+             \n\
+             exec java -Xms1024M -Xmx4096M -jar fabric-server-launch.jar nogui \"$@\"\n"
+        );
+    }
+
+    #[test]
+    fn vanilla_start_script() {
+        let setup = server_setup("1.20.1", None, true);
+
+        assert_eq!(
+            start_script(&setup, "", true),
+            "@echo off\r\ncd /d \"%~dp0\"\r\n\r\njava -jar server.jar nogui %*\r\npause\r\n"
+        );
+    }
+}
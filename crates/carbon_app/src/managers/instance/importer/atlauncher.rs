@@ -0,0 +1,463 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::{error, trace};
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::{
+            info::{CurseforgeModpack, GameVersion, ModLoaderType, Modpack, ModrinthModpack},
+            InstanceSettingsUpdate,
+        },
+        modplatforms::ModFileSource,
+        vtask::VisualTaskId,
+    },
+    managers::{instance::InstanceVersionSource, AppInner},
+};
+
+use super::{
+    resolve_standard_version, ImportScanStatus, ImportableInstance, ImporterState,
+    InstanceImporter, InternalImportEntry, InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    path: PathBuf,
+    config: AtlInstance,
+    modloader: Option<(ModLoaderType, String)>,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value.filename,
+            instance_name: value.config.launcher.name,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ATLauncherImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl ATLauncherImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    async fn scan_instance(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        let config = path.join("instance.json");
+        if !config.is_file() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .expect("filename cannot be empty")
+            .to_string_lossy()
+            .to_string();
+
+        let config = tokio::fs::read_to_string(config).await?;
+        let Ok(config) = serde_json::from_str::<AtlInstance>(&config) else {
+            return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportAtlauncherBadConfigFile,
+            })));
+        };
+
+        let modloader = match &config.launcher.loader_version {
+            Some(loader) => match parse_loader_type(&loader.type_) {
+                Some(type_) => Some((type_, loader.version.clone())),
+                None => {
+                    return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                        name: config.launcher.name,
+                        reason: Translation::InstanceImportAtlauncherUnsupportedLoader {
+                            loader: loader.type_.clone(),
+                        },
+                    })))
+                }
+            },
+            None => None,
+        };
+
+        Ok(Some(InternalImportEntry::Valid(Importable {
+            filename,
+            path,
+            config,
+            modloader,
+        })))
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for ATLauncherImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        if scan_path.is_dir() {
+            if let Ok(Some(entry)) = self.scan_instance(scan_path.clone()).await {
+                self.state.write().await.set_single(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                return Ok(());
+            }
+
+            let Ok(mut dir) = tokio::fs::read_dir(&scan_path).await else {
+                return Ok(());
+            };
+
+            while let Some(path) = dir.next_entry().await? {
+                if path.metadata().await?.is_dir() {
+                    if let Ok(Some(entry)) = self.scan_instance(path.path()).await {
+                        self.state.write().await.push_multi(entry).await;
+                        app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning atlauncher import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let version = GameVersion::Standard(
+            resolve_standard_version(
+                app,
+                instance.config.id.clone(),
+                instance.modloader.clone().into_iter().collect(),
+            )
+            .await?,
+        );
+
+        let launcher = &instance.config.launcher;
+        let modpack = match (
+            &launcher.curse_forge_project,
+            &launcher.curse_forge_file,
+            &launcher.modrinth_project,
+            &launcher.modrinth_version,
+        ) {
+            (Some(project), Some(file), _, _) => Some(Modpack::Curseforge(CurseforgeModpack {
+                project_id: project.id,
+                file_id: file.id,
+            })),
+            (_, _, Some(project), Some(version)) => Some(Modpack::Modrinth(ModrinthModpack {
+                project_id: project.id.clone(),
+                version_id: version.id.clone(),
+            })),
+            _ => None,
+        };
+
+        let instance_version_source = match modpack {
+            Some(modpack) => {
+                InstanceVersionSource::ModpackWithKnownVersion(version, modpack, false)
+            }
+            None => InstanceVersionSource::Version(version),
+        };
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            async move {
+                let path = instance_path.join("instance");
+
+                tokio::fs::create_dir_all(instance_path.join(".setup").join("modpack-complete"))
+                    .await?;
+
+                // Mod files are copied untouched, the platform files atlauncher recorded
+                // for them are linked once the instance has been created.
+                trace!("Copying files from atlauncher instance");
+                crate::domain::runtime_path::copy_dir_filter(&instance.path, &path, |path| {
+                    !matches!(
+                        path.iter().next().and_then(|p| p.to_str()),
+                        Some(
+                            "instance.json"
+                                | "disabledmods"
+                                | "bin"
+                                | "libraries"
+                                | "natives"
+                                | "loaders"
+                                | "jarmods"
+                        )
+                    )
+                })
+                .await?;
+
+                // atlauncher moves disabled mods out of the mods folder instead of renaming them
+                let disabled = instance
+                    .config
+                    .launcher
+                    .mods
+                    .iter()
+                    .filter(|m| m.disabled && m.is_mod());
+
+                for mod_ in disabled {
+                    let source = instance.path.join("disabledmods").join(&mod_.file);
+                    if !source.is_file() {
+                        continue;
+                    }
+
+                    let mods = path.join("mods");
+                    tokio::fs::create_dir_all(&mods).await?;
+                    tokio::fs::copy(source, mods.join(format!("{}.disabled", mod_.file))).await?;
+                }
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| instance.config.launcher.name.clone()),
+                None,
+                None,
+                None,
+                instance_version_source,
+                String::new(),
+                initializer,
+            )
+            .await?;
+
+        let launcher = &instance.config.launcher;
+        app.instance_manager()
+            .update_instance(InstanceSettingsUpdate {
+                extra_java_args: Some(launcher.java_arguments.clone().filter(|a| !a.is_empty())),
+                memory: Some(
+                    launcher
+                        .maximum_memory
+                        .map(|max| (launcher.initial_memory.unwrap_or(max).min(max), max)),
+                ),
+                ..InstanceSettingsUpdate::new(id)
+            })
+            .await?;
+
+        let sources = mod_sources(&instance.config.launcher.mods);
+        if !sources.is_empty() {
+            let app = app.clone();
+            tokio::spawn(async move {
+                let r = app.meta_cache_manager().seed_mod_sources(id, sources).await;
+
+                if let Err(e) = r {
+                    error!({ error = ?e }, "could not link atlauncher mods to their platform files");
+                }
+            });
+        }
+
+        app.instance_manager()
+            .prepare_game(id, None, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+/// Platform files of the mods in the mods folder, by filename.
+fn mod_sources(mods: &[AtlMod]) -> Vec<(String, ModFileSource)> {
+    mods.iter()
+        .filter(|m| m.is_mod())
+        .filter_map(|m| {
+            let source = match (
+                m.curse_forge_project_id,
+                m.curse_forge_file_id,
+                &m.modrinth_version,
+            ) {
+                (Some(project_id), Some(file_id), _) => ModFileSource::Curseforge {
+                    project_id,
+                    file_id,
+                },
+                (_, _, Some(version)) => ModFileSource::Modrinth {
+                    version_id: version.id.clone(),
+                },
+                _ => return None,
+            };
+
+            Some((m.file.clone(), source))
+        })
+        .collect()
+}
+
+fn parse_loader_type(loader: &str) -> Option<ModLoaderType> {
+    match loader {
+        "Forge" => Some(ModLoaderType::Forge),
+        "NeoForge" => Some(ModLoaderType::Neoforge),
+        "Fabric" => Some(ModLoaderType::Fabric),
+        "Quilt" => Some(ModLoaderType::Quilt),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AtlInstance {
+    // minecraft version id, instance.json embeds the vanilla version json
+    id: String,
+    launcher: AtlLauncher,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AtlLauncher {
+    name: String,
+    loader_version: Option<AtlLoaderVersion>,
+    #[serde(default)]
+    mods: Vec<AtlMod>,
+    initial_memory: Option<u16>,
+    maximum_memory: Option<u16>,
+    java_arguments: Option<String>,
+    curse_forge_project: Option<AtlCurseforgeId>,
+    curse_forge_file: Option<AtlCurseforgeId>,
+    modrinth_project: Option<AtlModrinthId>,
+    modrinth_version: Option<AtlModrinthId>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AtlLoaderVersion {
+    version: String,
+    #[serde(rename = "type")]
+    type_: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AtlMod {
+    file: String,
+    #[serde(rename = "type")]
+    type_: Option<String>,
+    #[serde(default)]
+    disabled: bool,
+    curse_forge_project_id: Option<u32>,
+    curse_forge_file_id: Option<u32>,
+    modrinth_version: Option<AtlModrinthId>,
+}
+
+impl AtlMod {
+    /// Whether the file belongs in the mods folder, rather than being a resource pack or similar
+    fn is_mod(&self) -> bool {
+        self.type_.as_deref().map_or(true, |type_| type_ == "mods")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AtlCurseforgeId {
+    id: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AtlModrinthId {
+    id: String,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::instance::info::ModLoaderType;
+    use crate::domain::modplatforms::ModFileSource;
+
+    use super::{mod_sources, parse_loader_type, AtlInstance};
+
+    const INSTANCE: &str = r#"{
+        "id": "1.20.1",
+        "launcher": {
+            "name": "Test Pack",
+            "loaderVersion": { "version": "47.2.0", "type": "Forge" },
+            "initialMemory": 1024,
+            "maximumMemory": 4096,
+            "javaArguments": "",
+            "curseForgeProject": { "id": 1000 },
+            "curseForgeFile": { "id": 2000 },
+            "mods": [
+                {
+                    "file": "jei.jar",
+                    "type": "mods",
+                    "curseForgeProjectId": 238222,
+                    "curseForgeFileId": 4712866
+                },
+                {
+                    "file": "sodium.jar",
+                    "type": "mods",
+                    "disabled": true,
+                    "modrinthProject": { "id": "AANobbMI" },
+                    "modrinthVersion": { "id": "OihdIimA" }
+                },
+                {
+                    "file": "faithful.zip",
+                    "type": "resourcepack",
+                    "curseForgeProjectId": 1,
+                    "curseForgeFileId": 2
+                },
+                { "file": "local.jar", "type": "mods" }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn parse_instance() {
+        let instance = serde_json::from_str::<AtlInstance>(INSTANCE).unwrap();
+        let launcher = &instance.launcher;
+
+        assert_eq!(instance.id, "1.20.1");
+        assert_eq!(launcher.maximum_memory, Some(4096));
+        assert_eq!(launcher.curse_forge_file.as_ref().map(|f| f.id), Some(2000));
+        assert_eq!(
+            launcher
+                .loader_version
+                .as_ref()
+                .and_then(|loader| parse_loader_type(&loader.type_)),
+            Some(ModLoaderType::Forge)
+        );
+        assert_eq!(parse_loader_type("LegacyFabric"), None);
+
+        assert!(launcher.mods[1].disabled);
+        assert!(!launcher.mods[2].is_mod());
+    }
+
+    #[test]
+    fn platform_mod_sources() {
+        let instance = serde_json::from_str::<AtlInstance>(INSTANCE).unwrap();
+
+        // resource packs and mods without a platform file are left out
+        assert_eq!(
+            mod_sources(&instance.launcher.mods),
+            [
+                (
+                    String::from("jei.jar"),
+                    ModFileSource::Curseforge {
+                        project_id: 238222,
+                        file_id: 4712866,
+                    },
+                ),
+                (
+                    String::from("sodium.jar"),
+                    ModFileSource::Modrinth {
+                        version_id: String::from("OihdIimA"),
+                    },
+                ),
+            ]
+        );
+    }
+}
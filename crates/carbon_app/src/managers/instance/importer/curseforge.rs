@@ -286,7 +286,7 @@ impl InstanceImporter for CurseforgeImporter {
             .await?;
 
         app.instance_manager()
-            .prepare_game(id, None, None, false)
+            .prepare_game(id, None, None, None, false)
             .await
             .map(|r| r.1)
     }
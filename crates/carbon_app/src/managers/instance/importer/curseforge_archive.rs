@@ -300,7 +300,7 @@ impl InstanceImporter for CurseforgeArchiveImporter {
             .await?;
 
         app.instance_manager()
-            .prepare_game(id, None, None, true)
+            .prepare_game(id, None, None, None, true)
             .await
             .map(|r| r.1)
     }
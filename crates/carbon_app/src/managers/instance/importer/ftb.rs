@@ -0,0 +1,343 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::{
+            info::{CurseforgeModpack, GameResolution, GameVersion, ModLoaderType, Modpack},
+            InstanceSettingsUpdate,
+        },
+        vtask::VisualTaskId,
+    },
+    managers::{instance::InstanceVersionSource, AppInner},
+};
+
+use super::{
+    resolve_standard_version, ImportScanStatus, ImportableInstance, ImporterState,
+    InstanceImporter, InternalImportEntry, InvalidImportEntry,
+};
+
+// packType used by the FTB App for packs installed from curseforge
+const FTB_PACK_TYPE_CURSEFORGE: u32 = 1;
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    path: PathBuf,
+    config: FtbInstance,
+    modloader: Option<(ModLoaderType, String)>,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value.filename,
+            instance_name: value.config.name,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FTBImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl FTBImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    pub async fn get_default_scan_path() -> anyhow::Result<PathBuf> {
+        let basedirs = directories::BaseDirs::new().ok_or(anyhow!("Cannot build basedirs"))?;
+
+        #[cfg(target_os = "windows")]
+        let p = basedirs.data_local_dir().join(".ftba");
+        #[cfg(target_os = "macos")]
+        let p = basedirs.data_dir().join(".ftba");
+        // older app versions installed to the home directory instead of the xdg data dir
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let p = [
+            basedirs.data_dir().join(".ftba"),
+            basedirs.home_dir().join(".ftba"),
+        ]
+        .into_iter()
+        .find(|p| p.is_dir())
+        .unwrap_or_else(|| basedirs.home_dir().join(".ftba"));
+
+        Ok(p.join("instances"))
+    }
+
+    async fn scan_instance(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        let config = path.join("instance.json");
+        if !config.is_file() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .expect("filename cannot be empty")
+            .to_string_lossy()
+            .to_string();
+
+        let config = tokio::fs::read_to_string(config).await?;
+        let Ok(config) = serde_json::from_str::<FtbInstance>(&config) else {
+            return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportFtbBadConfigFile,
+            })));
+        };
+
+        let modloader = match config.mod_loader.as_deref() {
+            None | Some("") | Some("vanilla") => None,
+            Some(loader) => match parse_ftb_modloader(loader, &config.mc_version) {
+                Some(modloader) => Some(modloader),
+                None => {
+                    return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                        name: config.name,
+                        reason: Translation::InstanceImportFtbUnsupportedLoader {
+                            loader: loader.to_string(),
+                        },
+                    })))
+                }
+            },
+        };
+
+        Ok(Some(InternalImportEntry::Valid(Importable {
+            filename,
+            path,
+            config,
+            modloader,
+        })))
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for FTBImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        if scan_path.is_dir() {
+            if let Ok(Some(entry)) = self.scan_instance(scan_path.clone()).await {
+                self.state.write().await.set_single(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                return Ok(());
+            }
+
+            let Ok(mut dir) = tokio::fs::read_dir(&scan_path).await else {
+                return Ok(());
+            };
+
+            while let Some(path) = dir.next_entry().await? {
+                if path.metadata().await?.is_dir() {
+                    if let Ok(Some(entry)) = self.scan_instance(path.path()).await {
+                        self.state.write().await.push_multi(entry).await;
+                        app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning ftb import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let version = GameVersion::Standard(
+            resolve_standard_version(
+                app,
+                instance.config.mc_version.clone(),
+                instance.modloader.clone().into_iter().collect(),
+            )
+            .await?,
+        );
+
+        let instance_version_source = match &instance.config {
+            FtbInstance {
+                pack_type: Some(FTB_PACK_TYPE_CURSEFORGE),
+                id: Some(project_id),
+                version_id: Some(file_id),
+                ..
+            } => InstanceVersionSource::ModpackWithKnownVersion(
+                version,
+                Modpack::Curseforge(CurseforgeModpack {
+                    project_id: *project_id,
+                    file_id: *file_id,
+                }),
+                false,
+            ),
+            _ => InstanceVersionSource::Version(version),
+        };
+
+        let icon_path = instance.path.join("folder.jpg");
+        let icon = match icon_path.is_file() {
+            true => app.instance_manager().load_icon(icon_path).await.ok(),
+            false => None,
+        };
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            async move {
+                let path = instance_path.join("instance");
+
+                tokio::fs::create_dir_all(instance_path.join(".setup").join("modpack-complete"))
+                    .await?;
+
+                trace!("Copying files from ftb instance");
+                crate::domain::runtime_path::copy_dir_filter(&instance.path, &path, |path| {
+                    match path.iter().next().and_then(|p| p.to_str()) {
+                        Some(
+                            "folder.jpg" | ".ftbapp" | "natives" | "versions" | "libraries"
+                            | "assets",
+                        ) => false,
+                        Some(p) if p.starts_with("instance.json") => false,
+                        _ => true,
+                    }
+                })
+                .await?;
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| instance.config.name.clone()),
+                icon,
+                None,
+                None,
+                instance_version_source,
+                String::new(),
+                initializer,
+            )
+            .await?;
+
+        let config = &instance.config;
+        app.instance_manager()
+            .update_instance(InstanceSettingsUpdate {
+                extra_java_args: Some(config.jvm_args.clone().filter(|a| !a.is_empty())),
+                memory: Some(
+                    config
+                        .memory
+                        .map(|max| (config.min_memory.unwrap_or(max).min(max), max)),
+                ),
+                game_resolution: Some(
+                    config
+                        .width
+                        .zip(config.height)
+                        .map(|(w, h)| GameResolution::Custom(w, h)),
+                ),
+                ..InstanceSettingsUpdate::new(id)
+            })
+            .await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+/// Parses the FTB App `modLoader` field.
+///
+/// The app has used a few formats over time, e.g. `1.19.2-forge-43.2.14`,
+/// `forge-1.16.5-36.2.39` and `fabric-loader-0.14.21-1.20.1`.
+fn parse_ftb_modloader(loader: &str, mc_version: &str) -> Option<(ModLoaderType, String)> {
+    let parts = loader.split('-').collect::<Vec<_>>();
+
+    let (index, type_) = parts.iter().enumerate().find_map(|(i, part)| {
+        let type_ = match part.to_lowercase().as_str() {
+            "forge" => ModLoaderType::Forge,
+            "neoforge" => ModLoaderType::Neoforge,
+            "fabric" => ModLoaderType::Fabric,
+            "quilt" => ModLoaderType::Quilt,
+            _ => return None,
+        };
+
+        Some((i, type_))
+    })?;
+
+    let version = parts[index + 1..]
+        .iter()
+        .find(|part| **part != "loader" && **part != mc_version)?;
+
+    Some((type_, version.to_string()))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FtbInstance {
+    name: String,
+    mc_version: String,
+    mod_loader: Option<String>,
+    // pack ids, which are curseforge ids when `pack_type` is 1
+    id: Option<u32>,
+    version_id: Option<u32>,
+    pack_type: Option<u32>,
+    min_memory: Option<u16>,
+    memory: Option<u16>,
+    jvm_args: Option<String>,
+    width: Option<u16>,
+    height: Option<u16>,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::instance::info::ModLoaderType;
+
+    use super::parse_ftb_modloader;
+
+    #[test]
+    fn parse_modloader_formats() {
+        assert_eq!(
+            parse_ftb_modloader("1.19.2-forge-43.2.14", "1.19.2"),
+            Some((ModLoaderType::Forge, String::from("43.2.14")))
+        );
+
+        assert_eq!(
+            parse_ftb_modloader("forge-1.16.5-36.2.39", "1.16.5"),
+            Some((ModLoaderType::Forge, String::from("36.2.39")))
+        );
+
+        assert_eq!(
+            parse_ftb_modloader("1.20.1-neoforge-47.1.79", "1.20.1"),
+            Some((ModLoaderType::Neoforge, String::from("47.1.79")))
+        );
+
+        assert_eq!(
+            parse_ftb_modloader("fabric-loader-0.14.21-1.20.1", "1.20.1"),
+            Some((ModLoaderType::Fabric, String::from("0.14.21")))
+        );
+
+        assert_eq!(parse_ftb_modloader("liteloader-1.12.2", "1.12.2"), None);
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{instance::info, vtask::VisualTaskId},
+    managers::{
+        instance::{export::gdlinstance::CONFIG_FILE, schema},
+        AppInner,
+    },
+};
+
+use super::{
+    ImportScanStatus, ImportableInstance, ImporterState, InstanceImporter, InternalImportEntry,
+    InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    path: PathBuf,
+    config: info::Instance,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value.filename,
+            instance_name: value.config.name,
+        }
+    }
+}
+
+/// Importer for `.gdlinstance` archives created by the native export target.
+#[derive(Debug)]
+pub struct GDLInstanceImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl GDLInstanceImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    async fn scan_archive(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .expect("filename cannot be empty")
+            .to_string_lossy()
+            .to_string();
+
+        let path2 = path.clone();
+        let config = tokio::task::spawn_blocking(move || {
+            let file = fs::File::open(path2)
+                .map_err(|_| Translation::InstanceImportGdlinstanceMalformed)?;
+
+            let mut zip = zip::ZipArchive::new(file)
+                .map_err(|_| Translation::InstanceImportGdlinstanceMalformed)?;
+
+            let mut config = zip
+                .by_name(CONFIG_FILE)
+                .map_err(|_| Translation::InstanceImportGdlinstanceMissingConfig)?;
+
+            let mut data = String::new();
+            config
+                .read_to_string(&mut data)
+                .map_err(|_| Translation::InstanceImportGdlinstanceMalformedConfig)?;
+
+            schema::parse_instance_config(&data)
+                .map_err(|_| Translation::InstanceImportGdlinstanceMalformedConfig)
+        })
+        .await?;
+
+        match config {
+            Ok(config) => Ok(Some(InternalImportEntry::Valid(Importable {
+                filename,
+                path,
+                config,
+            }))),
+            Err(reason) => Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason,
+            }))),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for GDLInstanceImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        if scan_path.is_file() {
+            if let Ok(Some(entry)) = self.scan_archive(scan_path).await {
+                self.state.write().await.set_single(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+            }
+        } else if scan_path.is_dir() {
+            let Ok(mut dir) = tokio::fs::read_dir(&scan_path).await else {
+                return Ok(());
+            };
+
+            while let Some(entry) = dir.next_entry().await? {
+                if entry.metadata().await?.is_file() {
+                    if let Ok(Some(entry)) = self.scan_archive(entry.path()).await {
+                        self.state.write().await.push_multi(entry).await;
+                        app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning gdlinstance import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let mut config = instance.config.clone();
+        if let Some(name) = name {
+            config.name = name;
+        }
+
+        let initializer = |instance_path: PathBuf| {
+            let archive = instance.path.clone();
+            async move {
+                tokio::fs::create_dir_all(instance_path.join(".setup").join("modpack-complete"))
+                    .await?;
+
+                trace!("Extracting instance archive");
+                tokio::task::spawn_blocking(move || {
+                    let mut zip = zip::ZipArchive::new(fs::File::open(archive)?)?;
+
+                    for i in 0..zip.len() {
+                        let mut file = zip.by_index(i)?;
+
+                        let Some(relpath) = file.enclosed_name().map(Path::to_path_buf) else {
+                            continue;
+                        };
+
+                        // the config has already been written from the parsed archive config
+                        if file.is_dir()
+                            || relpath == Path::new(CONFIG_FILE)
+                            || relpath.starts_with(".setup")
+                        {
+                            continue;
+                        }
+
+                        let outpath = instance_path.join(relpath);
+                        if let Some(parent) = outpath.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+
+                        io::copy(&mut file, &mut fs::File::create(outpath)?)?;
+                    }
+
+                    Ok::<_, anyhow::Error>(())
+                })
+                .await??;
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_from_config(
+                app.instance_manager().get_default_group().await?,
+                config,
+                None,
+                initializer,
+            )
+            .await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
@@ -0,0 +1,456 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use daedalus::modded::PartialVersionInfo;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::{
+            info::{GameResolution, GameVersion, StandardVersion},
+            InstanceSettingsUpdate,
+        },
+        runtime_path::InstancePath,
+        vtask::VisualTaskId,
+    },
+    managers::{instance::InstanceVersionSource, AppInner},
+};
+
+use super::{
+    ImportScanStatus, ImportableInstance, ImporterState, InstanceImporter, InternalImportEntry,
+    InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    name: String,
+    root: PathBuf,
+    profile: LauncherProfile,
+    version: ProfileVersion,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value.filename,
+            instance_name: value.name,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ProfileVersion {
+    Release(String),
+    Latest { snapshot: bool },
+    // version json installed by a mod loader or other tool, inheriting from a vanilla version
+    Custom(String, Box<PartialVersionInfo>),
+}
+
+/// Importer for installations of the official Minecraft Launcher.
+#[derive(Debug)]
+pub struct MinecraftLauncherImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl MinecraftLauncherImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    pub async fn get_default_scan_path() -> anyhow::Result<PathBuf> {
+        let basedirs = directories::BaseDirs::new().ok_or(anyhow!("Cannot build basedirs"))?;
+
+        #[cfg(target_os = "windows")]
+        let p = basedirs.data_dir().join(".minecraft");
+        #[cfg(target_os = "macos")]
+        let p = basedirs.data_dir().join("minecraft");
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let p = basedirs.home_dir().join(".minecraft");
+
+        Ok(p)
+    }
+
+    async fn scan_profile(
+        &self,
+        root: &PathBuf,
+        key: String,
+        profile: LauncherProfile,
+    ) -> InternalImportEntry<Importable> {
+        let version_id = profile.last_version_id.clone().unwrap_or_default();
+
+        let version = match (profile.type_.as_deref(), &*version_id) {
+            (Some("latest-release"), _) | (_, "latest-release") => {
+                ProfileVersion::Latest { snapshot: false }
+            }
+            (Some("latest-snapshot"), _) | (_, "latest-snapshot") => {
+                ProfileVersion::Latest { snapshot: true }
+            }
+            _ => {
+                let version_json = root
+                    .join("versions")
+                    .join(&version_id)
+                    .join(format!("{version_id}.json"));
+
+                match tokio::fs::read_to_string(&version_json).await {
+                    // versions that have not been downloaded by the launcher yet must be vanilla
+                    Err(_) => ProfileVersion::Release(version_id.clone()),
+                    Ok(text) => match serde_json::from_str::<VersionJsonHeader>(&text) {
+                        Ok(VersionJsonHeader {
+                            inherits_from: None,
+                        }) => ProfileVersion::Release(version_id.clone()),
+                        Ok(VersionJsonHeader {
+                            inherits_from: Some(_),
+                        }) => match serde_json::from_str::<PartialVersionInfo>(&text) {
+                            Ok(partial) => {
+                                ProfileVersion::Custom(version_id.clone(), Box::new(partial))
+                            }
+                            Err(_) => {
+                                return InternalImportEntry::Invalid(InvalidImportEntry {
+                                    name: profile_name(&profile, &key),
+                                    reason:
+                                        Translation::InstanceImportMinecraftLauncherBadVersion {
+                                            version: version_id,
+                                        },
+                                })
+                            }
+                        },
+                        Err(_) => {
+                            return InternalImportEntry::Invalid(InvalidImportEntry {
+                                name: profile_name(&profile, &key),
+                                reason: Translation::InstanceImportMinecraftLauncherBadVersion {
+                                    version: version_id,
+                                },
+                            })
+                        }
+                    },
+                }
+            }
+        };
+
+        InternalImportEntry::Valid(Importable {
+            name: profile_name(&profile, &key),
+            filename: key,
+            root: root.clone(),
+            profile,
+            version,
+        })
+    }
+}
+
+fn profile_name(profile: &LauncherProfile, key: &str) -> String {
+    match (&profile.name, profile.type_.as_deref()) {
+        (Some(name), _) if !name.is_empty() => name.clone(),
+        (_, Some("latest-release")) => String::from("Latest release"),
+        (_, Some("latest-snapshot")) => String::from("Latest snapshot"),
+        _ => profile
+            .last_version_id
+            .clone()
+            .unwrap_or_else(|| key.to_string()),
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for MinecraftLauncherImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        let profiles = scan_path.join("launcher_profiles.json");
+        if !profiles.is_file() {
+            return Ok(());
+        }
+
+        let profiles = tokio::fs::read_to_string(profiles).await?;
+        let profiles = serde_json::from_str::<LauncherProfiles>(&profiles)?;
+
+        for (key, profile) in profiles.profiles {
+            let entry = self.scan_profile(&scan_path, key, profile).await;
+            self.state.write().await.push_multi(entry).await;
+            app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning minecraft launcher import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let version = match &instance.version {
+            ProfileVersion::Release(release) => GameVersion::Standard(StandardVersion {
+                release: release.clone(),
+                modloaders: HashSet::new(),
+            }),
+            ProfileVersion::Latest { snapshot } => {
+                let manifest = app.minecraft_manager().get_minecraft_manifest().await?;
+
+                GameVersion::Standard(StandardVersion {
+                    release: match snapshot {
+                        true => manifest.latest.snapshot,
+                        false => manifest.latest.release,
+                    },
+                    modloaders: HashSet::new(),
+                })
+            }
+            ProfileVersion::Custom(id, _) => GameVersion::Custom(id.clone()),
+        };
+
+        let game_dir = instance
+            .profile
+            .game_dir
+            .clone()
+            .unwrap_or_else(|| instance.root.clone());
+        let shared_game_dir = game_dir == instance.root;
+
+        let libraries_path = app
+            .settings_manager()
+            .runtime_path
+            .get_libraries()
+            .to_path();
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            let game_dir = &game_dir;
+            async move {
+                let path = instance_path.join("instance");
+
+                if game_dir.is_dir() {
+                    trace!("Copying files from minecraft launcher game directory");
+                    crate::domain::runtime_path::copy_dir_filter(game_dir, &path, |path| {
+                        let Some(first) = path.iter().next().and_then(|p| p.to_str()) else {
+                            return true;
+                        };
+
+                        // the default game directory is shared with the launcher's own files
+                        !shared_game_dir
+                            || !(matches!(
+                                first,
+                                "versions"
+                                    | "libraries"
+                                    | "assets"
+                                    | "runtime"
+                                    | "webcache2"
+                                    | "bin"
+                                    | "treatment_tags.json"
+                            ) || first.starts_with("launcher_"))
+                    })
+                    .await?;
+                }
+
+                if let ProfileVersion::Custom(_, partial) = &instance.version {
+                    tokio::fs::write(
+                        InstancePath::new(instance_path.clone()).get_custom_version_path(),
+                        serde_json::to_vec(partial)?,
+                    )
+                    .await?;
+
+                    // libraries without a download url only exist in the launcher's library folder
+                    for library in &partial.libraries {
+                        let relpath = library.name.into_path();
+                        let source = instance.root.join("libraries").join(&relpath);
+                        let target = libraries_path.join(&relpath);
+
+                        if source.is_file() && !target.exists() {
+                            if let Some(parent) = target.parent() {
+                                tokio::fs::create_dir_all(parent).await?;
+                            }
+
+                            tokio::fs::copy(source, target).await?;
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+        };
+
+        let last_played = instance
+            .profile
+            .last_used
+            .as_ref()
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            .map(|time| time.with_timezone(&Utc))
+            .filter(|time| time.timestamp() > 0);
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| instance.name.clone()),
+                None,
+                None,
+                last_played,
+                InstanceVersionSource::Version(version),
+                String::new(),
+                initializer,
+            )
+            .await?;
+
+        let (memory, extra_java_args) = match &instance.profile.java_args {
+            Some(args) => split_memory_args(args),
+            None => (None, None),
+        };
+
+        app.instance_manager()
+            .update_instance(InstanceSettingsUpdate {
+                extra_java_args: Some(extra_java_args),
+                memory: Some(memory),
+                game_resolution: Some(
+                    instance
+                        .profile
+                        .resolution
+                        .as_ref()
+                        .map(|r| GameResolution::Custom(r.width, r.height)),
+                ),
+                ..InstanceSettingsUpdate::new(id)
+            })
+            .await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+/// Splits `-Xms`/`-Xmx` out of a java argument string, returning the memory
+/// range in megabytes and the remaining arguments.
+fn split_memory_args(args: &str) -> (Option<(u16, u16)>, Option<String>) {
+    // `Some(None)` for sizes that don't fit in a u32 once converted
+    let parse_size = |size: &str| {
+        let (number, multiplier) = match size.chars().last()? {
+            'g' | 'G' => (&size[..size.len() - 1], 1024),
+            'm' | 'M' => (&size[..size.len() - 1], 1),
+            'k' | 'K' => {
+                return size[..size.len() - 1]
+                    .parse::<u32>()
+                    .ok()
+                    .map(|k| Some(k / 1024))
+            }
+            _ => return size.parse::<u32>().ok().map(|b| Some(b / (1024 * 1024))),
+        };
+
+        number
+            .parse::<u32>()
+            .ok()
+            .map(|n| n.checked_mul(multiplier))
+    };
+
+    let mut xms = None;
+    let mut xmx = None;
+    let mut rest = Vec::new();
+
+    for arg in args.split_whitespace() {
+        if let Some(size) = arg.strip_prefix("-Xmx").and_then(parse_size) {
+            xmx = size.or(xmx);
+        } else if let Some(size) = arg.strip_prefix("-Xms").and_then(parse_size) {
+            xms = size.or(xms);
+        } else {
+            rest.push(arg);
+        }
+    }
+
+    let memory = xmx.map(|xmx| {
+        let xmx = xmx.min(u16::MAX as u32) as u16;
+        let xms = xms
+            .map(|xms| xms.min(xmx as u32) as u16)
+            .unwrap_or(xmx.min(512));
+        (xms, xmx)
+    });
+
+    let rest = match rest.is_empty() {
+        true => None,
+        false => Some(rest.join(" ")),
+    };
+
+    (memory, rest)
+}
+
+#[derive(Debug, Deserialize)]
+struct LauncherProfiles {
+    profiles: HashMap<String, LauncherProfile>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LauncherProfile {
+    name: Option<String>,
+    #[serde(rename = "type")]
+    type_: Option<String>,
+    last_version_id: Option<String>,
+    game_dir: Option<PathBuf>,
+    java_args: Option<String>,
+    resolution: Option<LauncherProfileResolution>,
+    last_used: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct LauncherProfileResolution {
+    width: u16,
+    height: u16,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionJsonHeader {
+    inherits_from: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::split_memory_args;
+
+    #[test]
+    fn split_launcher_java_args() {
+        assert_eq!(
+            split_memory_args("-Xmx2G -XX:+UnlockExperimentalVMOptions -XX:+UseG1GC"),
+            (
+                Some((512, 2048)),
+                Some(String::from(
+                    "-XX:+UnlockExperimentalVMOptions -XX:+UseG1GC"
+                ))
+            )
+        );
+
+        assert_eq!(
+            split_memory_args("-Xms1024m -Xmx4096M"),
+            (Some((1024, 4096)), None)
+        );
+
+        assert_eq!(
+            split_memory_args("-Dfoo=bar"),
+            (None, Some(String::from("-Dfoo=bar")))
+        );
+
+        assert_eq!(
+            split_memory_args("-Xmx4G -Xmx8388608G -Xms4194304g"),
+            (Some((512, 4096)), None)
+        );
+    }
+}
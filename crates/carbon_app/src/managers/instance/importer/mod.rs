@@ -9,21 +9,40 @@ use tracing::{debug, trace};
 use crate::{
     api::keys::instance::*,
     api::translation::Translation,
-    domain::vtask::VisualTaskId,
-    managers::{modplatforms::curseforge::CurseForge, AppInner, ManagerRef},
+    domain::{
+        instance::info::{ModLoaderType, StandardVersion},
+        modplatforms::curseforge::manifest::{Minecraft, ModLoaders},
+        vtask::VisualTaskId,
+    },
+    managers::{
+        modplatforms::curseforge::{convert_cf_version_to_standard_version, CurseForge},
+        AppInner, ManagerRef,
+    },
 };
 
 use self::{
-    curseforge::CurseforgeImporter, curseforge_archive::CurseforgeArchiveImporter,
-    legacy_gdlauncher::LegacyGDLauncherImporter, modrinth_archive::ModrinthArchiveImporter,
+    atlauncher::ATLauncherImporter, curseforge::CurseforgeImporter,
+    curseforge_archive::CurseforgeArchiveImporter, ftb::FTBImporter,
+    gdlinstance::GDLInstanceImporter, legacy_gdlauncher::LegacyGDLauncherImporter,
+    minecraft_launcher::MinecraftLauncherImporter, modrinth::ModrinthImporter,
+    modrinth_archive::ModrinthArchiveImporter, multimc::MultiMCImporter, packwiz::PackwizImporter,
+    technic::TechnicImporter,
 };
 
 use super::{export::InstanceExportManager, InstanceManager};
 
+mod atlauncher;
 mod curseforge;
 mod curseforge_archive;
+mod ftb;
+mod gdlinstance;
 mod legacy_gdlauncher;
+mod minecraft_launcher;
+mod modrinth;
 mod modrinth_archive;
+mod multimc;
+mod packwiz;
+mod technic;
 
 #[derive(Debug)]
 pub struct InstanceImportManager {
@@ -176,6 +195,9 @@ pub enum Entity {
     FTB,
     MultiMC,
     PrismLauncher,
+    MinecraftLauncher,
+    GDLInstance,
+    Packwiz,
 }
 
 impl Entity {
@@ -191,17 +213,29 @@ impl Entity {
             Self::FTB => SelectionType::Directory,
             Self::MultiMC => SelectionType::Directory,
             Self::PrismLauncher => SelectionType::Directory,
+            Self::MinecraftLauncher => SelectionType::Directory,
+            Self::GDLInstance => SelectionType::File,
+            Self::Packwiz => SelectionType::File,
         }
     }
 
     pub fn list() -> Vec<(Self, bool, SelectionType)> {
         use strum::IntoEnumIterator;
 
-        const SUPPORT: [Entity; 4] = [
+        const SUPPORT: [Entity; 13] = [
             Entity::LegacyGDLauncher,
             Entity::CurseForgeZip,
             Entity::MRPack,
             Entity::CurseForge,
+            Entity::MultiMC,
+            Entity::PrismLauncher,
+            Entity::ATLauncher,
+            Entity::Technic,
+            Entity::FTB,
+            Entity::Modrinth,
+            Entity::MinecraftLauncher,
+            Entity::GDLInstance,
+            Entity::Packwiz,
         ];
 
         Self::iter()
@@ -215,7 +249,14 @@ impl Entity {
             Self::CurseForgeZip => Arc::new(CurseforgeArchiveImporter::new()),
             Self::MRPack => Arc::new(ModrinthArchiveImporter::new()),
             Self::CurseForge => Arc::new(CurseforgeImporter::new()),
-            _ => todo!(),
+            Self::MultiMC | Self::PrismLauncher => Arc::new(MultiMCImporter::new()),
+            Self::ATLauncher => Arc::new(ATLauncherImporter::new()),
+            Self::Technic => Arc::new(TechnicImporter::new()),
+            Self::FTB => Arc::new(FTBImporter::new()),
+            Self::Modrinth => Arc::new(ModrinthImporter::new()),
+            Self::MinecraftLauncher => Arc::new(MinecraftLauncherImporter::new()),
+            Self::GDLInstance => Arc::new(GDLInstanceImporter::new()),
+            Self::Packwiz => Arc::new(PackwizImporter::new()),
         }
     }
 
@@ -225,11 +266,47 @@ impl Entity {
                 Some(LegacyGDLauncherImporter::get_default_scan_path().await?)
             }
             Self::CurseForge => Some(CurseforgeImporter::get_default_scan_path().await?),
+            Self::PrismLauncher => Some(MultiMCImporter::get_default_prism_scan_path().await?),
+            Self::Technic => Some(TechnicImporter::get_default_scan_path().await?),
+            Self::FTB => Some(FTBImporter::get_default_scan_path().await?),
+            Self::Modrinth => Some(ModrinthImporter::get_default_scan_path().await?),
+            Self::MinecraftLauncher => {
+                Some(MinecraftLauncherImporter::get_default_scan_path().await?)
+            }
             _ => None,
         })
     }
 }
 
+/// Resolves a minecraft release and the loader versions recorded by another
+/// launcher into a version known by the meta server.
+async fn resolve_standard_version(
+    app: &Arc<AppInner>,
+    release: String,
+    modloaders: Vec<(ModLoaderType, String)>,
+) -> anyhow::Result<StandardVersion> {
+    let dummy_string = daedalus::BRANDING
+        .get_or_init(daedalus::Branding::default)
+        .dummy_replace_string
+        .clone();
+
+    convert_cf_version_to_standard_version(
+        app.clone(),
+        Minecraft {
+            version: release,
+            mod_loaders: modloaders
+                .into_iter()
+                .map(|(type_, version)| ModLoaders {
+                    id: format!("{}-{version}", type_.to_string()),
+                    primary: true,
+                })
+                .collect(),
+        },
+        dummy_string,
+    )
+    .await
+}
+
 #[derive(Debug)]
 pub struct ImportableInstance {
     pub filename: String,
@@ -0,0 +1,395 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::{
+            info::{GameResolution, GameVersion, ModLoaderType, Modpack, ModrinthModpack},
+            InstanceSettingsUpdate,
+        },
+        vtask::VisualTaskId,
+    },
+    managers::{instance::InstanceVersionSource, AppInner},
+};
+
+use super::{
+    resolve_standard_version, ImportScanStatus, ImportableInstance, ImporterState,
+    InstanceImporter, InternalImportEntry, InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    path: PathBuf,
+    profile: MrProfile,
+    modloader: Option<(ModLoaderType, String)>,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value.filename,
+            instance_name: value.profile.metadata.name,
+        }
+    }
+}
+
+/// Importer for profiles created by the Modrinth App.
+#[derive(Debug)]
+pub struct ModrinthImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl ModrinthImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    pub async fn get_default_scan_path() -> anyhow::Result<PathBuf> {
+        let basedirs = directories::BaseDirs::new().ok_or(anyhow!("Cannot build basedirs"))?;
+
+        Ok(basedirs
+            .data_dir()
+            .join("com.modrinth.theseus")
+            .join("profiles"))
+    }
+
+    async fn scan_instance(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        let profile = path.join("profile.json");
+        if !profile.is_file() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .expect("filename cannot be empty")
+            .to_string_lossy()
+            .to_string();
+
+        let profile = tokio::fs::read_to_string(profile).await?;
+        let Ok(profile) = serde_json::from_str::<MrProfile>(&profile) else {
+            return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportModrinthBadProfile,
+            })));
+        };
+
+        let metadata = &profile.metadata;
+        let modloader = match (&*metadata.loader, &metadata.loader_version) {
+            ("vanilla", _) => None,
+            (loader, Some(version)) => {
+                let type_ = match loader {
+                    "forge" => ModLoaderType::Forge,
+                    "neoforge" => ModLoaderType::Neoforge,
+                    "fabric" => ModLoaderType::Fabric,
+                    "quilt" => ModLoaderType::Quilt,
+                    _ => {
+                        return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                            name: metadata.name.clone(),
+                            reason: Translation::InstanceImportModrinthUnsupportedLoader {
+                                loader: loader.to_string(),
+                            },
+                        })))
+                    }
+                };
+
+                Some((type_, version.id.clone()))
+            }
+            (_, None) => {
+                return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                    name: metadata.name.clone(),
+                    reason: Translation::InstanceImportModrinthBadProfile,
+                })))
+            }
+        };
+
+        Ok(Some(InternalImportEntry::Valid(Importable {
+            filename,
+            path,
+            profile,
+            modloader,
+        })))
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for ModrinthImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        if scan_path.is_dir() {
+            if let Ok(Some(entry)) = self.scan_instance(scan_path.clone()).await {
+                self.state.write().await.set_single(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                return Ok(());
+            }
+
+            let Ok(mut dir) = tokio::fs::read_dir(&scan_path).await else {
+                return Ok(());
+            };
+
+            while let Some(path) = dir.next_entry().await? {
+                if path.metadata().await?.is_dir() {
+                    if let Ok(Some(entry)) = self.scan_instance(path.path()).await {
+                        self.state.write().await.push_multi(entry).await;
+                        app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning modrinth app import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let metadata = &instance.profile.metadata;
+
+        let version = GameVersion::Standard(
+            resolve_standard_version(
+                app,
+                metadata.game_version.clone(),
+                instance.modloader.clone().into_iter().collect(),
+            )
+            .await?,
+        );
+
+        // linked profiles keep receiving pack updates as a modrinth modpack
+        let instance_version_source = match &metadata.linked_data {
+            Some(MrLinkedData {
+                project_id: Some(project_id),
+                version_id: Some(version_id),
+                locked,
+            }) => InstanceVersionSource::ModpackWithKnownVersion(
+                version,
+                Modpack::Modrinth(ModrinthModpack {
+                    project_id: project_id.clone(),
+                    version_id: version_id.clone(),
+                }),
+                locked.unwrap_or(false),
+            ),
+            _ => InstanceVersionSource::Version(version),
+        };
+
+        let icon = match &metadata.icon {
+            Some(icon) => app
+                .instance_manager()
+                .load_icon(PathBuf::from(icon))
+                .await
+                .ok(),
+            None => None,
+        };
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            async move {
+                let path = instance_path.join("instance");
+
+                tokio::fs::create_dir_all(instance_path.join(".setup").join("modpack-complete"))
+                    .await?;
+
+                trace!("Copying files from modrinth app profile");
+                crate::domain::runtime_path::copy_dir_filter(&instance.path, &path, |path| {
+                    !matches!(path.to_str(), Some("profile.json" | "natives"))
+                })
+                .await?;
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| metadata.name.clone()),
+                icon,
+                None,
+                metadata.last_played,
+                instance_version_source,
+                String::new(),
+                initializer,
+            )
+            .await?;
+
+        let profile = &instance.profile;
+        let hooks = profile.hooks.clone().unwrap_or_default();
+        app.instance_manager()
+            .update_instance(InstanceSettingsUpdate {
+                extra_java_args: Some(
+                    profile
+                        .java
+                        .as_ref()
+                        .and_then(|java| java.extra_arguments.as_ref())
+                        .map(|args| args.join(" "))
+                        .filter(|args| !args.is_empty()),
+                ),
+                memory: profile.memory.as_ref().map(|memory| Some(memory.range())),
+                game_resolution: profile
+                    .resolution
+                    .map(|(w, h)| Some(GameResolution::Custom(w, h))),
+                pre_launch_hook: Some(hooks.pre_launch),
+                post_exit_hook: Some(hooks.post_exit),
+                wrapper_command: Some(hooks.wrapper),
+                ..InstanceSettingsUpdate::new(id)
+            })
+            .await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MrProfile {
+    metadata: MrProfileMetadata,
+    java: Option<MrJavaSettings>,
+    memory: Option<MrMemorySettings>,
+    resolution: Option<(u16, u16)>,
+    hooks: Option<MrHooks>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MrProfileMetadata {
+    name: String,
+    icon: Option<String>,
+    game_version: String,
+    loader: String,
+    loader_version: Option<MrLoaderVersion>,
+    linked_data: Option<MrLinkedData>,
+    last_played: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MrLoaderVersion {
+    id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MrLinkedData {
+    project_id: Option<String>,
+    version_id: Option<String>,
+    locked: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MrJavaSettings {
+    extra_arguments: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MrMemorySettings {
+    /// Only written by older versions of the app
+    minimum: Option<u16>,
+    maximum: u16,
+}
+
+impl MrMemorySettings {
+    fn range(&self) -> (u16, u16) {
+        let minimum = self.minimum.unwrap_or(self.maximum).min(self.maximum);
+        (minimum, self.maximum)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct MrHooks {
+    pre_launch: Option<String>,
+    wrapper: Option<String>,
+    post_exit: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MrLinkedData, MrProfile};
+
+    const PROFILE: &str = r#"{
+        "uuid": "0b5a1a3c-2d1e-4f51-a7d4-6bbd33c6e0a2",
+        "install_stage": "installed",
+        "path": "Fabulously Optimized",
+        "metadata": {
+            "name": "Fabulously Optimized",
+            "icon": null,
+            "groups": [],
+            "game_version": "1.20.1",
+            "loader": "fabric",
+            "loader_version": { "id": "0.15.7", "url": "", "stable": true },
+            "linked_data": {
+                "project_id": "1KVo5zza",
+                "version_id": "xFcz3mSf",
+                "locked": true
+            },
+            "date_created": "2024-01-01T00:00:00Z",
+            "date_modified": "2024-01-02T00:00:00Z",
+            "last_played": null
+        },
+        "java": { "extra_arguments": ["-XX:+UseG1GC"] },
+        "memory": { "maximum": 4096 },
+        "resolution": [1280, 720],
+        "hooks": { "pre_launch": null, "wrapper": "gamemoderun", "post_exit": null },
+        "projects": {}
+    }"#;
+
+    #[test]
+    fn parse_profile() {
+        let profile = serde_json::from_str::<MrProfile>(PROFILE).unwrap();
+        let metadata = &profile.metadata;
+
+        assert_eq!(metadata.loader, "fabric");
+        assert_eq!(metadata.loader_version.as_ref().unwrap().id, "0.15.7");
+        assert!(matches!(
+            &metadata.linked_data,
+            Some(MrLinkedData {
+                project_id: Some(project_id),
+                version_id: Some(version_id),
+                locked: Some(true),
+            }) if project_id == "1KVo5zza" && version_id == "xFcz3mSf"
+        ));
+        assert_eq!(profile.resolution, Some((1280, 720)));
+        assert_eq!(
+            profile.hooks.unwrap().wrapper.as_deref(),
+            Some("gamemoderun")
+        );
+    }
+
+    #[test]
+    fn profile_memory() {
+        let profile = serde_json::from_str::<MrProfile>(PROFILE).unwrap();
+        assert_eq!(profile.memory.unwrap().range(), (4096, 4096));
+
+        let old_profile =
+            PROFILE.replace(r#""maximum": 4096"#, r#""minimum": 1024, "maximum": 4096"#);
+        let old_profile = serde_json::from_str::<MrProfile>(&old_profile).unwrap();
+        assert_eq!(old_profile.memory.unwrap().range(), (1024, 4096));
+    }
+}
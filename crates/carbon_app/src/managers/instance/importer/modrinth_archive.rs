@@ -280,7 +280,7 @@ impl InstanceImporter for ModrinthArchiveImporter {
             .await?;
 
         app.instance_manager()
-            .prepare_game(id, None, None, true)
+            .prepare_game(id, None, None, None, true)
             .await
             .map(|r| r.1)
     }
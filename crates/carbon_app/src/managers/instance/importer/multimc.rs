@@ -0,0 +1,535 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::{
+            info::{
+                CurseforgeModpack, GameResolution, GameVersion, ModLoaderType, Modpack,
+                ModrinthModpack,
+            },
+            InstanceSettingsUpdate,
+        },
+        vtask::VisualTaskId,
+    },
+    managers::{instance::InstanceVersionSource, AppInner},
+};
+
+use super::{
+    resolve_standard_version, ImportScanStatus, ImportableInstance, ImporterState,
+    InstanceImporter, InternalImportEntry, InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    path: PathBuf,
+    config: MmcInstanceConfig,
+    pack: MmcPackVersion,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            instance_name: value.config.name.unwrap_or_else(|| value.filename.clone()),
+            filename: value.filename,
+        }
+    }
+}
+
+/// Importer for MultiMC and its forks (Prism Launcher, PolyMC), which share
+/// the same on-disk instance layout.
+#[derive(Debug)]
+pub struct MultiMCImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl MultiMCImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    // MultiMC is distributed as a portable application, so only prism has a default path
+    pub async fn get_default_prism_scan_path() -> anyhow::Result<PathBuf> {
+        let basedirs = directories::BaseDirs::new().ok_or(anyhow!("Cannot build basedirs"))?;
+
+        Ok(basedirs.data_dir().join("PrismLauncher").join("instances"))
+    }
+
+    async fn scan_instance(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        let config = path.join("instance.cfg");
+        let pack = path.join("mmc-pack.json");
+        if !config.is_file() || !pack.is_file() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .expect("filename cannot be empty")
+            .to_string_lossy()
+            .to_string();
+
+        let config = tokio::fs::read_to_string(config).await;
+        let Ok(config) = config else {
+            return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportMmcBadConfigFile,
+            })));
+        };
+
+        let config = MmcInstanceConfig::from_cfg(&parse_instance_cfg(&config));
+
+        let pack = tokio::fs::read_to_string(pack)
+            .await
+            .map_err(|_| Translation::InstanceImportMmcBadPackFile)
+            .and_then(|pack| MmcPackVersion::parse(&pack));
+
+        match pack {
+            Ok(pack) => Ok(Some(InternalImportEntry::Valid(Importable {
+                filename,
+                path,
+                config,
+                pack,
+            }))),
+            Err(reason) => Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: config.name.unwrap_or(filename),
+                reason,
+            }))),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for MultiMCImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        if scan_path.is_dir() {
+            if let Ok(Some(entry)) = self.scan_instance(scan_path.clone()).await {
+                self.state.write().await.set_single(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                return Ok(());
+            }
+
+            let Ok(mut dir) = tokio::fs::read_dir(&scan_path).await else {
+                return Ok(());
+            };
+
+            while let Some(path) = dir.next_entry().await? {
+                if path.metadata().await?.is_dir() {
+                    if let Ok(Some(entry)) = self.scan_instance(path.path()).await {
+                        self.state.write().await.push_multi(entry).await;
+                        app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning multimc import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let version = GameVersion::Standard(
+            resolve_standard_version(
+                app,
+                instance.pack.release.clone(),
+                instance.pack.modloaders.clone(),
+            )
+            .await?,
+        );
+
+        let instance_version_source = match &instance.config.managed_pack {
+            Some(modpack) => {
+                InstanceVersionSource::ModpackWithKnownVersion(version, modpack.clone(), false)
+            }
+            None => InstanceVersionSource::Version(version),
+        };
+
+        // icons are stored next to the instances folder, keyed by their file stem
+        let icon = 'icon: {
+            let Some(icon_key) = &instance.config.icon_key else {
+                break 'icon None;
+            };
+
+            let Some(icons_dir) = instance.path.parent().and_then(|p| p.parent()) else {
+                break 'icon None;
+            };
+
+            let Ok(mut dir) = tokio::fs::read_dir(icons_dir.join("icons")).await else {
+                break 'icon None;
+            };
+
+            while let Ok(Some(entry)) = dir.next_entry().await {
+                let path = entry.path();
+                if path.file_stem().map(|s| s.to_string_lossy()).as_deref()
+                    == Some(icon_key.as_str())
+                {
+                    break 'icon app.instance_manager().load_icon(path).await.ok();
+                }
+            }
+
+            None
+        };
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            async move {
+                tokio::fs::create_dir_all(instance_path.join(".setup").join("modpack-complete"))
+                    .await?;
+
+                // newer prism versions dropped the leading dot
+                let game_dir = [".minecraft", "minecraft"]
+                    .into_iter()
+                    .map(|dir| instance.path.join(dir))
+                    .find(|dir| dir.is_dir());
+
+                if let Some(game_dir) = game_dir {
+                    trace!("Copying files from multimc instance");
+                    crate::domain::runtime_path::copy_dir_filter(
+                        &game_dir,
+                        &instance_path.join("instance"),
+                        |path| !matches!(path.to_str(), Some("natives")),
+                    )
+                    .await?;
+                }
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| {
+                    instance
+                        .config
+                        .name
+                        .clone()
+                        .unwrap_or_else(|| instance.filename.clone())
+                }),
+                icon,
+                instance.config.seconds_played,
+                instance.config.last_played,
+                instance_version_source,
+                instance.config.notes.clone(),
+                initializer,
+            )
+            .await?;
+
+        app.instance_manager()
+            .update_instance(InstanceSettingsUpdate {
+                extra_java_args: Some(instance.config.jvm_args.clone()),
+                memory: Some(instance.config.memory),
+                game_resolution: Some(
+                    instance
+                        .config
+                        .resolution
+                        .map(|(w, h)| GameResolution::Custom(w, h)),
+                ),
+                ..InstanceSettingsUpdate::new(id)
+            })
+            .await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+/// Parses the QSettings ini format used by `instance.cfg`.
+///
+/// Section headers are ignored, as MultiMC writes every key at the top level
+/// and prism nests them all under `[General]`.
+fn parse_instance_cfg(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(['[', '#', ';']))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), unquote_cfg_value(value.trim())))
+        .collect()
+}
+
+fn unquote_cfg_value(value: &str) -> String {
+    let Some(value) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    else {
+        return value.to_string();
+    };
+
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some('r') => out.push('\r'),
+                Some(c) => out.push(c),
+                None => {}
+            },
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct MmcInstanceConfig {
+    name: Option<String>,
+    notes: String,
+    jvm_args: Option<String>,
+    memory: Option<(u16, u16)>,
+    resolution: Option<(u16, u16)>,
+    seconds_played: Option<u32>,
+    last_played: Option<DateTime<Utc>>,
+    icon_key: Option<String>,
+    managed_pack: Option<Modpack>,
+}
+
+impl MmcInstanceConfig {
+    fn from_cfg(cfg: &HashMap<String, String>) -> Self {
+        let get = |key: &str| cfg.get(key).filter(|v| !v.is_empty()).cloned();
+        let flag = |key: &str| cfg.get(key).map(|v| v == "true").unwrap_or(false);
+        let number = |key: &str| cfg.get(key).and_then(|v| v.parse::<u16>().ok());
+
+        // settings are only applied by multimc when their override flag is set
+        let jvm_args = match flag("OverrideJavaArgs") {
+            true => get("JvmArgs"),
+            false => None,
+        };
+
+        let memory = match flag("OverrideMemory") {
+            true => number("MinMemAlloc").zip(number("MaxMemAlloc")),
+            false => None,
+        };
+
+        let resolution = match flag("OverrideWindow") && !flag("LaunchMaximized") {
+            true => number("MinecraftWinWidth").zip(number("MinecraftWinHeight")),
+            false => None,
+        };
+
+        let last_played = cfg
+            .get("lastLaunchTime")
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .and_then(NaiveDateTime::from_timestamp_millis)
+            .map(|time| DateTime::<Utc>::from_utc(time, Utc));
+
+        let managed_pack = match flag("ManagedPack") {
+            true => match (
+                get("ManagedPackType").as_deref(),
+                get("ManagedPackID"),
+                get("ManagedPackVersionID"),
+            ) {
+                (Some("flame"), Some(project_id), Some(file_id)) => {
+                    project_id.parse().ok().zip(file_id.parse().ok()).map(
+                        |(project_id, file_id)| {
+                            Modpack::Curseforge(CurseforgeModpack {
+                                project_id,
+                                file_id,
+                            })
+                        },
+                    )
+                }
+                (Some("modrinth"), Some(project_id), Some(version_id)) => {
+                    Some(Modpack::Modrinth(ModrinthModpack {
+                        project_id,
+                        version_id,
+                    }))
+                }
+                _ => None,
+            },
+            false => None,
+        };
+
+        Self {
+            name: get("name"),
+            notes: get("notes").unwrap_or_default(),
+            jvm_args,
+            memory,
+            resolution,
+            seconds_played: cfg.get("totalTimePlayed").and_then(|v| v.parse().ok()),
+            last_played,
+            icon_key: get("iconKey").filter(|key| key != "default"),
+            managed_pack,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MmcPack {
+    components: Vec<MmcPackComponent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MmcPackComponent {
+    uid: String,
+    version: Option<String>,
+    cached_version: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct MmcPackVersion {
+    release: String,
+    modloaders: Vec<(ModLoaderType, String)>,
+}
+
+impl MmcPackVersion {
+    fn parse(text: &str) -> Result<Self, Translation> {
+        let pack = serde_json::from_str::<MmcPack>(text)
+            .map_err(|_| Translation::InstanceImportMmcBadPackFile)?;
+
+        let mut release = None;
+        let mut modloaders = Vec::new();
+
+        for component in pack.components {
+            let version = component.version.or(component.cached_version);
+
+            let loader = match &*component.uid {
+                "net.minecraft" => {
+                    release = version;
+                    continue;
+                }
+                // implied by the minecraft version or loader
+                "org.lwjgl" | "org.lwjgl3" | "net.fabricmc.intermediary" | "org.quiltmc.hashed" => {
+                    continue
+                }
+                "net.minecraftforge" => ModLoaderType::Forge,
+                "net.neoforged" => ModLoaderType::Neoforge,
+                "net.fabricmc.fabric-loader" => ModLoaderType::Fabric,
+                "org.quiltmc.quilt-loader" => ModLoaderType::Quilt,
+                _ => {
+                    return Err(Translation::InstanceImportMmcUnsupportedComponent {
+                        uid: component.uid,
+                    })
+                }
+            };
+
+            let version = version.ok_or(Translation::InstanceImportMmcBadPackFile)?;
+            modloaders.push((loader, version));
+        }
+
+        Ok(Self {
+            release: release.ok_or(Translation::InstanceImportMmcBadPackFile)?,
+            modloaders,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::api::translation::Translation;
+    use crate::domain::instance::info::ModLoaderType;
+
+    use super::{parse_instance_cfg, MmcInstanceConfig, MmcPackVersion};
+
+    #[test]
+    fn parse_prism_instance_cfg() {
+        let cfg = parse_instance_cfg(
+            r#"[General]
+ConfigVersion=1.2
+iconKey=default
+name=My Pack
+notes="first line\nsecond \"quoted\" line"
+OverrideJavaArgs=true
+JvmArgs=-XX:+UseG1GC
+OverrideMemory=true
+MinMemAlloc=512
+MaxMemAlloc=4096
+OverrideWindow=false
+MinecraftWinWidth=1280
+MinecraftWinHeight=720
+totalTimePlayed=3600
+lastLaunchTime=1700000000000
+"#,
+        );
+
+        let config = MmcInstanceConfig::from_cfg(&cfg);
+
+        assert_eq!(config.name.as_deref(), Some("My Pack"));
+        assert_eq!(config.notes, "first line\nsecond \"quoted\" line");
+        assert_eq!(config.jvm_args.as_deref(), Some("-XX:+UseG1GC"));
+        assert_eq!(config.memory, Some((512, 4096)));
+        assert_eq!(config.resolution, None);
+        assert_eq!(config.seconds_played, Some(3600));
+        assert_eq!(
+            config.last_played.map(|t| t.timestamp_millis()),
+            Some(1700000000000)
+        );
+        assert_eq!(config.icon_key, None);
+        assert_eq!(config.managed_pack, None);
+    }
+
+    #[test]
+    fn parse_mmc_pack() {
+        let pack = MmcPackVersion::parse(
+            r#"{
+    "components": [
+        { "uid": "org.lwjgl3", "version": "3.3.1", "dependencyOnly": true },
+        { "uid": "net.minecraft", "version": "1.20.1", "important": true },
+        { "uid": "net.fabricmc.intermediary", "version": "1.20.1", "dependencyOnly": true },
+        { "uid": "net.fabricmc.fabric-loader", "cachedVersion": "0.14.22" }
+    ],
+    "formatVersion": 1
+}"#,
+        );
+
+        assert_eq!(
+            pack,
+            Ok(MmcPackVersion {
+                release: String::from("1.20.1"),
+                modloaders: vec![(ModLoaderType::Fabric, String::from("0.14.22"))],
+            })
+        );
+
+        let jarmod = MmcPackVersion::parse(
+            r#"{ "components": [
+                { "uid": "net.minecraft", "version": "1.7.10" },
+                { "uid": "org.multimc.jarmod.abc", "version": "1" }
+            ] }"#,
+        );
+
+        assert_eq!(
+            jarmod,
+            Err(Translation::InstanceImportMmcUnsupportedComponent {
+                uid: String::from("org.multimc.jarmod.abc")
+            })
+        );
+    }
+}
@@ -0,0 +1,746 @@
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, bail};
+use carbon_net::{Checksum, Downloadable};
+use md5::{Digest, Md5};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use tokio::sync::RwLock;
+use tracing::trace;
+use url::Url;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::info::{GameVersion, ModLoaderType},
+        modplatforms::{
+            curseforge::{filters::ModFileParameters, HashAlgo},
+            packwiz::{IndexToml, ModToml, PackToml},
+        },
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::{
+            installer::{CurseforgeModInstaller, Installer, IntoInstaller, ModrinthModInstaller},
+            InstanceVersionSource,
+        },
+        AppInner,
+    },
+};
+
+use super::{
+    resolve_standard_version, ImportScanStatus, ImportableInstance, ImporterState,
+    InstanceImporter, InternalImportEntry, InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    source: PackSource,
+    pack: PackToml,
+    modloaders: Vec<(ModLoaderType, String)>,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value.filename,
+            instance_name: value.pack.name,
+        }
+    }
+}
+
+/// Location of a pack, files of the pack are resolved relative to its `pack.toml`.
+#[derive(Debug, Clone)]
+enum PackSource {
+    Local(PathBuf),
+    Remote(Url),
+}
+
+impl PackSource {
+    fn parse(scan_path: &Path) -> Option<Self> {
+        if let Some(url) = scan_path
+            .to_str()
+            .and_then(|path| Url::parse(path).ok())
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+        {
+            return Some(Self::Remote(url));
+        }
+
+        if scan_path.is_file() {
+            Some(Self::Local(scan_path.to_path_buf()))
+        } else if scan_path.join("pack.toml").is_file() {
+            Some(Self::Local(scan_path.join("pack.toml")))
+        } else {
+            None
+        }
+    }
+
+    fn pack_file(&self) -> String {
+        let name = match self {
+            Self::Local(path) => path.file_name().and_then(|name| name.to_str()),
+            Self::Remote(url) => url
+                .path_segments()
+                .and_then(|mut segments| segments.next_back()),
+        };
+
+        name.filter(|name| !name.is_empty())
+            .unwrap_or("pack.toml")
+            .to_string()
+    }
+
+    fn url(&self, relpath: &str) -> anyhow::Result<Url> {
+        match self {
+            Self::Local(_) => bail!("local packs have no url"),
+            Self::Remote(url) => Ok(url.join(relpath)?),
+        }
+    }
+
+    async fn read(&self, app: &Arc<AppInner>, relpath: &str) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Self::Local(path) => {
+                let dir = path.parent().unwrap_or(Path::new(""));
+                Ok(tokio::fs::read(dir.join(relpath)).await?)
+            }
+            Self::Remote(_) => Ok(app
+                .reqwest_client
+                .get(self.url(relpath)?)
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?
+                .to_vec()),
+        }
+    }
+}
+
+/// Importer for [packwiz](https://packwiz.infra.link) packs, either a local
+/// folder containing `pack.toml` or the `http(s)` url of a `pack.toml`.
+#[derive(Debug)]
+pub struct PackwizImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl PackwizImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    async fn scan_pack(
+        &self,
+        app: &Arc<AppInner>,
+        source: PackSource,
+    ) -> anyhow::Result<InternalImportEntry<Importable>> {
+        let filename = source.pack_file();
+
+        let data = source.read(app, &filename).await?;
+        let Ok(pack) = std::str::from_utf8(&data)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| Ok(toml::from_str::<PackToml>(data)?))
+        else {
+            return Ok(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportPackwizMalformedPack,
+            }));
+        };
+
+        if !pack.versions.contains_key("minecraft") {
+            return Ok(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: pack.name,
+                reason: Translation::InstanceImportPackwizMalformedPack,
+            }));
+        }
+
+        let modloaders = match pack_modloaders(&pack) {
+            Ok(modloaders) => modloaders,
+            Err(loader) => {
+                return Ok(InternalImportEntry::Invalid(InvalidImportEntry {
+                    name: pack.name,
+                    reason: Translation::InstanceImportPackwizUnsupportedLoader { loader },
+                }))
+            }
+        };
+
+        Ok(InternalImportEntry::Valid(Importable {
+            filename,
+            source,
+            pack,
+            modloaders,
+        }))
+    }
+}
+
+/// Maps the loader versions of a pack, returning the first unsupported loader on failure.
+fn pack_modloaders(pack: &PackToml) -> Result<Vec<(ModLoaderType, String)>, String> {
+    pack.versions
+        .iter()
+        .filter(|(loader, _)| *loader != "minecraft")
+        .map(|(loader, version)| {
+            let type_ = match &**loader {
+                "forge" => ModLoaderType::Forge,
+                "neoforge" => ModLoaderType::Neoforge,
+                "fabric" => ModLoaderType::Fabric,
+                "quilt" => ModLoaderType::Quilt,
+                _ => return Err(loader.clone()),
+            };
+
+            Ok((type_, version.clone()))
+        })
+        .collect()
+}
+
+/// Checks `data` against a packwiz hash, failing on mismatches and unknown hash formats.
+fn verify_hash(data: &[u8], hash_format: &str, hash: &str, file: &str) -> anyhow::Result<()> {
+    let actual = match hash_format {
+        "sha1" => hex::encode(Sha1::digest(data)),
+        "sha256" => hex::encode(Sha256::digest(data)),
+        "sha512" => hex::encode(Sha512::digest(data)),
+        "md5" => hex::encode(Md5::digest(data)),
+        // curseforge fingerprints skip whitespace and are written as decimal
+        "murmur2" => {
+            let data = data
+                .iter()
+                .copied()
+                .filter(|&x| x != 9 && x != 10 && x != 13 && x != 32)
+                .collect::<Vec<_>>();
+
+            murmurhash32::murmurhash2(&data).to_string()
+        }
+        _ => bail!("unsupported hash format {hash_format} for packwiz file {file}"),
+    };
+
+    if !actual.eq_ignore_ascii_case(hash) {
+        bail!("hash mismatch for packwiz file {file}: expected {hash}, got {actual}");
+    }
+
+    Ok(())
+}
+
+/// Checks the hashes a platform publishes for a file against the hash pinned by a metafile.
+///
+/// Returns false if the platform has no hash in the metafile's format.
+fn verify_platform_hash(
+    platform_hashes: &[(&str, String)],
+    hash_format: &str,
+    hash: &str,
+    file: &str,
+) -> anyhow::Result<bool> {
+    let Some((_, actual)) = platform_hashes
+        .iter()
+        .find(|(format, _)| *format == hash_format)
+    else {
+        return Ok(false);
+    };
+
+    if !actual.eq_ignore_ascii_case(hash) {
+        bail!("hash mismatch for packwiz file {file}: expected {hash}, got {actual}");
+    }
+
+    Ok(true)
+}
+
+/// Checksum that can be verified while downloading, if the format is supported by carbon_net.
+fn download_checksum(hash_format: &str, hash: &str) -> Option<Checksum> {
+    match hash_format {
+        "sha1" => Some(Checksum::Sha1(hash.to_lowercase())),
+        "sha256" => Some(Checksum::Sha256(hash.to_lowercase())),
+        "md5" => Some(Checksum::Md5(hash.to_lowercase())),
+        _ => None,
+    }
+}
+
+/// Joins a path from `index.toml` onto the folder of the index, rejecting paths
+/// that would escape the pack.
+fn index_relative(index_file: &str, file: &str) -> anyhow::Result<String> {
+    if !Path::new(file)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        bail!("packwiz file {file} is outside of the pack");
+    }
+
+    Ok(match index_file.rsplit_once('/') {
+        Some((dir, _)) => format!("{dir}/{file}"),
+        None => file.to_string(),
+    })
+}
+
+#[derive(Debug)]
+struct PackFile {
+    target: String,
+    hash_format: String,
+    hash: String,
+}
+
+#[derive(Default)]
+struct ResolvedPack {
+    // plain files of a local pack, already verified
+    local: Vec<(String, Vec<u8>)>,
+    downloads: Vec<(String, PackFile)>,
+    installers: Vec<Installer>,
+}
+
+/// Reads the index and metafiles of a pack, verifying them against their hashes.
+async fn resolve_pack(app: &Arc<AppInner>, instance: &Importable) -> anyhow::Result<ResolvedPack> {
+    let index_ref = &instance.pack.index;
+    let index = instance.source.read(app, &index_ref.file).await?;
+    verify_hash(
+        &index,
+        &index_ref.hash_format,
+        &index_ref.hash,
+        &index_ref.file,
+    )?;
+    let index = toml::from_str::<IndexToml>(std::str::from_utf8(&index)?)?;
+
+    let mut resolved = ResolvedPack::default();
+
+    for file in index.files {
+        let source = index_relative(&index_ref.file, &file.file)?;
+        let hash_format = file.hash_format.as_ref().unwrap_or(&index.hash_format);
+
+        if !file.metafile {
+            let target = index_relative("", file.alias.as_ref().unwrap_or(&file.file))?;
+            match &instance.source {
+                PackSource::Local(_) => {
+                    let data = instance.source.read(app, &source).await?;
+                    verify_hash(&data, hash_format, &file.hash, &source)?;
+                    resolved.local.push((target, data));
+                }
+                PackSource::Remote(_) => resolved.downloads.push((
+                    instance.source.url(&source)?.to_string(),
+                    PackFile {
+                        target,
+                        hash_format: hash_format.clone(),
+                        hash: file.hash,
+                    },
+                )),
+            }
+
+            continue;
+        }
+
+        let data = instance.source.read(app, &source).await?;
+        verify_hash(&data, hash_format, &file.hash, &source)?;
+        let metafile = toml::from_str::<ModToml>(std::str::from_utf8(&data)?)?;
+
+        if metafile.side.as_deref() == Some("server") {
+            trace!("Skipping server side packwiz file {}", metafile.name);
+            continue;
+        }
+
+        let dir = Path::new(&file.file).parent().unwrap_or(Path::new(""));
+        let update = metafile.update.as_ref();
+
+        let download = &metafile.download;
+
+        // mods linked to a platform are installed through it, so they can be updated later.
+        // installers only check downloads against the platform's hashes, so those have to
+        // match the metafile, otherwise the file is downloaded and checked directly
+        if dir == Path::new("mods") {
+            if let Some(mr) = update.and_then(|update| update.modrinth.as_ref()) {
+                let installer =
+                    ModrinthModInstaller::create(app, mr.mod_id.clone(), mr.version.clone())
+                        .await?;
+                let hashes = &installer.file().hashes;
+                let platform_hashes = [
+                    ("sha1", hashes.sha1.clone()),
+                    ("sha512", hashes.sha512.clone()),
+                ];
+
+                if verify_platform_hash(
+                    &platform_hashes,
+                    &download.hash_format,
+                    &download.hash,
+                    &source,
+                )? {
+                    resolved.installers.push(installer.into_installer());
+                    continue;
+                }
+            } else if let Some(cf) = update.and_then(|update| update.curseforge.as_ref()) {
+                let installer =
+                    CurseforgeModInstaller::create(app, cf.project_id, cf.file_id).await?;
+                let file = installer.file();
+                let platform_hashes = file
+                    .hashes
+                    .iter()
+                    .map(|hash| match hash.algo {
+                        HashAlgo::Sha1 => ("sha1", hash.value.clone()),
+                        HashAlgo::Md5 => ("md5", hash.value.clone()),
+                    })
+                    .chain([("murmur2", file.file_fingerprint.to_string())])
+                    .collect::<Vec<_>>();
+
+                if verify_platform_hash(
+                    &platform_hashes,
+                    &download.hash_format,
+                    &download.hash,
+                    &source,
+                )? {
+                    resolved.installers.push(installer.into_installer());
+                    continue;
+                }
+            }
+        }
+
+        let url = match (
+            &metafile.download.url,
+            update.and_then(|u| u.curseforge.as_ref()),
+        ) {
+            (Some(url), _) => url.clone(),
+            (None, Some(cf)) => app
+                .modplatforms_manager()
+                .curseforge
+                .get_mod_file(ModFileParameters {
+                    mod_id: cf.project_id as i32,
+                    file_id: cf.file_id as i32,
+                })
+                .await?
+                .data
+                .download_url
+                .ok_or_else(|| {
+                    anyhow!(
+                        "{} cannot be downloaded without privileged api key",
+                        metafile.name
+                    )
+                })?,
+            (None, None) => bail!("packwiz file {} has no download url", metafile.name),
+        };
+
+        resolved.downloads.push((
+            url,
+            PackFile {
+                target: dir
+                    .join(index_relative("", &metafile.filename)?)
+                    .to_string_lossy()
+                    .to_string(),
+                hash_format: metafile.download.hash_format,
+                hash: metafile.download.hash,
+            },
+        ));
+    }
+
+    Ok(resolved)
+}
+
+/// Downloads the files of a remote pack into `path`, failing on hash mismatches.
+async fn download_pack_files(
+    path: &Path,
+    downloads: &[(String, PackFile)],
+    concurrency: usize,
+) -> anyhow::Result<()> {
+    let downloadables = downloads
+        .iter()
+        .map(|(url, file)| {
+            Downloadable::new(url, path.join(&file.target))
+                .with_checksum(download_checksum(&file.hash_format, &file.hash))
+        })
+        .collect::<Vec<_>>();
+
+    carbon_net::download_multiple(&downloadables, None, concurrency, true, false).await?;
+
+    // hashes carbon_net does not know about are checked after downloading
+    for (_, file) in downloads {
+        if download_checksum(&file.hash_format, &file.hash).is_none() {
+            let data = tokio::fs::read(path.join(&file.target)).await?;
+            verify_hash(&data, &file.hash_format, &file.hash, &file.target)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for PackwizImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        let Some(source) = PackSource::parse(&scan_path) else {
+            return Ok(());
+        };
+
+        let entry = self.scan_pack(app, source).await?;
+        self.state.write().await.set_single(entry).await;
+        app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning packwiz import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let version = GameVersion::Standard(
+            resolve_standard_version(
+                app,
+                instance.pack.versions["minecraft"].clone(),
+                instance.modloaders.clone(),
+            )
+            .await?,
+        );
+
+        trace!("Resolving packwiz index");
+        let ResolvedPack {
+            local,
+            downloads,
+            installers,
+        } = resolve_pack(app, &instance).await?;
+
+        let concurrency = app
+            .settings_manager()
+            .get_settings()
+            .await?
+            .concurrent_downloads;
+
+        let initializer = |instance_path: PathBuf| async move {
+            let path = instance_path.join("instance");
+
+            for (target, data) in local {
+                let target = path.join(target);
+                if let Some(parent) = target.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+
+                tokio::fs::write(target, data).await?;
+            }
+
+            trace!("Downloading packwiz files");
+            download_pack_files(&path, &downloads, concurrency as usize).await
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| instance.pack.name.clone()),
+                None,
+                None,
+                None,
+                InstanceVersionSource::Version(version),
+                String::new(),
+                initializer,
+            )
+            .await?;
+
+        for installer in installers {
+            installer.install(app, id, false, None).await?;
+        }
+
+        app.instance_manager()
+            .prepare_game(id, None, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use axum::{routing::get, Router};
+    use sha2::{Digest, Sha256, Sha512};
+    use tokio::net::TcpListener;
+
+    use super::{
+        download_pack_files, index_relative, resolve_pack, verify_hash, verify_platform_hash,
+        InternalImportEntry, PackFile, PackSource, PackwizImporter,
+    };
+
+    #[test]
+    fn verify_packwiz_hashes() {
+        verify_hash(
+            b"",
+            "sha256",
+            "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855",
+            "file",
+        )
+        .unwrap();
+        verify_hash(b"", "md5", "d41d8cd98f00b204e9800998ecf8427e", "file").unwrap();
+        verify_hash(
+            b"packwiz",
+            "md5",
+            "d41d8cd98f00b204e9800998ecf8427e",
+            "file",
+        )
+        .unwrap_err();
+        verify_hash(b"", "crc32", "0", "file").unwrap_err();
+
+        // whitespace does not change curseforge fingerprints
+        let murmur2 = murmurhash32::murmurhash2(b"packwiz").to_string();
+        verify_hash(b"pack wiz\r\n", "murmur2", &murmur2, "file").unwrap();
+    }
+
+    #[test]
+    fn index_relative_paths() {
+        assert_eq!(
+            index_relative("index.toml", "mods/a.pw.toml").unwrap(),
+            "mods/a.pw.toml"
+        );
+        assert_eq!(
+            index_relative("pack/index.toml", "config/a.json").unwrap(),
+            "pack/config/a.json"
+        );
+        index_relative("index.toml", "../outside").unwrap_err();
+        index_relative("index.toml", "/etc/passwd").unwrap_err();
+    }
+
+    #[test]
+    fn verify_platform_hashes() {
+        let hashes = [("sha1", String::from("ABCD"))];
+
+        assert!(verify_platform_hash(&hashes, "sha1", "abcd", "file").unwrap());
+        assert!(!verify_platform_hash(&hashes, "sha512", "abcd", "file").unwrap());
+        verify_platform_hash(&hashes, "sha1", "dcba", "file").unwrap_err();
+    }
+
+    fn pack_toml(index_hash: &str) -> String {
+        format!(
+            r#"name = "Remote Pack"
+
+[index]
+file = "index.toml"
+hash-format = "sha256"
+hash = "{index_hash}"
+
+[versions]
+minecraft = "1.20.1"
+fabric = "0.15.11"
+"#
+        )
+    }
+
+    #[tokio::test]
+    async fn import_remote_pack() {
+        let app = crate::setup_managers_for_test().await;
+
+        let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!(
+            "http://127.0.0.1:{}",
+            tcp_listener.local_addr().unwrap().port()
+        );
+
+        let jar = "mod jar";
+        let config = "{}";
+        let metafile = format!(
+            r#"name = "Test Mod"
+filename = "test.jar"
+side = "both"
+
+[download]
+url = "{base}/files/test.jar"
+hash-format = "sha512"
+hash = "{}"
+"#,
+            hex::encode(Sha512::digest(jar))
+        );
+        let index = format!(
+            r#"hash-format = "sha256"
+
+[[files]]
+file = "config/test.json"
+hash = "{}"
+
+[[files]]
+file = "mods/test.pw.toml"
+hash = "{}"
+metafile = true
+"#,
+            hex::encode(Sha256::digest(config)),
+            hex::encode(Sha256::digest(&metafile))
+        );
+        let pack = pack_toml(&hex::encode(Sha256::digest(&index)));
+        let bad_pack = pack_toml(&hex::encode(Sha256::digest("tampered")));
+        let bad_index = index.clone();
+
+        let server = Router::new()
+            .route("/pack/pack.toml", get(move || async move { pack }))
+            .route("/pack/index.toml", get(move || async move { index }))
+            .route("/pack/config/test.json", get(move || async move { config }))
+            .route(
+                "/pack/mods/test.pw.toml",
+                get(move || async move { metafile }),
+            )
+            .route("/bad/pack.toml", get(move || async move { bad_pack }))
+            .route("/bad/index.toml", get(move || async move { bad_index }))
+            .route("/files/test.jar", get(move || async move { jar }))
+            .route(
+                "/files/tampered.jar",
+                get(move || async move { "tampered" }),
+            );
+
+        tokio::spawn(async {
+            axum::serve(tcp_listener, server.into_make_service())
+                .await
+                .unwrap();
+        });
+
+        let importer = PackwizImporter::new();
+        let scan = |pack: &str| {
+            let source = PackSource::parse(Path::new(&format!("{base}/{pack}"))).unwrap();
+            importer.scan_pack(&app, source)
+        };
+
+        let InternalImportEntry::Valid(instance) = scan("pack/pack.toml").await.unwrap() else {
+            panic!("remote pack should be importable");
+        };
+        let resolved = resolve_pack(&app, &instance).await.unwrap();
+        assert!(resolved.installers.is_empty());
+
+        let targets = resolved
+            .downloads
+            .iter()
+            .map(|(_, file)| &file.target as &str)
+            .collect::<Vec<_>>();
+        assert_eq!(targets, ["config/test.json", "mods/test.jar"]);
+
+        let dir = tempdir::TempDir::new("carbon_app_test").unwrap();
+        download_pack_files(dir.path(), &resolved.downloads, 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            tokio::fs::read_to_string(dir.path().join("mods/test.jar"))
+                .await
+                .unwrap(),
+            jar
+        );
+
+        // files that don't match their metafile fail the import
+        let tampered = [(
+            format!("{base}/files/tampered.jar"),
+            PackFile {
+                target: String::from("mods/tampered.jar"),
+                hash_format: String::from("sha512"),
+                hash: hex::encode(Sha512::digest(jar)),
+            },
+        )];
+        download_pack_files(dir.path(), &tampered, 2)
+            .await
+            .unwrap_err();
+
+        let InternalImportEntry::Valid(bad) = scan("bad/pack.toml").await.unwrap() else {
+            panic!("remote pack should be importable");
+        };
+        resolve_pack(&app, &bad).await.unwrap_err();
+    }
+}
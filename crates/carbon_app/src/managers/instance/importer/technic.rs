@@ -0,0 +1,432 @@
+use std::{fs, io::Read, path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::info::{GameVersion, ModLoaderType},
+        vtask::VisualTaskId,
+    },
+    managers::{instance::InstanceVersionSource, AppInner},
+};
+
+use super::{
+    resolve_standard_version, ImportScanStatus, ImportableInstance, ImporterState,
+    InstanceImporter, InternalImportEntry, InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    path: PathBuf,
+    version: TechnicPackVersion,
+    build: Option<String>,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value.filename.clone(),
+            instance_name: value.filename,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TechnicImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl TechnicImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    pub async fn get_default_scan_path() -> anyhow::Result<PathBuf> {
+        let basedirs = directories::BaseDirs::new().ok_or(anyhow!("Cannot build basedirs"))?;
+
+        #[cfg(target_os = "windows")]
+        let p = basedirs.data_dir().join(".technic");
+        #[cfg(target_os = "macos")]
+        let p = basedirs.data_dir().join("technic");
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let p = basedirs.home_dir().join(".technic");
+
+        Ok(p.join("modpacks"))
+    }
+
+    async fn scan_instance(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        let bin = path.join("bin");
+        let modpack_jar = bin.join("modpack.jar");
+        let version_json = bin.join("version.json");
+        if !modpack_jar.is_file() && !version_json.is_file() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .expect("filename cannot be empty")
+            .to_string_lossy()
+            .to_string();
+
+        // the installed build is written by the launcher as `{"version": "..."}`
+        let build = tokio::fs::read(bin.join("version"))
+            .await
+            .ok()
+            .and_then(|data| serde_json::from_slice::<TechnicBuild>(&data).ok())
+            .map(|build| build.version);
+
+        let version = tokio::task::spawn_blocking(move || {
+            // packs may override the version json shipped in modpack.jar
+            let data = match fs::read(&version_json) {
+                Ok(data) => data,
+                Err(_) => {
+                    let file = fs::File::open(&modpack_jar)
+                        .map_err(|_| Translation::InstanceImportTechnicMalformedModpackJar)?;
+
+                    let mut zip = zip::ZipArchive::new(file)
+                        .map_err(|_| Translation::InstanceImportTechnicMalformedModpackJar)?;
+
+                    // pre 1.6 packs patch the minecraft jar directly
+                    let mut version = zip
+                        .by_name("version.json")
+                        .map_err(|_| Translation::InstanceImportTechnicUnsupportedJarmod)?;
+
+                    let mut data = Vec::new();
+                    version
+                        .read_to_end(&mut data)
+                        .map_err(|_| Translation::InstanceImportTechnicMalformedModpackJar)?;
+
+                    data
+                }
+            };
+
+            TechnicPackVersion::parse(&data)
+                .ok_or(Translation::InstanceImportTechnicMalformedVersionJson)
+        })
+        .await?;
+
+        match version {
+            Ok(version) => Ok(Some(InternalImportEntry::Valid(Importable {
+                filename,
+                path,
+                version,
+                build,
+            }))),
+            Err(reason) => Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason,
+            }))),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for TechnicImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        if scan_path.is_dir() {
+            if let Ok(Some(entry)) = self.scan_instance(scan_path.clone()).await {
+                self.state.write().await.set_single(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                return Ok(());
+            }
+
+            let Ok(mut dir) = tokio::fs::read_dir(&scan_path).await else {
+                return Ok(());
+            };
+
+            while let Some(path) = dir.next_entry().await? {
+                if path.metadata().await?.is_dir() {
+                    if let Ok(Some(entry)) = self.scan_instance(path.path()).await {
+                        self.state.write().await.push_multi(entry).await;
+                        app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning technic import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let version = GameVersion::Standard(
+            resolve_standard_version(
+                app,
+                instance.version.release.clone(),
+                instance.version.modloaders.clone(),
+            )
+            .await?,
+        );
+
+        // the launcher caches pack icons in `<technic>/assets/packs/<slug>`
+        let icon = match instance.path.parent().and_then(|p| p.parent()) {
+            Some(root) => {
+                let icon = root
+                    .join("assets")
+                    .join("packs")
+                    .join(&instance.filename)
+                    .join("icon.png");
+
+                match icon.is_file() {
+                    true => app.instance_manager().load_icon(icon).await.ok(),
+                    false => None,
+                }
+            }
+            None => None,
+        };
+
+        let notes = match &instance.build {
+            Some(build) => format!("Technic pack {} build {build}", instance.filename),
+            None => String::new(),
+        };
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            async move {
+                let path = instance_path.join("instance");
+
+                tokio::fs::create_dir_all(instance_path.join(".setup").join("modpack-complete"))
+                    .await?;
+
+                trace!("Copying files from technic pack");
+                crate::domain::runtime_path::copy_dir_filter(&instance.path, &path, |path| {
+                    !matches!(
+                        path.iter().next().and_then(|p| p.to_str()),
+                        Some("bin" | "natives" | "assets" | "libraries")
+                    )
+                })
+                .await?;
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| instance.filename.clone()),
+                icon,
+                None,
+                None,
+                InstanceVersionSource::Version(version),
+                notes,
+                initializer,
+            )
+            .await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TechnicBuild {
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionJson {
+    id: String,
+    inherits_from: Option<String>,
+    jar: Option<String>,
+    #[serde(default)]
+    libraries: Vec<VersionJsonLibrary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionJsonLibrary {
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TechnicPackVersion {
+    release: String,
+    modloaders: Vec<(ModLoaderType, String)>,
+}
+
+impl TechnicPackVersion {
+    /// Reads the minecraft release and loader from a launcher version json.
+    fn parse(data: &[u8]) -> Option<Self> {
+        let version = serde_json::from_slice::<VersionJson>(data).ok()?;
+
+        let mut release = version.inherits_from.or(version.jar);
+        let mut modloaders = Vec::new();
+
+        for library in &version.libraries {
+            let mut parts = library.name.split(':');
+            let (Some(group), Some(artifact), Some(lib_version)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+
+            // forge style artifacts prefix the loader version with the minecraft release
+            let (type_, forge_style) = match (group, artifact) {
+                ("net.minecraftforge", "forge" | "minecraftforge") => (ModLoaderType::Forge, true),
+                // the first neoforge releases for 1.20.1 kept the forge artifact name
+                ("net.neoforged", "forge") => (ModLoaderType::Neoforge, true),
+                ("net.neoforged", "neoforge") => (ModLoaderType::Neoforge, false),
+                ("net.fabricmc", "fabric-loader") => (ModLoaderType::Fabric, false),
+                ("org.quiltmc", "quilt-loader") => (ModLoaderType::Quilt, false),
+                _ => continue,
+            };
+
+            // forge versions are formatted as `<mc>-<forge>` and legacy ones as `<mc>-<forge>-<mc>`
+            let loader_version = match lib_version.split_once('-') {
+                Some((mc, rest)) if forge_style => {
+                    release.get_or_insert_with(|| mc.to_string());
+                    rest.strip_suffix(&format!("-{mc}")).unwrap_or(rest)
+                }
+                _ => lib_version,
+            };
+
+            if type_ == ModLoaderType::Neoforge && !forge_style && release.is_none() {
+                release = neoforge_release(lib_version);
+            }
+
+            modloaders.push((type_, loader_version.to_string()));
+        }
+
+        Some(Self {
+            release: release.unwrap_or_else(|| match version.id.split_once('-') {
+                Some((release, _)) => release.to_string(),
+                None => version.id,
+            }),
+            modloaders,
+        })
+    }
+}
+
+/// Minecraft release of a neoforge version, `20.4.80-beta` being for 1.20.4 and `21.0.1` for 1.21.
+fn neoforge_release(version: &str) -> Option<String> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse::<u32>().ok()?;
+    let minor = parts.next()?.parse::<u32>().ok()?;
+
+    Some(match minor {
+        0 => format!("1.{major}"),
+        _ => format!("1.{major}.{minor}"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::instance::info::ModLoaderType;
+
+    use super::TechnicPackVersion;
+
+    #[test]
+    fn parse_legacy_forge_version() {
+        let version = TechnicPackVersion::parse(
+            br#"{
+    "id": "1.7.10-Forge10.13.4.1614-1.7.10",
+    "libraries": [
+        { "name": "net.minecraftforge:forge:1.7.10-10.13.4.1614-1.7.10" },
+        { "name": "net.minecraft:launchwrapper:1.12" }
+    ]
+}"#,
+        );
+
+        assert_eq!(
+            version,
+            Some(TechnicPackVersion {
+                release: String::from("1.7.10"),
+                modloaders: vec![(ModLoaderType::Forge, String::from("10.13.4.1614"))],
+            })
+        );
+    }
+
+    #[test]
+    fn parse_neoforge_version() {
+        let version = TechnicPackVersion::parse(
+            br#"{
+    "id": "neoforge-20.4.80-beta",
+    "libraries": [
+        { "name": "net.neoforged.fancymodloader:loader:2.0.17" },
+        { "name": "net.neoforged:neoforge:20.4.80-beta:universal" }
+    ]
+}"#,
+        );
+
+        assert_eq!(
+            version,
+            Some(TechnicPackVersion {
+                release: String::from("1.20.4"),
+                modloaders: vec![(ModLoaderType::Neoforge, String::from("20.4.80-beta"))],
+            })
+        );
+
+        let legacy = TechnicPackVersion::parse(
+            br#"{
+    "id": "1.20.1-forge-47.1.79",
+    "libraries": [
+        { "name": "net.neoforged:forge:1.20.1-47.1.79:universal" }
+    ]
+}"#,
+        );
+
+        assert_eq!(
+            legacy,
+            Some(TechnicPackVersion {
+                release: String::from("1.20.1"),
+                modloaders: vec![(ModLoaderType::Neoforge, String::from("47.1.79"))],
+            })
+        );
+    }
+
+    #[test]
+    fn parse_fabric_version() {
+        let version = TechnicPackVersion::parse(
+            br#"{
+    "id": "fabric-loader-0.14.21-1.20.1",
+    "inheritsFrom": "1.20.1",
+    "libraries": [
+        { "name": "net.fabricmc:intermediary:1.20.1" },
+        { "name": "net.fabricmc:fabric-loader:0.14.21" }
+    ]
+}"#,
+        );
+
+        assert_eq!(
+            version,
+            Some(TechnicPackVersion {
+                release: String::from("1.20.1"),
+                modloaders: vec![(ModLoaderType::Fabric, String::from("0.14.21"))],
+            })
+        );
+    }
+}
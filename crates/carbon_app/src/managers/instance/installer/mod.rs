@@ -578,6 +578,10 @@ impl CurseforgeModInstaller {
             applied_data: Arc::new(Mutex::new(None)),
         })
     }
+
+    pub fn file(&self) -> &crate::domain::modplatforms::curseforge::File {
+        &self.file
+    }
 }
 
 #[async_trait::async_trait]
@@ -874,6 +878,10 @@ impl ModrinthModInstaller {
             applied_data: Arc::new(Mutex::new(None)),
         })
     }
+
+    pub fn file(&self) -> &crate::domain::modplatforms::modrinth::version::VersionFile {
+        &self.file
+    }
 }
 
 #[async_trait::async_trait]
@@ -50,6 +50,10 @@ use crate::domain::instance::{
 };
 use domain::info;
 
+mod backups;
+mod config_sync;
+mod datapacks;
+mod diff;
 pub mod explore;
 pub mod export;
 pub mod importer;
@@ -57,8 +61,14 @@ pub mod installer;
 pub mod log;
 pub mod modpack;
 mod mods;
+mod options;
+mod packs;
 mod run;
 mod schema;
+mod screenshots;
+mod servers;
+mod snapshots;
+mod worlds;
 
 #[derive(Debug)]
 pub struct InstanceManager {
@@ -72,6 +82,8 @@ pub struct InstanceManager {
     export_manager: InstanceExportManager,
     game_logs: RwLock<HashMap<GameLogId, (InstanceId, watch::Receiver<GameLog>)>>,
     modpack_info_semaphore: Mutex<()>,
+    // world backups, snapshots and their restores run one at a time
+    backup_lock: Mutex<()>,
     pub any_instance_running: Arc<watch::Sender<bool>>,
     instance_running_tracker: Arc<LivenessTracker>,
 }
@@ -96,6 +108,7 @@ impl InstanceManager {
             export_manager: InstanceExportManager::new(),
             game_logs: RwLock::new(HashMap::new()),
             modpack_info_semaphore: Mutex::new(()),
+            backup_lock: Mutex::new(()),
             any_instance_running: any_instance_running.clone(),
             instance_running_tracker: LivenessTracker::new(move |count| {
                 drop(any_instance_running.send_replace(count != 0))
@@ -108,6 +121,7 @@ impl<'s> ManagerRef<'s, InstanceManager> {
     pub async fn launch_background_tasks(self) {
         let _ = self.scan_instances().await;
         self.import_manager().launch_background_tasks();
+        self.launch_backup_scheduler();
     }
 
     pub async fn scan_instances(self) -> anyhow::Result<()> {
@@ -946,29 +960,6 @@ impl<'s> ManagerRef<'s, InstanceManager> {
         F: FnOnce(PathBuf) -> I,
         I: Future<Output = anyhow::Result<()>>,
     {
-        trace!("Creating instance");
-
-        let tmpdir = self
-            .app
-            .settings_manager()
-            .runtime_path
-            .get_temp()
-            .maketmpdir()
-            .await?;
-
-        tokio::fs::create_dir(tmpdir.join("instance")).await?;
-
-        let icon = match icon {
-            Some((path, data)) => {
-                tokio::fs::write(tmpdir.join(&path), data)
-                    .await
-                    .context("saving instance icon")?;
-
-                InstanceIcon::RelativePath(path)
-            }
-            None => InstanceIcon::Default,
-        };
-
         let (version, modpack, pack_locked) = match version {
             InstanceVersionSource::Version(version) => (Some(version), None, false),
             InstanceVersionSource::Modpack(modpack, locked) => (None, Some(modpack), locked),
@@ -978,8 +969,8 @@ impl<'s> ManagerRef<'s, InstanceManager> {
         };
 
         let info = info::Instance {
-            name: name.clone(),
-            icon,
+            name,
+            icon: InstanceIcon::Default,
             date_created: Utc::now(),
             date_updated: Utc::now(),
             last_played,
@@ -1001,27 +992,76 @@ impl<'s> ManagerRef<'s, InstanceManager> {
             wrapper_command: None,
             mod_sources: None,
             notes,
+            kind: info::InstanceKind::Client,
+            shared_servers: false,
+            world_backups: None,
+            shared_options: false,
         };
 
+        self.create_instance_from_config(group, info, icon, |instance_path: PathBuf| async move {
+            if let Some(modpack) = modpack {
+                let pack_version_text = serde_json::to_string(&PackVersionFile::from(modpack))?;
+                tokio::fs::write(
+                    instance_path
+                        .join(".setup")
+                        .join("change-pack-version.json"),
+                    &pack_version_text,
+                )
+                .await?;
+            }
+
+            initializer(instance_path).await
+        })
+        .await
+    }
+
+    /// Creates an instance from a complete config, keeping every field as given.
+    ///
+    /// `icon` replaces the configured icon when set. The initializer runs after
+    /// the config and setup marker have been written to the new instance folder.
+    #[tracing::instrument(skip(self, info, icon, initializer))]
+    pub async fn create_instance_from_config<F, I>(
+        self,
+        group: GroupId,
+        mut info: info::Instance,
+        icon: Option<(String, Vec<u8>)>,
+        initializer: F,
+    ) -> anyhow::Result<InstanceId>
+    where
+        F: FnOnce(PathBuf) -> I,
+        I: Future<Output = anyhow::Result<()>>,
+    {
+        trace!("Creating instance");
+
+        let tmpdir = self
+            .app
+            .settings_manager()
+            .runtime_path
+            .get_temp()
+            .maketmpdir()
+            .await?;
+
+        tokio::fs::create_dir(tmpdir.join("instance")).await?;
+
+        if let Some((path, data)) = icon {
+            tokio::fs::write(tmpdir.join(&path), data)
+                .await
+                .context("saving instance icon")?;
+
+            info.icon = InstanceIcon::RelativePath(path);
+        }
+
+        let name = info.name.clone();
+
         let json = schema::make_instance_config(info.clone())?;
         tokio::fs::write(tmpdir.join("instance.json"), json)
             .await
             .context("writing instance json")?;
 
-        let setup_path = tmpdir.join(".setup");
-        tokio::fs::create_dir(&setup_path)
+        tokio::fs::create_dir(tmpdir.join(".setup"))
             .await
             .context("writing setup marker")?;
 
-        if let Some(modpack) = modpack {
-            let pack_version_text = serde_json::to_string(&PackVersionFile::from(modpack))?;
-            tokio::fs::write(
-                setup_path.join("change-pack-version.json"),
-                &pack_version_text,
-            )
-            .await?;
-        }
-
         trace!("Running extended instance initializer");
         initializer(tmpdir.to_path_buf()).await?;
         trace!("Finished extended instance initializer");
@@ -1087,6 +1127,25 @@ impl<'s> ManagerRef<'s, InstanceManager> {
     ) -> anyhow::Result<()> {
         use db::instance::{SetParam, UniqueWhereParam};
 
+        let version_changes = {
+            let instances = self.instances.read().await;
+            let instance = instances
+                .get(&update.instance_id)
+                .ok_or(InvalidInstanceIdError(update.instance_id))?;
+
+            changes_version(
+                &instance.data()?.config.game_configuration.version,
+                update.version.as_deref(),
+                update.modloader.as_ref(),
+            )
+        };
+
+        if version_changes {
+            self.snapshot_before(update.instance_id, domain::SnapshotReason::VersionChange)
+                .await
+                .context("taking a snapshot before changing the game version")?;
+        }
+
         let mut instances = self.instances.write().await;
         let instance = instances
             .get_mut(&update.instance_id)
@@ -1212,6 +1271,22 @@ impl<'s> ManagerRef<'s, InstanceManager> {
             info.mod_sources = mod_sources;
         }
 
+        if let Some(kind) = update.kind {
+            info.kind = kind;
+        }
+
+        if let Some(shared_servers) = update.shared_servers {
+            info.shared_servers = shared_servers;
+        }
+
+        if let Some(world_backups) = update.world_backups {
+            info.world_backups = world_backups;
+        }
+
+        if let Some(shared_options) = update.shared_options {
+            info.shared_options = shared_options;
+        }
+
         if let Some(modpack_locked) = update.modpack_locked {
             if let Some(modpack_locked) = modpack_locked {
                 if let Some(modpack) = &mut info.modpack {
@@ -1241,6 +1316,12 @@ impl<'s> ManagerRef<'s, InstanceManager> {
                 let _lock = self.path_lock.lock().await;
                 let (new_shortpath, new_path) = self.next_folder(&name).await?;
                 tokio::fs::rename(path.clone(), new_path.clone()).await?;
+
+                // the instance folder already moved, so its new path has to be saved regardless
+                if let Err(e) = self.move_backups(shortpath, &new_shortpath).await {
+                    tracing::error!({ error = ?e }, "Failed to move backups of renamed instance");
+                }
+
                 *shortpath = new_shortpath.clone();
 
                 self.app
@@ -1277,7 +1358,7 @@ impl<'s> ManagerRef<'s, InstanceManager> {
             let app = self.app.clone();
             tokio::spawn(async move {
                 app.instance_manager()
-                    .prepare_game(InstanceId(*update.instance_id), None, None, true)
+                    .prepare_game(InstanceId(*update.instance_id), None, None, None, true)
                     .await?;
 
                 Ok(()) as anyhow::Result<()>
@@ -1688,6 +1769,10 @@ impl<'s> ManagerRef<'s, InstanceManager> {
             required_java_profile,
             state: (&instance.state).into(),
             notes: instance.config.notes.clone(),
+            kind: instance.config.kind.clone(),
+            shared_servers: instance.config.shared_servers,
+            world_backups: instance.config.world_backups.clone(),
+            shared_options: instance.config.shared_options,
             icon_revision,
             has_pack_update: instance.modpack_update_curseforge.unwrap_or(false)
                 || instance.modpack_update_modrinth.unwrap_or(false),
@@ -2030,6 +2115,25 @@ pub struct InvalidGroupIdError(GroupId);
 #[error("attempted to get data of an invalid instance")]
 pub struct InvalidInstanceDataError;
 
+/// Whether setting the release or modloader of an instance would change its game version.
+fn changes_version(
+    current: &Option<info::GameVersion>,
+    release: Option<&str>,
+    modloader: Option<&Option<info::ModLoader>>,
+) -> bool {
+    let Some(info::GameVersion::Standard(current)) = current else {
+        return release.is_some() || modloader.is_some();
+    };
+
+    let release_changes = release.is_some_and(|release| release != current.release);
+    let modloader_changes = modloader.is_some_and(|modloader| match modloader {
+        Some(modloader) => current.modloaders != HashSet::from([modloader.clone()]),
+        None => !current.modloaders.is_empty(),
+    });
+
+    release_changes || modloader_changes
+}
+
 #[cfg(test)]
 mod test {
     use std::{collections::HashSet, time::Duration};
@@ -2492,6 +2596,10 @@ mod test {
                 wrapper_command: None,
                 game_resolution: None,
                 modpack_locked: None,
+                kind: None,
+                shared_servers: None,
+                world_backups: None,
+                shared_options: None,
                 mod_sources: None,
             })
             .await?;
@@ -2641,4 +2749,35 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn version_change_detection() {
+        let forge = info::ModLoader {
+            type_: info::ModLoaderType::Forge,
+            version: String::from("47.2.0"),
+        };
+        let current = Some(info::GameVersion::Standard(info::StandardVersion {
+            release: String::from("1.20.1"),
+            modloaders: HashSet::from([forge.clone()]),
+        }));
+
+        // settings forms send the current values along with the changed ones
+        assert!(!super::changes_version(
+            &current,
+            Some("1.20.1"),
+            Some(&Some(forge.clone()))
+        ));
+        assert!(!super::changes_version(&current, None, None));
+        assert!(super::changes_version(&current, Some("1.20.4"), None));
+        assert!(super::changes_version(&current, None, Some(&None)));
+        assert!(super::changes_version(
+            &current,
+            None,
+            Some(&Some(info::ModLoader {
+                version: String::from("47.3.0"),
+                ..forge
+            }))
+        ));
+        assert!(super::changes_version(&None, Some("1.20.1"), None));
+    }
 }
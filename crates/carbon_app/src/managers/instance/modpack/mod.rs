@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 
-use anyhow::bail;
+use anyhow::{bail, Context};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     domain::{
         instance::{
             info::{self, CurseforgeModpack, Modpack, ModpackInfo, ModrinthModpack},
-            InstanceId,
+            InstanceId, SnapshotReason,
         },
         modplatforms::{
             curseforge::{
@@ -164,6 +164,10 @@ impl ManagerRef<'_, InstanceManager> {
             anyhow::bail!("Instance has not completed the setup phase, attempting to change the modpack may irreparably damage it.");
         }
 
+        self.snapshot_before(instance_id, SnapshotReason::ModpackUpdate)
+            .await
+            .context("taking a snapshot before changing the modpack")?;
+
         tokio::fs::create_dir_all(&setup_path).await?;
 
         let update_file_path = setup_path.join("change-pack-version.json");
@@ -175,7 +179,7 @@ impl ManagerRef<'_, InstanceManager> {
 
         self.app
             .instance_manager()
-            .prepare_game(instance_id, None, None, true)
+            .prepare_game(instance_id, None, None, None, true)
             .await
             .map(|r| r.1)
     }
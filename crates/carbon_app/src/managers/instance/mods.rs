@@ -6,7 +6,7 @@ use chrono::{DateTime, FixedOffset, Utc};
 use futures::Future;
 use thiserror::Error;
 
-use crate::api::keys::instance::INSTANCE_MODS;
+use crate::api::keys::instance::{INSTANCE_MODS, MISSING_SHADER_LOADER};
 use crate::db::{
     curse_forge_mod_cache as cfdb, mod_file_cache as fcdb, mod_metadata as metadb,
     modrinth_mod_cache as mrdb,
@@ -350,6 +350,8 @@ impl ManagerRef<'_, InstanceManager> {
 
         self.app
             .invalidate(INSTANCE_MODS, Some(instance_id.0.into()));
+        self.app
+            .invalidate(MISSING_SHADER_LOADER, Some(instance_id.0.into()));
         Ok(())
     }
 
@@ -443,7 +445,7 @@ impl ManagerRef<'_, InstanceManager> {
         };
 
         let (version, modloader) = match version {
-            domain::info::GameVersion::Custom(_) => todo!("Unsupported"),
+            domain::info::GameVersion::Custom(_) => bail!("Custom versions do not support mods"),
             domain::info::GameVersion::Standard(version) => {
                 let modloader = version
                     .modloaders
@@ -531,7 +533,7 @@ impl ManagerRef<'_, InstanceManager> {
         };
 
         let (version, modloader) = match version {
-            domain::info::GameVersion::Custom(_) => todo!("Unsupported"),
+            domain::info::GameVersion::Custom(_) => bail!("Custom versions do not support mods"),
             domain::info::GameVersion::Standard(version) => {
                 let modloader = version
                     .modloaders
@@ -0,0 +1,393 @@
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::bail;
+
+use crate::api::keys::instance::{GET_GAME_OPTIONS, GET_SHARED_GAME_OPTIONS};
+use crate::db::shared_game_option;
+use crate::domain::instance::{GameOptions, InstanceId, VideoOptions};
+use crate::managers::ManagerRef;
+
+use super::run::LaunchState;
+use super::{InstanceManager, InvalidInstanceIdError};
+
+const KEYBIND_PREFIX: &str = "key_";
+const VOLUME_PREFIX: &str = "soundCategory_";
+/// Data version of the flattening (1.13), which switched key bindings from LWJGL2 key codes to key names
+const FLATTENING_DATA_VERSION: u32 = 1451;
+
+impl ManagerRef<'_, InstanceManager> {
+    /// Path of the instance's options.txt, failing if `modify` is set and the game is running.
+    async fn options_path(self, instance_id: InstanceId, modify: bool) -> anyhow::Result<PathBuf> {
+        let instances = self.instances.read().await;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or(InvalidInstanceIdError(instance_id))?;
+
+        // the game rewrites the file whenever a setting changes
+        if modify && matches!(instance.data()?.state, LaunchState::Running(_)) {
+            bail!("cannot modify options while the instance is running");
+        }
+
+        Ok(self
+            .app
+            .settings_manager()
+            .runtime_path
+            .get_instances()
+            .get_instance_path(&instance.shortpath)
+            .get_options_file_path())
+    }
+
+    pub async fn get_game_options(self, instance_id: InstanceId) -> anyhow::Result<GameOptions> {
+        let path = self.options_path(instance_id, false).await?;
+        let options = read_options_file(&path).await?.unwrap_or_default();
+
+        Ok(options.game_options())
+    }
+
+    /// Writes the set options into the instance's options.txt, keeping all others.
+    pub async fn set_game_options(
+        self,
+        instance_id: InstanceId,
+        game_options: GameOptions,
+    ) -> anyhow::Result<()> {
+        let path = self.options_path(instance_id, true).await?;
+        let mut options = read_options_file(&path).await?.unwrap_or_default();
+
+        // the version describes the file, not the values written into it
+        let game_options = GameOptions {
+            data_version: None,
+            ..game_options
+        };
+
+        if options.apply(&OptionsFile::from(&game_options)) {
+            self.write_options_file(&path, &options).await?;
+        }
+
+        self.app
+            .invalidate(GET_GAME_OPTIONS, Some(instance_id.0.into()));
+        Ok(())
+    }
+
+    async fn shared_options_file(self) -> anyhow::Result<OptionsFile> {
+        Ok(OptionsFile(
+            self.app
+                .prisma_client
+                .shared_game_option()
+                .find_many(vec![])
+                .exec()
+                .await?
+                .into_iter()
+                .map(|option| (option.key, option.value))
+                .collect(),
+        ))
+    }
+
+    pub async fn get_shared_game_options(self) -> anyhow::Result<GameOptions> {
+        Ok(self.shared_options_file().await?.game_options())
+    }
+
+    /// Replaces the shared options profile, which is merged into opted in instances before launch.
+    pub async fn set_shared_game_options(self, game_options: GameOptions) -> anyhow::Result<()> {
+        let create = OptionsFile::from(&game_options)
+            .0
+            .into_iter()
+            .map(|(key, value)| {
+                self.app
+                    .prisma_client
+                    .shared_game_option()
+                    .create(key, value, vec![])
+            })
+            .collect::<Vec<_>>();
+
+        self.app
+            .prisma_client
+            ._batch((
+                self.app
+                    .prisma_client
+                    .shared_game_option()
+                    .delete_many(vec![]),
+                create,
+            ))
+            .await?;
+
+        self.app.invalidate(GET_SHARED_GAME_OPTIONS, None);
+        Ok(())
+    }
+
+    /// Merges the shared options profile into the options.txt at `path`.
+    pub(super) async fn write_shared_options(self, path: &Path) -> anyhow::Result<()> {
+        let shared = self.shared_options_file().await?;
+
+        let mut options = match read_options_file(path).await? {
+            Some(options) => options,
+            // files without a version get upgraded from the oldest format, breaking newer values
+            None if shared.get("version").is_some() => OptionsFile::default(),
+            None => return Ok(()),
+        };
+
+        if options.apply(&shared) {
+            self.write_options_file(path, &options).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn write_options_file(self, path: &Path, options: &OptionsFile) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        self.app
+            .settings_manager()
+            .runtime_path
+            .get_temp()
+            .write_file_atomic(path, options.to_string())
+            .await?;
+
+        Ok(())
+    }
+}
+
+async fn read_options_file(path: &Path) -> anyhow::Result<Option<OptionsFile>> {
+    match tokio::fs::read(path).await {
+        Ok(data) => Ok(Some(OptionsFile::parse(&String::from_utf8_lossy(&data)))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The `key:value` lines of an options.txt, in order so unknown options survive a rewrite.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct OptionsFile(Vec<(String, String)>);
+
+impl OptionsFile {
+    fn parse(content: &str) -> Self {
+        Self(
+            content
+                .lines()
+                // values may contain colons, like server addresses
+                .filter_map(|line| line.split_once(':'))
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        )
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value as &str)
+    }
+
+    fn get_parsed<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(|value| value.parse().ok())
+    }
+
+    /// Sets an option in place or appends it, returning whether anything changed.
+    fn set(&mut self, key: &str, value: &str) -> bool {
+        match self.0.iter_mut().find(|(k, _)| k == key) {
+            Some((_, existing)) if existing == value => false,
+            Some((_, existing)) => {
+                *existing = value.to_string();
+                true
+            }
+            None => {
+                self.0.push((key.to_string(), value.to_string()));
+                true
+            }
+        }
+    }
+
+    /// Sets every option of `other`, keeping this file's version if it has one.
+    ///
+    /// Key bindings are left alone when the two files store them in different formats.
+    fn apply(&mut self, other: &OptionsFile) -> bool {
+        let keep_version = self.get("version").is_some();
+
+        // options set without a version are meant for this file
+        let same_keybinds = match (
+            self.get_parsed::<u32>("version"),
+            other.get_parsed::<u32>("version"),
+        ) {
+            (Some(version), Some(other_version)) => {
+                (version >= FLATTENING_DATA_VERSION) == (other_version >= FLATTENING_DATA_VERSION)
+            }
+            _ => true,
+        };
+
+        let mut changed = false;
+
+        for (key, value) in &other.0 {
+            if key == "version" && keep_version {
+                continue;
+            }
+
+            if key.starts_with(KEYBIND_PREFIX) && !same_keybinds {
+                continue;
+            }
+
+            changed |= self.set(key, value);
+        }
+
+        changed
+    }
+
+    fn game_options(&self) -> GameOptions {
+        let prefixed = |prefix: &'static str| {
+            self.0
+                .iter()
+                .filter_map(move |(key, value)| Some((key.strip_prefix(prefix)?, value)))
+        };
+
+        GameOptions {
+            data_version: self.get_parsed("version"),
+            keybinds: prefixed(KEYBIND_PREFIX)
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+            volumes: prefixed(VOLUME_PREFIX)
+                .filter_map(|(key, value)| Some((key.to_string(), value.parse().ok()?)))
+                .collect(),
+            video: VideoOptions {
+                render_distance: self.get_parsed("renderDistance"),
+                simulation_distance: self.get_parsed("simulationDistance"),
+                max_fps: self.get_parsed("maxFps"),
+                gui_scale: self.get_parsed("guiScale"),
+                fov: self.get_parsed("fov"),
+                gamma: self.get_parsed("gamma"),
+                graphics_mode: self.get_parsed("graphicsMode"),
+                fullscreen: self.get_parsed("fullscreen"),
+                vsync: self.get_parsed("enableVsync"),
+                entity_shadows: self.get_parsed("entityShadows"),
+                view_bobbing: self.get_parsed("bobView"),
+            },
+        }
+    }
+}
+
+impl From<&GameOptions> for OptionsFile {
+    fn from(options: &GameOptions) -> Self {
+        let mut file = Self::default();
+
+        let mut set = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                file.set(key, &value);
+            }
+        };
+
+        // the game writes floats with at least one decimal
+        let float = |value: f32| format!("{value:?}");
+        let video = &options.video;
+
+        set("version", options.data_version.map(|v| v.to_string()));
+        set(
+            "renderDistance",
+            video.render_distance.map(|v| v.to_string()),
+        );
+        set(
+            "simulationDistance",
+            video.simulation_distance.map(|v| v.to_string()),
+        );
+        set("maxFps", video.max_fps.map(|v| v.to_string()));
+        set("guiScale", video.gui_scale.map(|v| v.to_string()));
+        set("fov", video.fov.map(float));
+        set("gamma", video.gamma.map(float));
+        set("graphicsMode", video.graphics_mode.map(|v| v.to_string()));
+        set("fullscreen", video.fullscreen.map(|v| v.to_string()));
+        set("enableVsync", video.vsync.map(|v| v.to_string()));
+        set("entityShadows", video.entity_shadows.map(|v| v.to_string()));
+        set("bobView", video.view_bobbing.map(|v| v.to_string()));
+
+        for (key, value) in &options.keybinds {
+            set(&format!("{KEYBIND_PREFIX}{key}"), Some(value.clone()));
+        }
+
+        for (category, volume) in &options.volumes {
+            set(&format!("{VOLUME_PREFIX}{category}"), Some(float(*volume)));
+        }
+
+        file
+    }
+}
+
+impl Display for OptionsFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.0 {
+            writeln!(f, "{key}:{value}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::instance::GameOptions;
+
+    use super::OptionsFile;
+
+    const OPTIONS: &str = "version:3465
+ao:true
+renderDistance:12
+fov:0.25
+lastServer:localhost:25565
+key_key.jump:key.keyboard.space
+key_key.sprint:key.keyboard.left.control
+soundCategory_master:0.5
+soundCategory_music:0.0
+";
+
+    #[test]
+    fn read_options() {
+        let options = OptionsFile::parse(OPTIONS);
+        assert_eq!(options.to_string(), OPTIONS);
+        assert_eq!(options.get("lastServer"), Some("localhost:25565"));
+
+        let game_options = options.game_options();
+        assert_eq!(game_options.data_version, Some(3465));
+        assert_eq!(game_options.video.render_distance, Some(12));
+        assert_eq!(game_options.video.fov, Some(0.25));
+        assert_eq!(game_options.video.vsync, None);
+        assert_eq!(
+            game_options.keybinds.get("key.jump").map(|v| v as &str),
+            Some("key.keyboard.space")
+        );
+        assert_eq!(game_options.volumes.get("music"), Some(&0.0));
+    }
+
+    #[test]
+    fn apply_shared_options() {
+        let mut shared = GameOptions {
+            data_version: Some(3700),
+            ..Default::default()
+        };
+        shared
+            .keybinds
+            .insert(String::from("key.jump"), String::from("key.keyboard.j"));
+        shared.volumes.insert(String::from("master"), 1.0);
+
+        let mut options = OptionsFile::parse(OPTIONS);
+        assert!(options.apply(&OptionsFile::from(&shared)));
+        assert!(!options.apply(&OptionsFile::from(&shared)));
+
+        // existing options keep their place and the file keeps its version
+        let written = options.to_string();
+        assert!(written.starts_with("version:3465\n"));
+        assert!(written.contains("key_key.jump:key.keyboard.j\nkey_key.sprint"));
+        assert!(written.contains("soundCategory_master:1.0\n"));
+
+        // key codes of a pre flattening instance can't hold key names, nor the other way around
+        let mut legacy = OptionsFile::parse("version:1343\nkey_key.jump:57\n");
+        assert!(legacy.apply(&OptionsFile::from(&shared)));
+        assert_eq!(
+            legacy.to_string(),
+            "version:1343\nkey_key.jump:57\nsoundCategory_master:1.0\n"
+        );
+
+        let legacy_shared = OptionsFile::parse("version:1343\nkey_key.jump:36\n");
+        let mut options = OptionsFile::parse(OPTIONS);
+        assert!(!options.apply(&legacy_shared));
+        assert_eq!(options.get("key_key.jump"), Some("key.keyboard.space"));
+    }
+}
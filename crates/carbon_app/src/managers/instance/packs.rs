@@ -0,0 +1,805 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use carbon_net::{Checksum, Downloadable};
+use thiserror::Error;
+
+use crate::api::keys::instance::{
+    INSTANCE_RESOURCE_PACKS, INSTANCE_SHADER_PACKS, MISSING_SHADER_LOADER,
+};
+use crate::api::keys::Key;
+use crate::api::translation::Translation;
+use crate::db::{
+    curse_forge_mod_cache as cfdb, mod_metadata as metadb, modrinth_mod_cache as mrdb,
+    pack_file_cache as pcdb,
+};
+use crate::domain::instance::info::GameVersion;
+use crate::domain::instance::{self as domain, InstanceId, PackType};
+use crate::domain::modplatforms::curseforge::filters::{
+    ModFileParameters, ModFilesParameters, ModFilesParametersQuery, ModParameters,
+};
+use crate::domain::modplatforms::curseforge::{ClassId, HashAlgo};
+use crate::domain::modplatforms::modrinth::project::{ProjectType, ProjectVersionsFilters};
+use crate::domain::modplatforms::modrinth::search::{ProjectID, VersionID};
+use crate::domain::modplatforms::{ModChannel, ModPlatform, RemoteVersion};
+use crate::domain::vtask::VisualTaskId;
+use crate::managers::vtask::{Subtask, TaskState, VisualTask};
+use crate::managers::ManagerRef;
+
+use super::worlds::check_file_name;
+use super::{InstanceManager, InvalidInstanceIdError};
+
+impl ManagerRef<'_, InstanceManager> {
+    async fn pack_path(
+        self,
+        instance_id: InstanceId,
+        pack_type: PackType,
+    ) -> anyhow::Result<PathBuf> {
+        let instances = self.instances.read().await;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or(InvalidInstanceIdError(instance_id))?;
+
+        let instance_path = self
+            .app
+            .settings_manager()
+            .runtime_path
+            .get_instances()
+            .get_instance_path(&instance.shortpath);
+
+        Ok(pack_type.path(&instance_path))
+    }
+
+    async fn get_pack_entry(
+        self,
+        instance_id: InstanceId,
+        id: String,
+    ) -> anyhow::Result<pcdb::Data> {
+        let pack = self
+            .app
+            .prisma_client
+            .pack_file_cache()
+            .find_unique(pcdb::UniqueWhereParam::IdEquals(id.clone()))
+            .with(
+                pcdb::metadata::fetch()
+                    .with(metadb::logo_image::fetch())
+                    .with(metadb::curseforge::fetch().with(cfdb::logo_image::fetch()))
+                    .with(metadb::modrinth::fetch().with(mrdb::logo_image::fetch())),
+            )
+            .exec()
+            .await?
+            .filter(|pack| pack.instance_id == *instance_id)
+            .ok_or(InvalidInstancePackIdError(instance_id, id))?;
+
+        Ok(pack)
+    }
+
+    pub async fn list_packs(
+        self,
+        instance_id: InstanceId,
+        pack_type: PackType,
+    ) -> anyhow::Result<Vec<domain::Pack>> {
+        // ensure the instance exists
+        self.pack_path(instance_id, pack_type).await?;
+
+        let packs = self
+            .app
+            .prisma_client
+            .pack_file_cache()
+            .find_many(vec![
+                pcdb::instance_id::equals(*instance_id),
+                pcdb::pack_type::equals(pack_type.to_string()),
+            ])
+            .with(
+                pcdb::metadata::fetch()
+                    .with(metadb::logo_image::fetch())
+                    .with(metadb::curseforge::fetch().with(cfdb::logo_image::fetch()))
+                    .with(metadb::modrinth::fetch().with(mrdb::logo_image::fetch())),
+            )
+            .exec()
+            .await?
+            .into_iter()
+            .map(|pack| {
+                let metadata = pack.metadata.map(|m| *m);
+
+                domain::Pack {
+                    id: pack.id,
+                    pack_type,
+                    filename: pack.filename,
+                    enabled: pack.enabled,
+                    pack_format: pack.pack_format,
+                    curseforge: metadata
+                        .as_ref()
+                        .and_then(|m| m.curseforge.clone().flatten())
+                        .map(|cf| curseforge_metadata(*cf)),
+                    modrinth: metadata
+                        .as_ref()
+                        .and_then(|m| m.modrinth.clone().flatten())
+                        .map(|mr| modrinth_metadata(*mr)),
+                    metadata: metadata.map(file_metadata),
+                }
+            })
+            .collect();
+
+        Ok(packs)
+    }
+
+    pub async fn enable_pack(
+        self,
+        instance_id: InstanceId,
+        id: String,
+        enabled: bool,
+    ) -> anyhow::Result<()> {
+        let pack = self.get_pack_entry(instance_id, id.clone()).await?;
+        let pack_type = PackType::try_from(&pack.pack_type as &str)?;
+        let pack_path = self.pack_path(instance_id, pack_type).await?;
+
+        let enabled_path = pack_path.join(&pack.filename);
+        let disabled_path = pack_path.join(format!("{}.disabled", pack.filename));
+
+        if enabled {
+            if enabled_path.exists() {
+                bail!("pack is already enabled");
+            }
+
+            if !disabled_path.exists() {
+                bail!("pack does not exist on disk");
+            }
+
+            tokio::fs::rename(disabled_path, enabled_path).await?;
+        } else {
+            if disabled_path.exists() {
+                bail!("pack is already disabled");
+            }
+
+            if !enabled_path.exists() {
+                bail!("pack does not exist on disk");
+            }
+
+            tokio::fs::rename(enabled_path, disabled_path).await?;
+        }
+
+        self.app
+            .prisma_client
+            .pack_file_cache()
+            .update(
+                pcdb::UniqueWhereParam::IdEquals(id),
+                vec![pcdb::SetParam::SetEnabled(enabled)],
+            )
+            .exec()
+            .await?;
+
+        self.app
+            .invalidate(pack_list_key(pack_type), Some(instance_id.0.into()));
+
+        if pack_type == PackType::ShaderPack {
+            self.app
+                .invalidate(MISSING_SHADER_LOADER, Some(instance_id.0.into()));
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_pack(self, instance_id: InstanceId, id: String) -> anyhow::Result<()> {
+        let pack = self.get_pack_entry(instance_id, id).await?;
+        let pack_type = PackType::try_from(&pack.pack_type as &str)?;
+        let pack_path = self.pack_path(instance_id, pack_type).await?;
+
+        let enabled_path = pack_path.join(&pack.filename);
+        let disabled_path = pack_path.join(format!("{}.disabled", pack.filename));
+
+        if enabled_path.is_file() {
+            tokio::fs::remove_file(enabled_path).await?;
+        } else if disabled_path.is_file() {
+            tokio::fs::remove_file(disabled_path).await?;
+        }
+
+        self.app
+            .meta_cache_manager()
+            .queue_caching(instance_id, true)
+            .await;
+
+        Ok(())
+    }
+
+    pub async fn install_curseforge_pack(
+        self,
+        instance_id: InstanceId,
+        pack_type: PackType,
+        project_id: u32,
+        file_id: u32,
+        replaces_pack_id: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        let curseforge = &self.app.modplatforms_manager().curseforge;
+
+        let cfmod = curseforge
+            .get_mod(ModParameters {
+                mod_id: project_id as i32,
+            })
+            .await?
+            .data;
+
+        let class_matches = matches!(
+            (pack_type, cfmod.class_id),
+            (PackType::ResourcePack, Some(ClassId::ResourcePacks))
+                | (PackType::ShaderPack, Some(ClassId::Shaders))
+        );
+
+        if !class_matches {
+            bail!(
+                "curseforge project {project_id} is not a {}",
+                pack_type.to_string()
+            );
+        }
+
+        let file = curseforge
+            .get_mod_file(ModFileParameters {
+                mod_id: project_id as i32,
+                file_id: file_id as i32,
+            })
+            .await?
+            .data;
+
+        let download_url = file
+            .download_url
+            .clone()
+            .ok_or_else(|| anyhow!("pack cannot be downloaded without privileged api key"))?;
+
+        check_file_name(&file.file_name)?;
+
+        let checksum = file
+            .hashes
+            .iter()
+            .find(|hash| matches!(hash.algo, HashAlgo::Sha1))
+            .map(|hash| Checksum::Sha1(hash.value.clone()));
+
+        let downloadable = Downloadable::new(
+            &download_url,
+            self.pack_path(instance_id, pack_type)
+                .await?
+                .join(&file.file_name),
+        )
+        .with_checksum(checksum)
+        .with_size(file.file_length as u64);
+
+        self.install_pack_file(
+            instance_id,
+            pack_type,
+            file.display_name,
+            downloadable,
+            replaces_pack_id,
+        )
+        .await
+    }
+
+    pub async fn install_modrinth_pack(
+        self,
+        instance_id: InstanceId,
+        pack_type: PackType,
+        project_id: String,
+        version_id: String,
+        replaces_pack_id: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        let modrinth = &self.app.modplatforms_manager().modrinth;
+
+        let project = modrinth.get_project(ProjectID(project_id.clone())).await?;
+
+        if project.project_type != modrinth_project_type(pack_type) {
+            bail!(
+                "modrinth project {project_id} is not a {}",
+                pack_type.to_string()
+            );
+        }
+
+        let version = modrinth.get_version(VersionID(version_id.clone())).await?;
+
+        if version.project_id != project.id {
+            bail!("modrinth version {version_id} does not belong to project {project_id}");
+        }
+
+        let file = version
+            .files
+            .iter()
+            .reduce(|a, b| if b.primary { b } else { a })
+            .ok_or_else(|| {
+                anyhow!(
+                    "Modrinth project '{project_id}' version '{version_id}' does not have a file"
+                )
+            })?;
+
+        check_file_name(&file.filename)?;
+
+        let downloadable = Downloadable::new(
+            &file.url,
+            self.pack_path(instance_id, pack_type)
+                .await?
+                .join(&file.filename),
+        )
+        .with_checksum(Some(Checksum::Sha1(file.hashes.sha1.clone())))
+        .with_size(file.size as u64);
+
+        self.install_pack_file(
+            instance_id,
+            pack_type,
+            version.name,
+            downloadable,
+            replaces_pack_id,
+        )
+        .await
+    }
+
+    async fn install_pack_file(
+        self,
+        instance_id: InstanceId,
+        pack_type: PackType,
+        display_name: String,
+        mut downloadable: Downloadable,
+        replaces_pack_id: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        let instance_name = {
+            let instances = self.instances.read().await;
+            let instance = instances
+                .get(&instance_id)
+                .ok_or(InvalidInstanceIdError(instance_id))?;
+
+            instance.data()?.config.name.clone()
+        };
+
+        let replaced_filename = match &replaces_pack_id {
+            Some(id) => Some(self.get_pack_entry(instance_id, id.clone()).await?.filename),
+            None => None,
+        };
+
+        let replaces_same_file = replaced_filename.is_some()
+            && replaced_filename.as_deref()
+                == downloadable.path.file_name().and_then(|name| name.to_str());
+
+        let mut disabled_path = downloadable.path.clone().into_os_string();
+        disabled_path.push(".disabled");
+
+        if !replaces_same_file
+            && (downloadable.path.exists() || PathBuf::from(disabled_path).exists())
+        {
+            bail!("pack is already installed");
+        }
+
+        // an update keeping the file name is downloaded aside until the old pack is removed
+        let target_path = downloadable.path.clone();
+        let tmpfile = match replaces_same_file {
+            true => {
+                let tmpfile = self
+                    .app
+                    .settings_manager()
+                    .runtime_path
+                    .get_temp()
+                    .maketmpfile()
+                    .await?;
+
+                downloadable.path = tmpfile.to_path_buf();
+                Some(tmpfile)
+            }
+            false => None,
+        };
+
+        let vtask = VisualTask::new(Translation::InstanceTaskInstallPack {
+            pack_name: display_name,
+            instance_name,
+        });
+
+        let t_download_file = vtask.subtask(Translation::InstanceTaskInstallModDownloadFile);
+
+        let task_id = self.app.task_manager().spawn_task(&vtask).await;
+        let app = self.app.clone();
+
+        tokio::spawn(async move {
+            let try_result: anyhow::Result<_> = async {
+                vtask
+                    .edit(|data| data.state = TaskState::KnownProgress)
+                    .await;
+
+                download_with_progress(&downloadable, t_download_file).await?;
+
+                if let Some(id) = replaces_pack_id {
+                    app.instance_manager().delete_pack(instance_id, id).await?;
+                }
+
+                if let Some(tmpfile) = tmpfile {
+                    tmpfile.try_rename_or_move(&target_path).await?;
+                }
+
+                // keep the task alive until the pack is cached
+                app.meta_cache_manager()
+                    .override_caching_and_wait(instance_id, true, true)
+                    .await?;
+
+                Ok(())
+            }
+            .await;
+
+            app.invalidate(pack_list_key(pack_type), Some(instance_id.0.into()));
+
+            if let Err(e) = try_result {
+                vtask.fail(e).await;
+            }
+        });
+
+        Ok(task_id)
+    }
+
+    /// Find the newest version of a pack for the instance's game version,
+    /// respecting the instance's (and the global) channel preference.
+    pub async fn find_pack_update(
+        self,
+        instance_id: InstanceId,
+        id: String,
+    ) -> anyhow::Result<Option<RemoteVersion>> {
+        let release = {
+            let instances = self.instances.read().await;
+            let instance = instances
+                .get(&instance_id)
+                .ok_or(InvalidInstanceIdError(instance_id))?;
+
+            match &instance.data()?.config.game_configuration.version {
+                Some(GameVersion::Standard(version)) => version.release.clone(),
+                _ => bail!("Instance uses a custom game version file. Cannot resolve minecraft version for pack updates"),
+            }
+        };
+
+        let mod_sources = self.get_instance_mod_sources(instance_id).await?;
+
+        let metadata = self
+            .get_pack_entry(instance_id, id)
+            .await?
+            .metadata
+            .ok_or_else(|| anyhow!("broken db state"))?;
+
+        let cf = metadata.curseforge.flatten().filter(|_| {
+            !mod_sources
+                .platform_blacklist
+                .contains(&ModPlatform::Curseforge)
+        });
+
+        let mr = metadata.modrinth.flatten().filter(|_| {
+            !mod_sources
+                .platform_blacklist
+                .contains(&ModPlatform::Modrinth)
+        });
+
+        let mut versions = Vec::new();
+
+        if let Some(cf) = &cf {
+            let response = self
+                .app
+                .modplatforms_manager()
+                .curseforge
+                .get_mod_files(ModFilesParameters {
+                    mod_id: cf.project_id,
+                    query: ModFilesParametersQuery {
+                        game_version: Some(release.clone()),
+                        game_version_type_id: None,
+                        mod_loader_type: None,
+                        index: None,
+                        page_size: None,
+                    },
+                })
+                .await?;
+
+            versions.extend(response.data.into_iter().map(RemoteVersion::Curseforge));
+        }
+
+        if let Some(mr) = &mr {
+            let response = self
+                .app
+                .modplatforms_manager()
+                .modrinth
+                .get_project_versions(ProjectVersionsFilters {
+                    project_id: ProjectID(mr.project_id.clone()),
+                    game_versions: Some(vec![release.clone()]),
+                    loaders: None,
+                    limit: None,
+                    offset: None,
+                })
+                .await?;
+
+            versions.extend(response.into_iter().map(RemoteVersion::Modrinth));
+        }
+
+        // newest first
+        versions.sort();
+
+        let is_installed = |version: &RemoteVersion| match version {
+            RemoteVersion::Curseforge(file) => cf.as_ref().is_some_and(|cf| cf.file_id == file.id),
+            RemoteVersion::Modrinth(version) => {
+                mr.as_ref().is_some_and(|mr| mr.version_id == version.id)
+            }
+        };
+
+        let allows_updates = |version: &RemoteVersion| {
+            let channel = match version {
+                RemoteVersion::Curseforge(file) => ModChannel::from(file.release_type),
+                RemoteVersion::Modrinth(version) => ModChannel::from(version.version_type),
+            };
+
+            mod_sources
+                .channels
+                .iter()
+                .any(|c| c.channel == channel && c.allow_updates)
+        };
+
+        let installed = versions.iter().position(is_installed);
+        let newest = versions.iter().position(allows_updates);
+
+        let update = match (newest, installed) {
+            (Some(newest), Some(installed)) if newest < installed => Some(newest),
+            (Some(newest), None) => Some(newest),
+            _ => None,
+        };
+
+        Ok(update.map(|i| versions.swap_remove(i)))
+    }
+
+    pub async fn update_pack(
+        self,
+        instance_id: InstanceId,
+        id: String,
+    ) -> anyhow::Result<VisualTaskId> {
+        let pack = self.get_pack_entry(instance_id, id.clone()).await?;
+        let pack_type = PackType::try_from(&pack.pack_type as &str)?;
+
+        match self.find_pack_update(instance_id, id.clone()).await? {
+            Some(RemoteVersion::Curseforge(file)) => {
+                self.install_curseforge_pack(
+                    instance_id,
+                    pack_type,
+                    file.mod_id as u32,
+                    file.id as u32,
+                    Some(id),
+                )
+                .await
+            }
+            Some(RemoteVersion::Modrinth(version)) => {
+                self.install_modrinth_pack(
+                    instance_id,
+                    pack_type,
+                    version.project_id,
+                    version.id,
+                    Some(id),
+                )
+                .await
+            }
+            None => bail!("unable to find newer pack version in availible update channels"),
+        }
+    }
+
+    pub async fn get_pack_icon(
+        self,
+        instance_id: InstanceId,
+        id: String,
+        platformid: i32,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let metadata = self
+            .get_pack_entry(instance_id, id)
+            .await?
+            .metadata
+            .ok_or_else(|| anyhow!("broken db state"))?;
+
+        let logo_image = match platformid {
+            0 => metadata.logo_image.flatten().map(|img| img.data),
+            1 => metadata
+                .curseforge
+                .flatten()
+                .and_then(|cf| cf.logo_image.flatten())
+                .and_then(|img| img.data),
+            2 => metadata
+                .modrinth
+                .flatten()
+                .and_then(|mr| mr.logo_image.flatten())
+                .and_then(|img| img.data),
+            _ => bail!("unsupported platform"),
+        };
+
+        Ok(logo_image)
+    }
+
+    /// True when the instance has shader packs enabled but no mod that can load them.
+    pub async fn missing_shader_loader(self, instance_id: InstanceId) -> anyhow::Result<bool> {
+        let shaderpacks_path = self.pack_path(instance_id, PackType::ShaderPack).await?;
+
+        if !shaderpacks_path.is_dir() {
+            return Ok(false);
+        }
+
+        // unpacked shader packs are not cached, so look at the folder itself
+        let mut has_shaders = false;
+        let mut entries = tokio::fs::read_dir(shaderpacks_path).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+
+            if file_name.ends_with(".zip") || entry.file_type().await?.is_dir() {
+                has_shaders = true;
+                break;
+            }
+        }
+
+        if !has_shaders {
+            return Ok(false);
+        }
+
+        let mods = self.list_mods(instance_id).await?;
+        Ok(!mods.iter().any(|m| m.enabled && is_shader_loader(m)))
+    }
+}
+
+/// Downloads a file while reporting progress on `subtask`, removing partial downloads on failure.
+pub(super) async fn download_with_progress(
+    downloadable: &Downloadable,
+    subtask: Subtask,
+) -> anyhow::Result<()> {
+    let (progress_watch_tx, mut progress_watch_rx) =
+        tokio::sync::watch::channel(carbon_net::Progress::new());
+
+    // dropped when the sender is dropped
+    tokio::spawn(async move {
+        while progress_watch_rx.changed().await.is_ok() {
+            {
+                let progress = progress_watch_rx.borrow();
+                subtask.update_download(
+                    progress.current_size as u32,
+                    progress.total_size as u32,
+                    false,
+                );
+            }
+
+            tokio::time::sleep(Duration::from_millis(30)).await;
+        }
+
+        subtask.complete_download();
+    });
+
+    if let Err(e) = carbon_net::download_file(downloadable, Some(progress_watch_tx)).await {
+        let _ = tokio::fs::remove_file(&downloadable.path).await;
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+/// Mod ids and project slugs of mods that load shader packs.
+const SHADER_LOADERS: &[&str] = &[
+    "iris",
+    "irisshaders",
+    "oculus",
+    "optifine",
+    "optifabric",
+    "optiforge",
+];
+
+fn is_shader_loader(m: &domain::Mod) -> bool {
+    let modid = m.metadata.as_ref().and_then(|meta| meta.modid.as_deref());
+    let cf_slug = m.curseforge.as_ref().map(|cf| &cf.urlslug as &str);
+    let mr_slug = m.modrinth.as_ref().map(|mr| &mr.urlslug as &str);
+
+    let known_id = [modid, cf_slug, mr_slug]
+        .into_iter()
+        .flatten()
+        .any(|id| SHADER_LOADERS.contains(&&*id.to_lowercase()));
+
+    // optifine jars usually don't carry loader metadata
+    known_id || m.filename.to_lowercase().contains("optifine")
+}
+
+/// Query listing the packs of a type, invalidated when they change.
+pub(crate) fn pack_list_key(pack_type: PackType) -> Key {
+    match pack_type {
+        PackType::ResourcePack => INSTANCE_RESOURCE_PACKS,
+        PackType::ShaderPack => INSTANCE_SHADER_PACKS,
+    }
+}
+
+fn modrinth_project_type(pack_type: PackType) -> ProjectType {
+    match pack_type {
+        PackType::ResourcePack => ProjectType::ResourcePack,
+        PackType::ShaderPack => ProjectType::Shader,
+    }
+}
+
+fn file_metadata(m: metadb::Data) -> domain::ModFileMetadata {
+    domain::ModFileMetadata {
+        has_image: m.logo_image.flatten().is_some(),
+        id: m.id,
+        modid: m.modid,
+        name: m.name,
+        version: m.version,
+        description: m.description,
+        authors: m.authors,
+        modloaders: Vec::new(),
+        sha_512: m.sha_512,
+        sha_1: m.sha_1,
+        murmur_2: m.murmur_2,
+    }
+}
+
+fn curseforge_metadata(cf: cfdb::Data) -> domain::CurseForgeModMetadata {
+    domain::CurseForgeModMetadata {
+        has_image: cf
+            .logo_image
+            .flatten()
+            .is_some_and(|row| row.data.is_some()),
+        project_id: cf.project_id as u32,
+        file_id: cf.file_id as u32,
+        name: cf.name,
+        version: cf.version,
+        urlslug: cf.urlslug,
+        summary: cf.summary,
+        authors: cf.authors,
+    }
+}
+
+fn modrinth_metadata(mr: mrdb::Data) -> domain::ModrinthModMetadata {
+    domain::ModrinthModMetadata {
+        has_image: mr
+            .logo_image
+            .flatten()
+            .is_some_and(|row| row.data.is_some()),
+        project_id: mr.project_id,
+        version_id: mr.version_id,
+        title: mr.title,
+        version: mr.version,
+        urlslug: mr.urlslug,
+        description: mr.description,
+        authors: mr.authors,
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("invalid pack id '{1}' given for instance '{0}'")]
+pub struct InvalidInstancePackIdError(InstanceId, String);
+
+#[cfg(test)]
+mod test {
+    use crate::domain::instance::{Mod, ModFileMetadata};
+
+    use super::is_shader_loader;
+
+    fn local_mod(filename: &str, modid: Option<&str>) -> Mod {
+        Mod {
+            id: String::new(),
+            filename: filename.to_string(),
+            enabled: true,
+            metadata: Some(ModFileMetadata {
+                id: String::new(),
+                modid: modid.map(ToString::to_string),
+                name: None,
+                version: None,
+                description: None,
+                authors: None,
+                modloaders: Vec::new(),
+                sha_512: Vec::new(),
+                sha_1: Vec::new(),
+                murmur_2: 0,
+                has_image: false,
+            }),
+            curseforge: None,
+            modrinth: None,
+            has_update: false,
+        }
+    }
+
+    #[test]
+    fn shader_loaders() {
+        assert!(is_shader_loader(&local_mod(
+            "iris-mc1.20.1-1.6.4.jar",
+            Some("iris")
+        )));
+        assert!(is_shader_loader(&local_mod("oculus.jar", Some("oculus"))));
+        assert!(is_shader_loader(&local_mod(
+            "OptiFine_1.20.1_HD_U_I6.jar",
+            None
+        )));
+        assert!(!is_shader_loader(&local_mod("sodium.jar", Some("sodium"))));
+        assert!(!is_shader_loader(&local_mod("unknown.jar", None)));
+    }
+}
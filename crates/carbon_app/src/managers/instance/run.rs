@@ -10,8 +10,8 @@ use crate::managers::java::java_checker::{JavaChecker, RealJavaChecker};
 use crate::managers::java::managed::Step;
 use crate::managers::minecraft::assets::get_assets_dir;
 use crate::managers::minecraft::minecraft::get_lwjgl_meta;
-use crate::managers::minecraft::modrinth;
 use crate::managers::minecraft::{curseforge, UpdateValue};
+use crate::managers::minecraft::{modrinth, server};
 use crate::managers::modplatforms::curseforge::convert_cf_version_to_standard_version;
 use crate::managers::modplatforms::modrinth::convert_mr_version_to_standard_version;
 use crate::managers::vtask::Subtask;
@@ -34,7 +34,9 @@ use crate::{
     },
 };
 use anyhow::{anyhow, bail, Context};
+use carbon_net::{Checksum, Downloadable};
 use chrono::{DateTime, Local, Utc};
+use daedalus::minecraft::DownloadType;
 use futures::Future;
 use itertools::Itertools;
 use md5::{Digest, Md5};
@@ -45,9 +47,13 @@ use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::process::ChildStdin;
 use tokio::sync::{watch, Mutex, Semaphore};
 use tokio::task::JoinHandle;
-use tokio::{io::AsyncReadExt, sync::mpsc};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    sync::mpsc,
+};
 use tracing::{debug, info, trace};
 
 #[derive(Debug)]
@@ -76,6 +82,7 @@ impl ManagerRef<'_, InstanceManager> {
         self,
         instance_id: InstanceId,
         launch_account: Option<FullAccount>,
+        quick_play: Option<domain::QuickPlay>,
         callback_task: Option<InstanceCallback>,
         deep_check: bool,
     ) -> anyhow::Result<(JoinHandle<()>, VisualTaskId)> {
@@ -200,6 +207,18 @@ impl ManagerRef<'_, InstanceManager> {
 
         let java_override = config.game_configuration.java_override.clone();
 
+        let server_eula = match config.kind {
+            info::InstanceKind::Client => None,
+            info::InstanceKind::Server { eula_accepted } => Some(eula_accepted),
+        };
+
+        let shared_servers = config.shared_servers && server_eula.is_none();
+        let shared_options = config.shared_options && server_eula.is_none();
+
+        if quick_play.is_some() && server_eula.is_some() {
+            bail!("quick play is not available for server instances");
+        }
+
         let runtime_path = self.app.settings_manager().runtime_path.clone();
         let instance_path = runtime_path
             .get_instances()
@@ -207,9 +226,22 @@ impl ManagerRef<'_, InstanceManager> {
 
         tracing::debug!("instance path: {:?}", instance_path);
 
+        let mut custom_version = None;
         let mut version = match config.game_configuration.version {
             Some(GameVersion::Standard(ref v)) => Some(v.clone()),
-            Some(GameVersion::Custom(_)) => bail!("Custom versions are not supported yet"),
+            Some(GameVersion::Custom(ref id)) => {
+                let partial = read_custom_version(&instance_path)
+                    .await
+                    .with_context(|| format!("Failed to read custom version `{id}`"))?;
+
+                let version = StandardVersion {
+                    release: partial.inherits_from.clone(),
+                    modloaders: HashSet::new(),
+                };
+
+                custom_version = Some(partial);
+                Some(version)
+            }
             None if config.modpack.as_ref().is_some() => None,
             None => bail!("Instance has no associated game version and cannot be launched"),
         };
@@ -292,16 +324,26 @@ impl ManagerRef<'_, InstanceManager> {
 
                 let t_fill_cache = task.subtask(Translation::InstanceTaskFillCache);
 
-                let t_extract_natives = task.subtask(Translation::InstanceTaskLaunchExtractNatives);
-
-                let t_reconstruct_assets = task.subtask(Translation::InstanceTaskReconstructAssets);
+                let (t_extract_natives, t_reconstruct_assets, t_install_server) =
+                    match server_eula {
+                        None => (
+                            Some(task.subtask(Translation::InstanceTaskLaunchExtractNatives)),
+                            Some(task.subtask(Translation::InstanceTaskReconstructAssets)),
+                            None,
+                        ),
+                        Some(_) => (
+                            None,
+                            None,
+                            Some(task.subtask(Translation::InstanceTaskLaunchInstallServer)),
+                        ),
+                    };
 
-                let t_forge_processors = match is_first_run {
+                let t_forge_processors = match is_first_run && server_eula.is_none() {
                     true => Some(task.subtask(Translation::InstanceTaskLaunchRunForgeProcessors)),
                     false => None,
                 };
 
-                let t_neoforge_processors = match is_first_run {
+                let t_neoforge_processors = match is_first_run && server_eula.is_none() {
                     true => {
                         Some(task.subtask(Translation::InstanceTaskLaunchRunNeoforgeProcessors))
                     }
@@ -871,6 +913,11 @@ impl ManagerRef<'_, InstanceManager> {
                     .await
                     .map_err(|e| anyhow::anyhow!("Error getting minecraft version: {:?}", e))?;
 
+                if let Some(custom_version) = custom_version {
+                    version_info =
+                        daedalus::modded::merge_partial_version(custom_version, version_info);
+                }
+
                 let lwjgl_group = get_lwjgl_meta(
                     Arc::clone(&app.prisma_client),
                     &app.reqwest_client,
@@ -1189,11 +1236,34 @@ impl ManagerRef<'_, InstanceManager> {
 
                 t_request_version_info.update_items(2, 2);
 
-                downloads.extend(
-                    app.minecraft_manager()
-                        .get_all_version_info_files(version_info.clone(), &java.arch)
-                        .await?,
-                );
+                let server_setup = server_eula.map(|_| {
+                    server::server_setup(&version.release, version.modloaders.iter().next(), false)
+                });
+
+                match &server_setup {
+                    None => downloads.extend(
+                        app.minecraft_manager()
+                            .get_all_version_info_files(version_info.clone(), &java.arch)
+                            .await?,
+                    ),
+                    Some(setup) => {
+                        let server = version_info
+                            .downloads
+                            .get(&DownloadType::Server)
+                            .ok_or_else(|| {
+                                anyhow!("Minecraft {} has no dedicated server", version.release)
+                            })?;
+
+                        downloads.push(
+                            Downloadable::new(
+                                &server.url,
+                                instance_path.get_data_path().join(&setup.server_jar),
+                            )
+                            .with_checksum(Some(Checksum::Sha1(server.sha1.clone())))
+                            .with_size(server.size as u64),
+                        );
+                    }
+                }
 
                 let concurrency = app
                     .settings_manager()
@@ -1331,59 +1401,149 @@ impl ManagerRef<'_, InstanceManager> {
                     trace!("queued metadata caching");
                 }
 
-                t_extract_natives.start_opaque();
-                managers::minecraft::minecraft::extract_natives(
-                    &runtime_path,
-                    &version_info,
-                    &lwjgl_group,
-                    &java.arch,
-                )
-                .await?;
-                t_extract_natives.complete_opaque();
+                enum LaunchTarget {
+                    Client(managers::minecraft::assets::AssetsDir),
+                    Server(server::ServerSetup),
+                }
 
-                t_reconstruct_assets.start_opaque();
-                managers::minecraft::assets::reconstruct_assets(
-                    Arc::clone(&app.prisma_client),
-                    app.reqwest_client.clone(),
-                    &version_info.asset_index,
-                    runtime_path.get_assets(),
-                    instance_path.get_resources_path(),
-                )
-                .await?;
-                t_reconstruct_assets.complete_opaque();
+                let launch_target = match server_setup {
+                    None => {
+                        let t_extract_natives = t_extract_natives
+                            .expect("client instances always have a natives subtask");
+                        let t_reconstruct_assets = t_reconstruct_assets
+                            .expect("client instances always have an assets subtask");
+
+                        t_extract_natives.start_opaque();
+                        managers::minecraft::minecraft::extract_natives(
+                            &runtime_path,
+                            &version_info,
+                            &lwjgl_group,
+                            &java.arch,
+                        )
+                        .await?;
+                        t_extract_natives.complete_opaque();
+
+                        t_reconstruct_assets.start_opaque();
+                        managers::minecraft::assets::reconstruct_assets(
+                            Arc::clone(&app.prisma_client),
+                            app.reqwest_client.clone(),
+                            &version_info.asset_index,
+                            runtime_path.get_assets(),
+                            instance_path.get_resources_path(),
+                        )
+                        .await?;
+                        t_reconstruct_assets.complete_opaque();
+
+                        let libraries_path = runtime_path.get_libraries();
+                        let game_version = version_info.id.to_string();
+                        let client_path = runtime_path.get_libraries().get_mc_client(
+                            version_info
+                                .inherits_from
+                                .as_ref()
+                                .unwrap_or(&version_info.id),
+                        );
+                        let assets_dir = get_assets_dir(
+                            app.prisma_client.clone(),
+                            app.reqwest_client.clone(),
+                            &version_info.asset_index,
+                            runtime_path.get_assets(),
+                            instance_path.get_resources_path(),
+                        )
+                        .await
+                        .unwrap();
 
-                let libraries_path = runtime_path.get_libraries();
-                let game_version = version_info.id.to_string();
-                let client_path = runtime_path.get_libraries().get_mc_client(
-                    version_info
-                        .inherits_from
-                        .as_ref()
-                        .unwrap_or(&version_info.id),
-                );
-                let assets_dir = get_assets_dir(
-                    app.prisma_client.clone(),
-                    app.reqwest_client.clone(),
-                    &version_info.asset_index,
-                    runtime_path.get_assets(),
-                    instance_path.get_resources_path(),
-                )
-                .await
-                .unwrap();
+                        for modloader in version.modloaders.iter() {
+                            let instance_path = instance_path.clone();
+                            let client_path = client_path.clone();
+                            let game_version = game_version.clone();
+                            let libraries_path = libraries_path.clone();
+
+                            match modloader {
+                                ModLoader {
+                                    type_: ModLoaderType::Forge,
+                                    ..
+                                } => {
+                                    if let Some(t_forge_processors) = &t_forge_processors {
+                                        t_forge_processors.start_opaque();
+
+                                        let _lock = instance_manager
+                                            .persistence_manager
+                                            .loader_install_lock
+                                            .acquire()
+                                            .await
+                                            .unwrap();
+
+                                        if let Some(processors) = &version_info.processors {
+                                            managers::minecraft::forge::execute_processors(
+                                                processors,
+                                                version_info.data.as_ref().ok_or_else(|| {
+                                                    anyhow::anyhow!("Data entries missing")
+                                                })?,
+                                                PathBuf::from(&java.path),
+                                                instance_path,
+                                                client_path,
+                                                game_version,
+                                                libraries_path,
+                                                Some(Box::new(|current, total| {
+                                                    t_forge_processors.update_items(current, total);
+                                                })),
+                                            )
+                                            .await?;
+                                        }
 
-                for modloader in version.modloaders.iter() {
-                    let instance_path = instance_path.clone();
-                    let client_path = client_path.clone();
-                    let game_version = game_version.clone();
-                    let libraries_path = libraries_path.clone();
+                                        t_forge_processors.complete_opaque();
+                                    }
+                                }
+                                ModLoader {
+                                    type_: ModLoaderType::Neoforge,
+                                    ..
+                                } => {
+                                    if let Some(t_neoforge_processors) = &t_neoforge_processors {
+                                        t_neoforge_processors.start_opaque();
+
+                                        let _lock = instance_manager
+                                            .persistence_manager
+                                            .loader_install_lock
+                                            .acquire()
+                                            .await
+                                            .unwrap();
+
+                                        if let Some(processors) = &version_info.processors {
+                                            managers::minecraft::neoforge::execute_processors(
+                                                processors,
+                                                version_info.data.as_ref().ok_or_else(|| {
+                                                    anyhow::anyhow!("Data entries missing")
+                                                })?,
+                                                PathBuf::from(&java.path),
+                                                instance_path.clone(),
+                                                client_path,
+                                                game_version,
+                                                libraries_path,
+                                                Some(Box::new(|current, total| {
+                                                    t_neoforge_processors.update_items(current, total);
+                                                })),
+                                            )
+                                            .await?;
+                                        }
 
-                    match modloader {
-                        ModLoader {
-                            type_: ModLoaderType::Forge,
-                            ..
-                        } => {
-                            if let Some(t_forge_processors) = &t_forge_processors {
-                                t_forge_processors.start_opaque();
+                                        t_neoforge_processors.complete_opaque();
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+
+                        LaunchTarget::Client(assets_dir)
+                    }
+                    Some(setup) => {
+                        let t_install_server = t_install_server
+                            .expect("server instances always have an install subtask");
+                        let data_path = instance_path.get_data_path();
 
+                        t_install_server.start_opaque();
+
+                        if let Some(installer) = &setup.installer {
+                            if !data_path.join(setup.launch.marker(cfg!(windows))).exists() {
                                 let _lock = instance_manager
                                     .persistence_manager
                                     .loader_install_lock
@@ -1391,65 +1551,38 @@ impl ManagerRef<'_, InstanceManager> {
                                     .await
                                     .unwrap();
 
-                                if let Some(processors) = &version_info.processors {
-                                    managers::minecraft::forge::execute_processors(
-                                        processors,
-                                        version_info.data.as_ref().ok_or_else(|| {
-                                            anyhow::anyhow!("Data entries missing")
-                                        })?,
-                                        PathBuf::from(&java.path),
-                                        instance_path,
-                                        client_path,
-                                        game_version,
-                                        libraries_path,
-                                        Some(Box::new(|current, total| {
-                                            t_forge_processors.update_items(current, total);
-                                        })),
-                                    )
-                                    .await?;
-                                }
+                                let installer_dir = runtime_path.get_temp().maketmpdir().await?;
+                                let installer_path = installer_dir.join(&installer.jar);
+                                let installer_url =
+                                    installer.resolve_url(&app.reqwest_client).await?;
 
-                                t_forge_processors.complete_opaque();
+                                carbon_net::download_multiple(
+                                    &[Downloadable::new(installer_url, &installer_path)],
+                                    None,
+                                    1,
+                                    deep_check,
+                                    false,
+                                )
+                                .await
+                                .context("Failed to download server installer")?;
+
+                                server::run_installer(&java, installer, &installer_path, &data_path)
+                                    .await?;
                             }
                         }
-                        ModLoader {
-                            type_: ModLoaderType::Neoforge,
-                            ..
-                        } => {
-                            if let Some(t_neoforge_processors) = &t_neoforge_processors {
-                                t_neoforge_processors.start_opaque();
 
-                                let _lock = instance_manager
-                                    .persistence_manager
-                                    .loader_install_lock
-                                    .acquire()
-                                    .await
-                                    .unwrap();
+                        let eula_accepted =
+                            server::check_eula(&data_path, server_eula.unwrap_or(false)).await?;
 
-                                if let Some(processors) = &version_info.processors {
-                                    managers::minecraft::neoforge::execute_processors(
-                                        processors,
-                                        version_info.data.as_ref().ok_or_else(|| {
-                                            anyhow::anyhow!("Data entries missing")
-                                        })?,
-                                        PathBuf::from(&java.path),
-                                        instance_path.clone(),
-                                        client_path,
-                                        game_version,
-                                        libraries_path,
-                                        Some(Box::new(|current, total| {
-                                            t_neoforge_processors.update_items(current, total);
-                                        })),
-                                    )
-                                    .await?;
-                                }
+                        t_install_server.complete_opaque();
 
-                                t_neoforge_processors.complete_opaque();
-                            }
+                        if launch_account.is_some() && !eula_accepted {
+                            bail!("The Minecraft EULA (https://aka.ms/MinecraftEULA) must be accepted before this server can be started");
                         }
-                        _ => {}
+
+                        LaunchTarget::Server(setup)
                     }
-                }
+                };
 
                 if is_first_run {
                     tokio::fs::remove_dir_all(setup_path).await?;
@@ -1457,6 +1590,24 @@ impl ManagerRef<'_, InstanceManager> {
 
                 match launch_account {
                     Some(account) => {
+                        if shared_servers {
+                            if let Err(e) = instance_manager
+                                .write_shared_servers(&instance_path.get_data_path())
+                                .await
+                            {
+                                tracing::error!({ error = ?e }, "Failed to write shared server list");
+                            }
+                        }
+
+                        if shared_options {
+                            if let Err(e) = instance_manager
+                                .write_shared_options(&instance_path.get_options_file_path())
+                                .await
+                            {
+                                tracing::error!({ error = ?e }, "Failed to merge shared options");
+                            }
+                        }
+
                         if let Some(pre_launch_hook) = pre_launch_hook.filter(|v| !v.is_empty()) {
                             let mut split = shlex::split(&pre_launch_hook)
                                 .ok_or_else(|| anyhow::anyhow!("Failed to parse pre-launch hook"))?
@@ -1490,23 +1641,40 @@ impl ManagerRef<'_, InstanceManager> {
                             );
                         }
 
-                        Ok(Some(
-                            managers::minecraft::minecraft::launch_minecraft(
-                                java,
-                                account,
-                                xmx_memory,
-                                xms_memory,
-                                game_resolution,
-                                &extra_java_args,
-                                &runtime_path,
-                                version_info,
-                                &lwjgl_group,
-                                instance_path.clone(),
-                                assets_dir,
-                                wrapper_command,
-                            )
-                            .await?,
-                        ))
+                        let child = match launch_target {
+                            LaunchTarget::Client(assets_dir) => {
+                                managers::minecraft::minecraft::launch_minecraft(
+                                    java,
+                                    account,
+                                    xmx_memory,
+                                    xms_memory,
+                                    game_resolution,
+                                    &extra_java_args,
+                                    &runtime_path,
+                                    version_info,
+                                    &lwjgl_group,
+                                    instance_path.clone(),
+                                    assets_dir,
+                                    wrapper_command,
+                                    quick_play.as_ref(),
+                                )
+                                .await?
+                            }
+                            LaunchTarget::Server(setup) => {
+                                server::launch_server(
+                                    java,
+                                    xmx_memory,
+                                    xms_memory,
+                                    &extra_java_args,
+                                    &setup,
+                                    instance_path.clone(),
+                                    wrapper_command,
+                                )
+                                .await?
+                            }
+                        };
+
+                        Ok(Some(child))
                     }
                     None => {
                         if let Some(callback_task) = callback_task {
@@ -1557,6 +1725,8 @@ impl ManagerRef<'_, InstanceManager> {
                         .await;
 
                     let (kill_tx, mut kill_rx) = mpsc::channel::<()>(1);
+                    let (command_tx, mut command_rx) = mpsc::channel::<String>(16);
+                    let stdin = child.stdin.take();
 
                     let start_time = Utc::now();
 
@@ -1567,6 +1737,7 @@ impl ManagerRef<'_, InstanceManager> {
                             LaunchState::Running(RunningInstance {
                                 process_id: child.id().expect("child process id is not present even though child process was started"),
                                 kill_tx,
+                                command_tx: stdin.is_some().then_some(command_tx),
                                 start_time,
                                 log: log_id,
                             }),
@@ -1603,6 +1774,8 @@ impl ManagerRef<'_, InstanceManager> {
                         _ = kill_rx.recv() => drop(child.kill().await),
                         // infallible, canceled by the above tasks
                         _ = read_logs(&log, &mut stdout,&mut  stderr) => {},
+                        // infallible, only server instances have a stdin to write to
+                        _ = forward_commands(&log, stdin, &mut command_rx) => {},
                         _ = update_playtime => {}
                     }
 
@@ -1682,6 +1855,15 @@ impl ManagerRef<'_, InstanceManager> {
                             LaunchState::Inactive { failed_task: None },
                         )
                         .await;
+
+                    // the instance is usable again while its worlds are zipped
+                    if let Err(e) = app
+                        .instance_manager()
+                        .backup_worlds_after_exit(instance_id)
+                        .await
+                    {
+                        tracing::error!({ error = ?e }, "Failed to back up worlds after exit");
+                    }
                 }
             }
 
@@ -1829,6 +2011,30 @@ impl ManagerRef<'_, InstanceManager> {
 
         Ok(())
     }
+
+    /// Sends a console command to a running server instance.
+    pub async fn send_instance_command(
+        self,
+        instance_id: InstanceId,
+        command: String,
+    ) -> anyhow::Result<()> {
+        let instances = self.instances.read().await;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or(InvalidInstanceIdError(instance_id))?;
+
+        let LaunchState::Running(running) = &instance.data()?.state else {
+            bail!("send_instance_command called on instance that was not running")
+        };
+
+        let Some(command_tx) = &running.command_tx else {
+            bail!("instance {instance_id} does not accept console commands")
+        };
+
+        command_tx.send(command).await?;
+
+        Ok(())
+    }
 }
 
 pub enum LaunchState {
@@ -1856,6 +2062,8 @@ impl Debug for LaunchState {
 pub struct RunningInstance {
     process_id: u32,
     kill_tx: mpsc::Sender<()>,
+    /// Console input, only present for server instances
+    command_tx: Option<mpsc::Sender<String>>,
     start_time: DateTime<Utc>,
     log: GameLogId,
 }
@@ -1878,6 +2086,14 @@ impl From<&LaunchState> for domain::LaunchState {
     }
 }
 
+/// Reads the version json of an instance using a `GameVersion::Custom` version.
+async fn read_custom_version(
+    instance_path: &InstancePath,
+) -> anyhow::Result<daedalus::modded::PartialVersionInfo> {
+    let text = tokio::fs::read_to_string(instance_path.get_custom_version_path()).await?;
+    Ok(serde_json::from_str(&text)?)
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashSet;
@@ -1917,7 +2133,7 @@ mod test {
         };
 
         app.instance_manager()
-            .prepare_game(instance_id, Some(account), None, true)
+            .prepare_game(instance_id, Some(account), None, None, true)
             .await?;
 
         let task = match app.instance_manager().get_launch_state(instance_id).await? {
@@ -1986,6 +2202,39 @@ async fn read_logs(
     }
 }
 
+/// Writes each received command to the process' stdin, echoing it to the log.
+///
+/// Never returns, so it can be raced against the process exiting.
+async fn forward_commands(
+    log: &watch::Sender<GameLog>,
+    stdin: Option<ChildStdin>,
+    commands: &mut mpsc::Receiver<String>,
+) {
+    if let Some(mut stdin) = stdin {
+        while let Some(command) = commands.recv().await {
+            log.send_modify(|log| log.add_entry(LogEntry::system_message(format!("> {command}"))));
+
+            let r = async {
+                stdin.write_all(format!("{command}\n").as_bytes()).await?;
+                stdin.flush().await
+            }
+            .await;
+
+            if let Err(err) = r {
+                tracing::error!("failed to write command to stdin:\n{err:#?}");
+
+                log.send_modify(|log| {
+                    log.add_entry(LogEntry::system_error(format!(
+                        "failed to send command `{command}`"
+                    )))
+                });
+            }
+        }
+    }
+
+    futures::future::pending().await
+}
+
 /// Performs a single poll for data from the given pipe.
 ///
 /// Returns `true` when a line was fully received, at which point it is
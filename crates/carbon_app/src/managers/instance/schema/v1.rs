@@ -34,6 +34,47 @@ pub struct Instance {
     pub mod_sources: Option<ModSources>,
     #[serde(default)]
     pub notes: String,
+    #[serde(default, skip_serializing_if = "InstanceKind::is_client")]
+    pub kind: InstanceKind,
+    #[serde(default)]
+    pub shared_servers: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_backups: Option<WorldBackups>,
+    #[serde(default)]
+    pub shared_options: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorldBackups {
+    #[serde(default)]
+    pub interval: Option<u32>,
+    #[serde(default)]
+    pub after_exit: bool,
+    pub keep: u32,
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum InstanceKind {
+    Client,
+    Server {
+        #[serde(default)]
+        eula_accepted: bool,
+    },
+}
+
+impl InstanceKind {
+    fn is_client(&self) -> bool {
+        matches!(self, Self::Client)
+    }
+}
+
+impl Default for InstanceKind {
+    fn default() -> Self {
+        Self::Client
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -253,6 +294,10 @@ impl From<Instance> for info::Instance {
             wrapper_command: value.wrapper_command,
             mod_sources: value.mod_sources.map(Into::into),
             notes: value.notes,
+            kind: value.kind.into(),
+            shared_servers: value.shared_servers,
+            world_backups: value.world_backups.map(Into::into),
+            shared_options: value.shared_options,
         }
     }
 }
@@ -273,6 +318,54 @@ impl From<info::Instance> for Instance {
             wrapper_command: value.wrapper_command,
             mod_sources: value.mod_sources.map(Into::into),
             notes: value.notes,
+            kind: value.kind.into(),
+            shared_servers: value.shared_servers,
+            world_backups: value.world_backups.map(Into::into),
+            shared_options: value.shared_options,
+        }
+    }
+}
+
+impl From<WorldBackups> for info::WorldBackups {
+    fn from(value: WorldBackups) -> Self {
+        Self {
+            interval: value.interval,
+            after_exit: value.after_exit,
+            keep: value.keep,
+            max_age_days: value.max_age_days,
+        }
+    }
+}
+
+impl From<info::WorldBackups> for WorldBackups {
+    fn from(value: info::WorldBackups) -> Self {
+        Self {
+            interval: value.interval,
+            after_exit: value.after_exit,
+            keep: value.keep,
+            max_age_days: value.max_age_days,
+        }
+    }
+}
+
+impl From<InstanceKind> for info::InstanceKind {
+    fn from(value: InstanceKind) -> Self {
+        use InstanceKind as Schema;
+
+        match value {
+            Schema::Client => Self::Client,
+            Schema::Server { eula_accepted } => Self::Server { eula_accepted },
+        }
+    }
+}
+
+impl From<info::InstanceKind> for InstanceKind {
+    fn from(value: info::InstanceKind) -> Self {
+        use info::InstanceKind as Info;
+
+        match value {
+            Info::Client => Self::Client,
+            Info::Server { eula_accepted } => Self::Server { eula_accepted },
         }
     }
 }
@@ -0,0 +1,293 @@
+use std::fs::Metadata;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::bail;
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use image::{DynamicImage, ImageOutputFormat};
+use sha1::{Digest, Sha1};
+use tracing::warn;
+
+use crate::api::keys::instance::GET_SCREENSHOTS;
+use crate::domain::instance::{InstanceId, Screenshot};
+use crate::managers::ManagerRef;
+
+use super::worlds::check_file_name;
+use super::{InstanceManager, InvalidInstanceIdError};
+
+/// Thumbnails fit within a square of this size, keeping their aspect ratio.
+const THUMBNAIL_SIZE: u32 = 320;
+
+impl ManagerRef<'_, InstanceManager> {
+    async fn screenshots_path(self, instance_id: InstanceId) -> anyhow::Result<PathBuf> {
+        let instances = self.instances.read().await;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or(InvalidInstanceIdError(instance_id))?;
+
+        Ok(self
+            .app
+            .settings_manager()
+            .runtime_path
+            .get_instances()
+            .get_instance_path(&instance.shortpath)
+            .get_screenshots_path())
+    }
+
+    /// Lists the screenshots of one instance, or of every instance when unset, newest first.
+    pub async fn list_screenshots(
+        self,
+        instance_id: Option<InstanceId>,
+    ) -> anyhow::Result<Vec<Screenshot>> {
+        let folders = match instance_id {
+            Some(instance_id) => vec![(instance_id, self.screenshots_path(instance_id).await?)],
+            None => {
+                let instances_path = self.app.settings_manager().runtime_path.get_instances();
+
+                self.instances
+                    .read()
+                    .await
+                    .iter()
+                    .map(|(instance_id, instance)| {
+                        (
+                            *instance_id,
+                            instances_path
+                                .get_instance_path(&instance.shortpath)
+                                .get_screenshots_path(),
+                        )
+                    })
+                    .collect()
+            }
+        };
+
+        let mut screenshots = tokio::task::spawn_blocking(move || {
+            let mut screenshots = Vec::new();
+
+            for (instance_id, path) in folders {
+                screenshots.extend(read_screenshots(instance_id, &path)?);
+            }
+
+            Ok::<_, anyhow::Error>(screenshots)
+        })
+        .await??;
+
+        screenshots.sort_by(|a, b| b.taken_at.cmp(&a.taken_at));
+
+        Ok(screenshots)
+    }
+
+    pub async fn get_screenshot(
+        self,
+        instance_id: InstanceId,
+        file_name: String,
+    ) -> anyhow::Result<Vec<u8>> {
+        let path = self.get_screenshot_path(instance_id, file_name).await?;
+        Ok(tokio::fs::read(path).await?)
+    }
+
+    /// Downscaled version of a screenshot, cached until the screenshot changes.
+    pub async fn get_screenshot_thumbnail(
+        self,
+        instance_id: InstanceId,
+        file_name: String,
+    ) -> anyhow::Result<Vec<u8>> {
+        let path = self.get_screenshot_path(instance_id, file_name).await?;
+        let metadata = tokio::fs::metadata(&path).await?;
+
+        let runtime_path = &self.app.settings_manager().runtime_path;
+        let thumbnails_path = runtime_path.get_cache().get_thumbnails_path();
+        let thumbnail_path = thumbnails_path.join(thumbnail_name(&path, &metadata));
+
+        match tokio::fs::read(&thumbnail_path).await {
+            Ok(thumbnail) => return Ok(thumbnail),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let thumbnail = tokio::task::spawn_blocking(move || make_thumbnail(&path)).await??;
+
+        tokio::fs::create_dir_all(&thumbnails_path).await?;
+        runtime_path
+            .get_temp()
+            .write_file_atomic(&thumbnail_path, &thumbnail)
+            .await?;
+
+        Ok(thumbnail)
+    }
+
+    /// Absolute path of a screenshot, for copying it elsewhere.
+    pub async fn get_screenshot_path(
+        self,
+        instance_id: InstanceId,
+        file_name: String,
+    ) -> anyhow::Result<PathBuf> {
+        let screenshots_path = self.screenshots_path(instance_id).await?;
+        check_file_name(&file_name)?;
+
+        let path = screenshots_path.join(&file_name);
+
+        if !is_image(&file_name) || !path.is_file() {
+            bail!("screenshot {file_name} does not exist");
+        }
+
+        Ok(path)
+    }
+
+    pub async fn delete_screenshot(
+        self,
+        instance_id: InstanceId,
+        file_name: String,
+    ) -> anyhow::Result<()> {
+        let path = self.get_screenshot_path(instance_id, file_name).await?;
+        let metadata = tokio::fs::metadata(&path).await?;
+
+        let thumbnail_path = self
+            .app
+            .settings_manager()
+            .runtime_path
+            .get_cache()
+            .get_thumbnails_path()
+            .join(thumbnail_name(&path, &metadata));
+
+        let should_go_to_trash = self
+            .app
+            .settings_manager()
+            .get_settings()
+            .await?
+            .deletion_through_recycle_bin;
+
+        tokio::task::spawn_blocking(move || {
+            if should_go_to_trash {
+                trash::delete(&path)?;
+            } else {
+                std::fs::remove_file(&path)?;
+            }
+
+            // the thumbnail may not have been generated
+            let _ = std::fs::remove_file(thumbnail_path);
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .await??;
+
+        self.app.invalidate(GET_SCREENSHOTS, None);
+        Ok(())
+    }
+}
+
+fn is_image(file_name: &str) -> bool {
+    let file_name = file_name.to_ascii_lowercase();
+    [".png", ".jpg", ".jpeg"]
+        .iter()
+        .any(|extension| file_name.ends_with(extension))
+}
+
+fn read_screenshots(instance_id: InstanceId, path: &Path) -> anyhow::Result<Vec<Screenshot>> {
+    if !path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut screenshots = Vec::new();
+
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+
+        let Ok(file_name) = entry.file_name().into_string() else {
+            continue;
+        };
+
+        let metadata = entry.metadata()?;
+
+        if !metadata.is_file() || !is_image(&file_name) {
+            continue;
+        }
+
+        let (width, height) = match image::image_dimensions(entry.path()) {
+            Ok(dimensions) => dimensions,
+            Err(e) => {
+                warn!({ error = ?e }, "Skipping unreadable screenshot {:?}", entry.path());
+                continue;
+            }
+        };
+
+        let taken_at = match parse_screenshot_name(&file_name)
+            .and_then(|time| Local.from_local_datetime(&time).earliest())
+        {
+            Some(time) => time.with_timezone(&Utc),
+            None => DateTime::<Utc>::from(metadata.modified()?),
+        };
+
+        screenshots.push(Screenshot {
+            instance_id,
+            file_name,
+            taken_at,
+            width,
+            height,
+            size: metadata.len(),
+        });
+    }
+
+    Ok(screenshots)
+}
+
+/// Reads the local time out of the names the game gives screenshots,
+/// like `2024-05-01_12.34.56.png` or `2024-05-01_12.34.56_2.png`.
+fn parse_screenshot_name(file_name: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(file_name.get(..19)?, "%Y-%m-%d_%H.%M.%S").ok()
+}
+
+/// Names thumbnails after the screenshot path, size and modification time,
+/// so a changed screenshot gets a new thumbnail.
+fn thumbnail_name(path: &Path, metadata: &Metadata) -> String {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|time| time.as_nanos())
+        .unwrap_or_default();
+
+    let mut hasher = Sha1::new();
+    hasher.update(path.to_string_lossy().as_bytes());
+    hasher.update(metadata.len().to_le_bytes());
+    hasher.update(modified.to_le_bytes());
+
+    format!("{}.jpg", hex::encode(hasher.finalize()))
+}
+
+fn make_thumbnail(path: &Path) -> anyhow::Result<Vec<u8>> {
+    let image = image::io::Reader::open(path)?
+        .with_guessed_format()?
+        .decode()?;
+
+    // jpeg has no alpha channel
+    let thumbnail =
+        DynamicImage::ImageRgb8(image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgb8());
+
+    let mut output = Vec::new();
+    thumbnail.write_to(&mut Cursor::new(&mut output), ImageOutputFormat::Jpeg(85))?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::parse_screenshot_name;
+
+    #[test]
+    fn screenshot_names() {
+        let time = NaiveDate::from_ymd_opt(2024, 5, 1)
+            .unwrap()
+            .and_hms_opt(12, 34, 56)
+            .unwrap();
+
+        assert_eq!(parse_screenshot_name("2024-05-01_12.34.56.png"), Some(time));
+        assert_eq!(
+            parse_screenshot_name("2024-05-01_12.34.56_2.png"),
+            Some(time)
+        );
+        assert_eq!(parse_screenshot_name("panorama_0.png"), None);
+        assert_eq!(parse_screenshot_name("2024.png"), None);
+    }
+}
@@ -0,0 +1,363 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail};
+use carbon_parsing::nbt::{self, Compound, Compression, List, Tag, TagKind};
+use prisma_client_rust::Direction;
+
+use crate::api::keys::instance::{GET_SERVERS, GET_SHARED_SERVERS};
+use crate::db::shared_server;
+use crate::domain::instance::{InstanceId, ServerEntry};
+use crate::managers::ManagerRef;
+
+use super::{InstanceManager, InvalidInstanceIdError};
+
+impl ManagerRef<'_, InstanceManager> {
+    async fn servers_dat_path(self, instance_id: InstanceId) -> anyhow::Result<PathBuf> {
+        let instances = self.instances.read().await;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or(InvalidInstanceIdError(instance_id))?;
+
+        Ok(self
+            .app
+            .settings_manager()
+            .runtime_path
+            .get_instances()
+            .get_instance_path(&instance.shortpath)
+            .get_data_path()
+            .join("servers.dat"))
+    }
+
+    async fn edit_servers(
+        self,
+        instance_id: InstanceId,
+        edit: impl FnOnce(&mut Vec<Compound<'static>>) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let path = self.servers_dat_path(instance_id).await?;
+        let mut servers = read_servers_dat(&path).await?;
+
+        edit(&mut servers)?;
+
+        self.write_servers_dat(&path, servers).await?;
+        self.app.invalidate(GET_SERVERS, Some(instance_id.0.into()));
+
+        Ok(())
+    }
+
+    async fn write_servers_dat(
+        self,
+        path: &Path,
+        servers: Vec<Compound<'static>>,
+    ) -> anyhow::Result<()> {
+        let mut root = Compound::new();
+        root.insert(
+            "servers",
+            Tag::List(List {
+                kind: TagKind::Compound,
+                items: servers.into_iter().map(Tag::Compound).collect(),
+            }),
+        );
+
+        let bytes = nbt::to_bytes("", &Tag::Compound(root), Compression::None)?;
+
+        self.app
+            .settings_manager()
+            .runtime_path
+            .get_temp()
+            .write_file_atomic(path, bytes)
+            .await
+    }
+
+    pub async fn list_servers(self, instance_id: InstanceId) -> anyhow::Result<Vec<ServerEntry>> {
+        let path = self.servers_dat_path(instance_id).await?;
+
+        Ok(read_servers_dat(&path)
+            .await?
+            .iter()
+            .filter(|server| !is_hidden(server))
+            .map(server_from_nbt)
+            .collect())
+    }
+
+    pub async fn add_server(
+        self,
+        instance_id: InstanceId,
+        server: ServerEntry,
+    ) -> anyhow::Result<()> {
+        self.edit_servers(instance_id, |servers| {
+            servers.push(server_to_nbt(&server, Compound::new()));
+            Ok(())
+        })
+        .await
+    }
+
+    /// Replaces the server at `index`, keeping any fields the launcher doesn't manage.
+    pub async fn update_server(
+        self,
+        instance_id: InstanceId,
+        index: usize,
+        server: ServerEntry,
+    ) -> anyhow::Result<()> {
+        self.edit_servers(instance_id, |servers| {
+            let index = visible_index(servers, index)?;
+            let existing = std::mem::take(&mut servers[index]);
+            servers[index] = server_to_nbt(&server, existing);
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn remove_server(self, instance_id: InstanceId, index: usize) -> anyhow::Result<()> {
+        self.edit_servers(instance_id, |servers| {
+            let index = visible_index(servers, index)?;
+            servers.remove(index);
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn move_server(
+        self,
+        instance_id: InstanceId,
+        from: usize,
+        to: usize,
+    ) -> anyhow::Result<()> {
+        self.edit_servers(instance_id, |servers| {
+            let from = visible_index(servers, from)?;
+            let to = visible_index(servers, to)?;
+            let server = servers.remove(from);
+            servers.insert(to, server);
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_shared_servers(self) -> anyhow::Result<Vec<ServerEntry>> {
+        Ok(self
+            .app
+            .prisma_client
+            .shared_server()
+            .find_many(vec![])
+            .order_by(shared_server::OrderByParam::Position(Direction::Asc))
+            .exec()
+            .await?
+            .into_iter()
+            .map(|server| ServerEntry {
+                name: server.name,
+                ip: server.ip,
+                icon: server.icon,
+                accept_textures: server.accept_textures,
+            })
+            .collect())
+    }
+
+    /// Replaces the shared server list, which is written into opted in instances before launch.
+    pub async fn set_shared_servers(self, servers: Vec<ServerEntry>) -> anyhow::Result<()> {
+        let create = servers
+            .into_iter()
+            .enumerate()
+            .map(|(position, server)| {
+                self.app.prisma_client.shared_server().create(
+                    server.name,
+                    server.ip,
+                    position as i32,
+                    vec![
+                        shared_server::SetParam::SetIcon(server.icon),
+                        shared_server::SetParam::SetAcceptTextures(server.accept_textures),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>();
+
+        self.app
+            .prisma_client
+            ._batch((
+                self.app.prisma_client.shared_server().delete_many(vec![]),
+                create,
+            ))
+            .await?;
+
+        self.app.invalidate(GET_SHARED_SERVERS, None);
+        Ok(())
+    }
+
+    /// Adds any missing shared servers to the top of the `servers.dat` in `data_path`.
+    pub(super) async fn write_shared_servers(self, data_path: &Path) -> anyhow::Result<()> {
+        let shared = self.get_shared_servers().await?;
+        let path = data_path.join("servers.dat");
+        let mut servers = read_servers_dat(&path).await?;
+
+        if merge_shared_servers(&mut servers, &shared) {
+            self.write_servers_dat(&path, servers).await?;
+        }
+
+        Ok(())
+    }
+}
+
+async fn read_servers_dat(path: &Path) -> anyhow::Result<Vec<Compound<'static>>> {
+    let data = match tokio::fs::read(path).await {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    parse_servers_dat(&data)
+}
+
+fn parse_servers_dat(data: &[u8]) -> anyhow::Result<Vec<Compound<'static>>> {
+    let raw = nbt::decompress(data)?;
+    let (_, (_, root)) =
+        nbt::parse_nbt(&raw).map_err(|_| anyhow!("servers.dat is not valid NBT"))?;
+
+    let Some(servers) = root
+        .as_compound()
+        .and_then(|root| root.get("servers"))
+        .and_then(Tag::as_list)
+    else {
+        return Ok(Vec::new());
+    };
+
+    Ok(servers
+        .items
+        .iter()
+        .filter_map(Tag::as_compound)
+        .map(|server| server.clone().into_owned())
+        .collect())
+}
+
+/// Servers joined through direct connect are saved but not shown in the list.
+fn is_hidden(server: &Compound) -> bool {
+    server.get("hidden").and_then(Tag::as_i64).unwrap_or(0) != 0
+}
+
+/// Maps an index into the visible server list onto the full list.
+fn visible_index(servers: &[Compound], index: usize) -> anyhow::Result<usize> {
+    match servers
+        .iter()
+        .enumerate()
+        .filter(|(_, server)| !is_hidden(server))
+        .nth(index)
+    {
+        Some((index, _)) => Ok(index),
+        None => bail!("no server at index {index}"),
+    }
+}
+
+fn server_from_nbt(server: &Compound) -> ServerEntry {
+    let string = |name: &str| server.get(name).and_then(Tag::as_str).map(String::from);
+
+    ServerEntry {
+        name: string("name").unwrap_or_default(),
+        ip: string("ip").unwrap_or_default(),
+        icon: string("icon"),
+        accept_textures: server
+            .get("acceptTextures")
+            .and_then(Tag::as_i64)
+            .map(|v| v != 0),
+    }
+}
+
+fn server_to_nbt(server: &ServerEntry, mut base: Compound<'static>) -> Compound<'static> {
+    base.insert("name", Tag::String(server.name.clone().into()));
+    base.insert("ip", Tag::String(server.ip.clone().into()));
+
+    match &server.icon {
+        Some(icon) => base.insert("icon", Tag::String(icon.clone().into())),
+        None => {
+            base.remove("icon");
+        }
+    }
+
+    match server.accept_textures {
+        Some(accept) => base.insert("acceptTextures", Tag::Byte(accept as i8)),
+        None => {
+            base.remove("acceptTextures");
+        }
+    }
+
+    base
+}
+
+/// Inserts shared servers that aren't listed yet, returning whether anything changed.
+fn merge_shared_servers(servers: &mut Vec<Compound<'static>>, shared: &[ServerEntry]) -> bool {
+    let missing = shared
+        .iter()
+        .filter(|entry| {
+            !servers.iter().any(|server| {
+                server
+                    .get("ip")
+                    .and_then(Tag::as_str)
+                    .is_some_and(|ip| ip.trim().eq_ignore_ascii_case(entry.ip.trim()))
+            })
+        })
+        .map(|entry| server_to_nbt(entry, Compound::new()))
+        .collect::<Vec<_>>();
+
+    let changed = !missing.is_empty();
+    servers.splice(0..0, missing);
+
+    changed
+}
+
+#[cfg(test)]
+mod test {
+    use carbon_parsing::nbt::{self, Compound, Compression, List, Tag, TagKind};
+
+    use crate::domain::instance::ServerEntry;
+
+    use super::{merge_shared_servers, parse_servers_dat, server_from_nbt, visible_index};
+
+    #[test]
+    fn shared_servers() {
+        let mut hidden = Compound::new();
+        hidden.insert("name", Tag::String("Direct".into()));
+        hidden.insert("ip", Tag::String("direct.example.com".into()));
+        hidden.insert("hidden", Tag::Byte(1));
+
+        let mut existing = Compound::new();
+        existing.insert("name", Tag::String("Renamed".into()));
+        existing.insert("ip", Tag::String("Play.Example.com".into()));
+        existing.insert("acceptTextures", Tag::Byte(1));
+
+        let mut root = Compound::new();
+        root.insert(
+            "servers",
+            Tag::List(List {
+                kind: TagKind::Compound,
+                items: vec![Tag::Compound(hidden), Tag::Compound(existing)],
+            }),
+        );
+
+        let bytes = nbt::to_bytes("", &Tag::Compound(root), Compression::None).unwrap();
+        let mut servers = parse_servers_dat(&bytes).unwrap();
+
+        assert_eq!(visible_index(&servers, 0).unwrap(), 1);
+        assert!(visible_index(&servers, 1).is_err());
+
+        let shared = [
+            ServerEntry {
+                name: String::from("Team"),
+                ip: String::from("team.example.com"),
+                icon: None,
+                accept_textures: None,
+            },
+            ServerEntry {
+                name: String::from("Play"),
+                ip: String::from("play.example.com"),
+                icon: None,
+                accept_textures: Some(false),
+            },
+        ];
+
+        assert!(merge_shared_servers(&mut servers, &shared));
+        assert!(!merge_shared_servers(&mut servers, &shared));
+
+        let entries = servers.iter().map(server_from_nbt).collect::<Vec<_>>();
+        assert_eq!(
+            entries.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+            ["Team", "Direct", "Renamed"]
+        );
+        assert_eq!(entries[2].accept_textures, Some(true));
+    }
+}
@@ -0,0 +1,693 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::api::keys::instance::*;
+use crate::api::translation::Translation;
+use crate::db;
+use crate::domain::instance::{InstanceId, Snapshot, SnapshotReason};
+use crate::domain::vtask::VisualTaskId;
+use crate::managers::vtask::{Subtask, TaskState, VisualTask};
+use crate::managers::{App, ManagerRef};
+use crate::util::NormalizedWalkdir;
+
+use super::backups::BACKUP_ID_FORMAT;
+use super::run::LaunchState;
+use super::worlds::check_file_name;
+use super::{Instance, InstanceManager, InstanceType, InvalidInstanceIdError};
+
+/// Automatic snapshots kept per instance, manual ones are kept until deleted.
+const AUTOMATIC_SNAPSHOTS_KEPT: usize = 10;
+
+/// Snapshot manifest, stored as `<id>.json` next to the other snapshots of the instance.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    created_at: DateTime<Utc>,
+    reason: ManifestReason,
+    /// Every directory, so empty ones are restored as well
+    directories: Vec<String>,
+    files: Vec<ManifestFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestFile {
+    /// Path relative to the instance folder, separated by forward slashes
+    path: String,
+    sha256: String,
+    size: u64,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum ManifestReason {
+    Manual,
+    VersionChange,
+    ModpackUpdate,
+    Restore,
+}
+
+struct SnapshotTarget {
+    instance_id: InstanceId,
+    name: String,
+    root: PathBuf,
+    snapshots_path: PathBuf,
+    objects_path: PathBuf,
+    idle: bool,
+}
+
+impl ManagerRef<'_, InstanceManager> {
+    async fn snapshot_target(self, instance_id: InstanceId) -> anyhow::Result<SnapshotTarget> {
+        let instances = self.instances.read().await;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or(InvalidInstanceIdError(instance_id))?;
+
+        let runtime_path = &self.app.settings_manager().runtime_path;
+        let data = instance.data()?;
+        let backups = runtime_path.get_backups();
+
+        Ok(SnapshotTarget {
+            instance_id,
+            name: data.config.name.clone(),
+            root: runtime_path
+                .get_instances()
+                .get_instance_path(&instance.shortpath)
+                .get_root(),
+            snapshots_path: backups.get_snapshots_path(&instance.shortpath),
+            objects_path: backups.get_snapshot_objects_path(),
+            idle: matches!(data.state, LaunchState::Inactive { .. }),
+        })
+    }
+
+    /// Lists the snapshots of an instance, newest first.
+    pub async fn list_snapshots(self, instance_id: InstanceId) -> anyhow::Result<Vec<Snapshot>> {
+        let target = self.snapshot_target(instance_id).await?;
+
+        let mut snapshots = read_manifests(&target.snapshots_path)
+            .await?
+            .into_iter()
+            .map(|(id, manifest)| Snapshot {
+                id,
+                created_at: manifest.created_at,
+                reason: manifest.reason.into(),
+                files: manifest.files.len() as u32,
+                size: manifest.files.iter().map(|file| file.size).sum(),
+            })
+            .collect::<Vec<_>>();
+
+        snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        Ok(snapshots)
+    }
+
+    pub async fn create_snapshot(self, instance_id: InstanceId) -> anyhow::Result<VisualTaskId> {
+        let target = self.snapshot_target(instance_id).await?;
+
+        if !target.idle {
+            bail!("cannot snapshot an instance while it is running");
+        }
+
+        let (task_id, _) = self.start_snapshot(target, SnapshotReason::Manual).await;
+        Ok(task_id)
+    }
+
+    /// Takes an automatic snapshot ahead of a change to the instance, waiting until it is stored.
+    pub(super) async fn snapshot_before(
+        self,
+        instance_id: InstanceId,
+        reason: SnapshotReason,
+    ) -> anyhow::Result<()> {
+        let target = self.snapshot_target(instance_id).await?;
+
+        if !target.idle {
+            bail!("cannot snapshot an instance while it is running");
+        }
+
+        let (_, handle) = self.start_snapshot(target, reason).await;
+        handle.await?
+    }
+
+    async fn start_snapshot(
+        self,
+        target: SnapshotTarget,
+        reason: SnapshotReason,
+    ) -> (VisualTaskId, JoinHandle<anyhow::Result<()>>) {
+        let vtask = VisualTask::new(Translation::InstanceTaskCreateSnapshot {
+            name: target.name.clone(),
+        });
+
+        let task_id = self.app.task_manager().spawn_task(&vtask).await;
+        let app = self.app.clone();
+
+        let handle = tokio::spawn(async move {
+            let instance_manager = app.instance_manager();
+
+            let try_result: anyhow::Result<_> = async {
+                let _lock = instance_manager.backup_lock.lock().await;
+
+                let t_store = vtask.subtask(Translation::InstanceTaskSnapshotStoreFiles);
+
+                vtask
+                    .edit(|data| data.state = TaskState::KnownProgress)
+                    .await;
+
+                write_snapshot(&app, &target, reason.into(), t_store).await?;
+                prune_snapshots(&target, None).await?;
+
+                Ok(())
+            }
+            .await;
+
+            app.invalidate(GET_SNAPSHOTS, Some(target.instance_id.0.into()));
+
+            match try_result {
+                Ok(()) => Ok(()),
+                Err(e) => {
+                    let message = format!("{e:#}");
+                    vtask.fail(e).await;
+                    Err(anyhow!("failed to snapshot instance: {message}"))
+                }
+            }
+        });
+
+        (task_id, handle)
+    }
+
+    /// Puts the instance back into the state it had when the snapshot was taken.
+    ///
+    /// The current state is snapshotted first, so a restore can be undone.
+    pub async fn restore_snapshot(
+        self,
+        instance_id: InstanceId,
+        snapshot_id: String,
+    ) -> anyhow::Result<VisualTaskId> {
+        let target = self.snapshot_target(instance_id).await?;
+        check_file_name(&snapshot_id)?;
+
+        if !target.idle {
+            bail!("cannot restore a snapshot while the instance is running");
+        }
+
+        let manifest_path = target.snapshots_path.join(format!("{snapshot_id}.json"));
+        let manifest = read_manifest(&manifest_path).await?;
+
+        let vtask = VisualTask::new(Translation::InstanceTaskRestoreSnapshot {
+            name: target.name.clone(),
+        });
+
+        let task_id = self.app.task_manager().spawn_task(&vtask).await;
+        let app = self.app.clone();
+
+        tokio::spawn(async move {
+            let instance_manager = app.instance_manager();
+
+            let try_result: anyhow::Result<_> = async {
+                let _lock = instance_manager.backup_lock.lock().await;
+
+                let t_store = vtask.subtask(Translation::InstanceTaskSnapshotStoreFiles);
+                let t_restore = vtask.subtask(Translation::InstanceTaskSnapshotRestoreFiles);
+
+                vtask
+                    .edit(|data| data.state = TaskState::KnownProgress)
+                    .await;
+
+                write_snapshot(&app, &target, ManifestReason::Restore, t_store).await?;
+
+                let tmpdir = app
+                    .settings_manager()
+                    .runtime_path
+                    .get_temp()
+                    .maketmpdir()
+                    .await?;
+
+                let new_root = tmpdir.join("instance");
+                let objects_path = target.objects_path.clone();
+                let send_root = new_root.clone();
+
+                let t_restore = tokio::task::spawn_blocking(move || {
+                    restore_files(&manifest, &objects_path, &send_root, &t_restore)?;
+                    Ok::<_, anyhow::Error>(t_restore)
+                })
+                .await??;
+
+                // keep the current state until the snapshot is in place
+                let old_root = tmpdir.join("old");
+                tokio::fs::rename(&target.root, &old_root).await?;
+
+                if let Err(e) = tokio::fs::rename(&new_root, &target.root).await {
+                    tokio::fs::rename(&old_root, &target.root).await?;
+                    return Err(e.into());
+                }
+
+                t_restore.complete_items();
+
+                // the snapshot just restored may be the oldest automatic one
+                prune_snapshots(&target, Some(&snapshot_id)).await?;
+                instance_manager.reload_instance(instance_id).await?;
+
+                Ok(())
+            }
+            .await;
+
+            app.invalidate(GET_SNAPSHOTS, Some(instance_id.0.into()));
+
+            if let Err(e) = try_result {
+                vtask.fail(e).await;
+            }
+        });
+
+        Ok(task_id)
+    }
+
+    pub async fn delete_snapshot(
+        self,
+        instance_id: InstanceId,
+        snapshot_id: String,
+    ) -> anyhow::Result<()> {
+        let target = self.snapshot_target(instance_id).await?;
+        check_file_name(&snapshot_id)?;
+
+        let _lock = self.backup_lock.lock().await;
+
+        tokio::fs::remove_file(target.snapshots_path.join(format!("{snapshot_id}.json"))).await?;
+        collect_garbage(&target).await?;
+
+        self.app
+            .invalidate(GET_SNAPSHOTS, Some(instance_id.0.into()));
+        Ok(())
+    }
+
+    /// Reloads an instance whose files were replaced from disk.
+    async fn reload_instance(self, instance_id: InstanceId) -> anyhow::Result<()> {
+        let cached = self
+            .app
+            .prisma_client
+            .instance()
+            .find_unique(db::instance::UniqueWhereParam::IdEquals(*instance_id))
+            .exec()
+            .await?
+            .ok_or(InvalidInstanceIdError(instance_id))?;
+
+        let path = self
+            .app
+            .settings_manager()
+            .runtime_path
+            .get_instances()
+            .get_instance_path(&cached.shortpath)
+            .get_root();
+
+        let mut instance = self
+            .scan_instance(cached.shortpath.clone(), path, Some(&cached))
+            .await?
+            .ok_or(InvalidInstanceIdError(instance_id))?;
+
+        if let InstanceType::Valid(data) = &instance.type_ {
+            if data.config.name != cached.name {
+                self.app
+                    .prisma_client
+                    .instance()
+                    .update(
+                        db::instance::UniqueWhereParam::IdEquals(*instance_id),
+                        vec![db::instance::SetParam::SetName(data.config.name.clone())],
+                    )
+                    .exec()
+                    .await?;
+            }
+        }
+
+        let mut instances = self.instances.write().await;
+
+        if let (
+            Instance {
+                type_: InstanceType::Valid(data),
+                ..
+            },
+            Some(Instance {
+                type_: InstanceType::Valid(old_data),
+                ..
+            }),
+        ) = (&mut instance, instances.remove(&instance_id))
+        {
+            data.state = old_data.state;
+            // the icon may have changed without its path changing
+            data.icon_revision = data
+                .icon_revision
+                .map(|_| old_data.icon_revision.unwrap_or(0) + 1);
+        }
+
+        instances.insert(instance_id, instance);
+        drop(instances);
+
+        self.app
+            .meta_cache_manager()
+            .queue_caching(instance_id, false)
+            .await;
+
+        self.app.invalidate(GET_GROUPS, None);
+        self.app.invalidate(GET_ALL_INSTANCES, None);
+        self.app
+            .invalidate(INSTANCE_DETAILS, Some(instance_id.0.into()));
+        self.app
+            .invalidate(INSTANCE_MODS, Some(instance_id.0.into()));
+        self.app.invalidate(GET_WORLDS, Some(instance_id.0.into()));
+
+        Ok(())
+    }
+}
+
+/// Stores the current state of the instance as a new snapshot.
+async fn write_snapshot(
+    app: &App,
+    target: &SnapshotTarget,
+    reason: ManifestReason,
+    subtask: Subtask,
+) -> anyhow::Result<()> {
+    let root = target.root.clone();
+    let objects_path = target.objects_path.clone();
+
+    let (manifest, subtask) = tokio::task::spawn_blocking(move || {
+        let manifest = store_files(&root, &objects_path, reason, &subtask)?;
+        Ok::<_, anyhow::Error>((manifest, subtask))
+    })
+    .await??;
+
+    let id = manifest.created_at.format(BACKUP_ID_FORMAT).to_string();
+
+    tokio::fs::create_dir_all(&target.snapshots_path).await?;
+    app.settings_manager()
+        .runtime_path
+        .get_temp()
+        .write_file_atomic(
+            target.snapshots_path.join(format!("{id}.json")),
+            serde_json::to_vec(&manifest)?,
+        )
+        .await?;
+
+    subtask.complete_items();
+
+    Ok(())
+}
+
+/// Removes automatic snapshots past the retention limit, along with objects no longer used.
+/// The `keep` snapshot is never removed and doesn't count towards the limit.
+async fn prune_snapshots(target: &SnapshotTarget, keep: Option<&str>) -> anyhow::Result<()> {
+    let manifests = read_manifests(&target.snapshots_path).await?;
+
+    let mut automatic = manifests
+        .iter()
+        .filter(|(id, manifest)| {
+            manifest.reason != ManifestReason::Manual && Some(id.as_str()) != keep
+        })
+        .collect::<Vec<_>>();
+
+    if automatic.len() <= AUTOMATIC_SNAPSHOTS_KEPT {
+        return Ok(());
+    }
+
+    automatic.sort_by(|(_, a), (_, b)| b.created_at.cmp(&a.created_at));
+
+    for (id, _) in &automatic[AUTOMATIC_SNAPSHOTS_KEPT..] {
+        tokio::fs::remove_file(target.snapshots_path.join(format!("{id}.json"))).await?;
+    }
+
+    collect_garbage(target).await
+}
+
+/// Removes objects that aren't referenced by the snapshot of any instance.
+async fn collect_garbage(target: &SnapshotTarget) -> anyhow::Result<()> {
+    let instances_path = target
+        .snapshots_path
+        .parent()
+        .ok_or_else(|| anyhow!("snapshots path has no parent"))?;
+
+    let mut referenced = HashSet::new();
+
+    if instances_path.is_dir() {
+        let mut dir = tokio::fs::read_dir(instances_path).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            for (_, manifest) in read_manifests(&entry.path()).await? {
+                referenced.extend(manifest.files.into_iter().map(|file| file.sha256));
+            }
+        }
+    }
+
+    let objects_path = target.objects_path.clone();
+    tokio::task::spawn_blocking(move || remove_unreferenced(&objects_path, &referenced)).await?
+}
+
+fn remove_unreferenced(objects_path: &Path, referenced: &HashSet<String>) -> anyhow::Result<()> {
+    if !objects_path.is_dir() {
+        return Ok(());
+    }
+
+    for prefix in fs::read_dir(objects_path)? {
+        let prefix = prefix?.path();
+
+        for object in fs::read_dir(&prefix)? {
+            let object = object?;
+
+            // leftover partial copies are never referenced either
+            if !referenced.contains(&*object.file_name().to_string_lossy()) {
+                fs::remove_file(object.path())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads every readable manifest in `snapshots_path` along with its id.
+async fn read_manifests(snapshots_path: &Path) -> anyhow::Result<Vec<(String, Manifest)>> {
+    if !snapshots_path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut manifests = Vec::new();
+    let mut dir = tokio::fs::read_dir(snapshots_path).await?;
+
+    while let Some(entry) = dir.next_entry().await? {
+        let file_name = entry.file_name();
+        let Some(id) = file_name
+            .to_str()
+            .and_then(|name| name.strip_suffix(".json"))
+        else {
+            continue;
+        };
+
+        if NaiveDateTime::parse_from_str(id, BACKUP_ID_FORMAT).is_err() {
+            continue;
+        }
+
+        match read_manifest(&entry.path()).await {
+            Ok(manifest) => manifests.push((id.to_string(), manifest)),
+            Err(e) => warn!({ error = ?e }, "Skipping unreadable snapshot {:?}", entry.path()),
+        }
+    }
+
+    Ok(manifests)
+}
+
+async fn read_manifest(path: &Path) -> anyhow::Result<Manifest> {
+    let data = tokio::fs::read(path).await?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+fn object_path(objects_path: &Path, sha256: &str) -> PathBuf {
+    objects_path.join(&sha256[..2]).join(sha256)
+}
+
+/// Hashes every file of the instance into the object store.
+fn store_files(
+    root: &Path,
+    objects_path: &Path,
+    reason: ManifestReason,
+    subtask: &Subtask,
+) -> anyhow::Result<Manifest> {
+    let created_at = Utc::now();
+    let mut directories = Vec::new();
+    let mut sources = Vec::new();
+
+    let mut walker = NormalizedWalkdir::new(root)?;
+    while let Some(entry) = walker.next()? {
+        let path = entry.relative_path.trim_start_matches('/').to_string();
+
+        if entry.is_dir {
+            directories.push(path);
+        } else {
+            sources.push((path, entry.entry.path()));
+        }
+    }
+
+    let total = sources.len() as u32;
+    let mut files = Vec::with_capacity(sources.len());
+
+    for (i, (path, source)) in sources.into_iter().enumerate() {
+        let (sha256, size) = store_object(objects_path, &source)?;
+        files.push(ManifestFile { path, sha256, size });
+        subtask.update_items(i as u32 + 1, total);
+    }
+
+    Ok(Manifest {
+        created_at,
+        reason,
+        directories,
+        files,
+    })
+}
+
+/// Adds a file to the object store, copying it only if its content isn't stored yet.
+fn store_object(objects_path: &Path, source: &Path) -> anyhow::Result<(String, u64)> {
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut File::open(source)?, &mut hasher)?;
+    let sha256 = hex::encode(hasher.finalize());
+
+    let object_path = object_path(objects_path, &sha256);
+
+    if !object_path.is_file() {
+        let parent = object_path
+            .parent()
+            .expect("object paths are always inside a prefix folder");
+
+        fs::create_dir_all(parent)?;
+
+        let part_path = parent.join(format!("{sha256}.part"));
+        fs::copy(source, &part_path)?;
+        fs::rename(&part_path, &object_path)?;
+    }
+
+    Ok((sha256, size))
+}
+
+/// Recreates the files of a snapshot in `target`, which must not exist yet.
+fn restore_files(
+    manifest: &Manifest,
+    objects_path: &Path,
+    target: &Path,
+    subtask: &Subtask,
+) -> anyhow::Result<()> {
+    // refuse manifests that would write outside of the target
+    let resolve = |path: &str| -> anyhow::Result<PathBuf> {
+        if path
+            .split('/')
+            .any(|part| part.is_empty() || part == "." || part == "..")
+        {
+            bail!("invalid path in snapshot: {path}");
+        }
+
+        Ok(target.join(path))
+    };
+
+    fs::create_dir(target)?;
+
+    for directory in &manifest.directories {
+        fs::create_dir_all(resolve(directory)?)?;
+    }
+
+    let total = manifest.files.len() as u32;
+
+    for (i, file) in manifest.files.iter().enumerate() {
+        if file.sha256.len() != 64 || !file.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+            bail!("invalid hash in snapshot: {}", file.sha256);
+        }
+
+        let object_path = object_path(objects_path, &file.sha256);
+
+        if !object_path.is_file() {
+            bail!("snapshot is missing the contents of {}", file.path);
+        }
+
+        let path = resolve(&file.path)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::copy(object_path, path)?;
+        subtask.update_items(i as u32 + 1, total);
+    }
+
+    Ok(())
+}
+
+impl From<ManifestReason> for SnapshotReason {
+    fn from(value: ManifestReason) -> Self {
+        match value {
+            ManifestReason::Manual => Self::Manual,
+            ManifestReason::VersionChange => Self::VersionChange,
+            ManifestReason::ModpackUpdate => Self::ModpackUpdate,
+            ManifestReason::Restore => Self::Restore,
+        }
+    }
+}
+
+impl From<SnapshotReason> for ManifestReason {
+    fn from(value: SnapshotReason) -> Self {
+        match value {
+            SnapshotReason::Manual => Self::Manual,
+            SnapshotReason::VersionChange => Self::VersionChange,
+            SnapshotReason::ModpackUpdate => Self::ModpackUpdate,
+            SnapshotReason::Restore => Self::Restore,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::api::translation::Translation;
+    use crate::managers::vtask::VisualTask;
+
+    use super::{restore_files, store_files, ManifestReason};
+
+    #[test]
+    fn snapshot_round_trip() {
+        let dir = tempdir::TempDir::new("carbon_app_test").unwrap();
+        let root = dir.path().join("instance");
+        let objects = dir.path().join("objects");
+
+        fs::create_dir_all(root.join("instance/mods")).unwrap();
+        fs::create_dir_all(root.join("instance/saves/empty")).unwrap();
+        fs::write(root.join("instance.json"), "{}").unwrap();
+        fs::write(root.join("instance/mods/a.jar"), "same").unwrap();
+        fs::write(root.join("instance/mods/b.jar"), "same").unwrap();
+        fs::write(root.join("instance/options.txt"), "fov:1.0").unwrap();
+
+        let vtask = VisualTask::new(Translation::InstanceTaskCreateSnapshot {
+            name: String::from("test"),
+        });
+        let subtask = vtask.subtask(Translation::InstanceTaskSnapshotStoreFiles);
+
+        let manifest = store_files(&root, &objects, ManifestReason::Manual, &subtask).unwrap();
+        assert_eq!(manifest.files.len(), 4);
+
+        // identical files share a single object
+        let objects_stored = fs::read_dir(&objects)
+            .unwrap()
+            .map(|prefix| fs::read_dir(prefix.unwrap().path()).unwrap().count())
+            .sum::<usize>();
+        assert_eq!(objects_stored, 3);
+
+        let restored = dir.path().join("restored");
+        restore_files(&manifest, &objects, &restored, &subtask).unwrap();
+
+        assert!(restored.join("instance/saves/empty").is_dir());
+        assert_eq!(
+            fs::read_to_string(restored.join("instance/mods/b.jar")).unwrap(),
+            "same"
+        );
+        assert_eq!(
+            fs::read_to_string(restored.join("instance/options.txt")).unwrap(),
+            "fov:1.0"
+        );
+    }
+}
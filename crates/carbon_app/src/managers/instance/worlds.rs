@@ -0,0 +1,446 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context};
+use carbon_parsing::nbt::{self, Compound, Compression, Tag};
+use chrono::{TimeZone, Utc};
+use tracing::warn;
+
+use crate::api::keys::instance::GET_WORLDS;
+use crate::domain::instance::{Difficulty, GameMode, InstanceId, World};
+use crate::domain::runtime_path::copy_dir_filter;
+use crate::managers::ManagerRef;
+
+use super::run::LaunchState;
+use super::{InstanceManager, InvalidInstanceIdError};
+
+impl ManagerRef<'_, InstanceManager> {
+    /// Saves folder of an instance, failing if `modify` is set and the game is running.
+    pub(super) async fn saves_path(
+        self,
+        instance_id: InstanceId,
+        modify: bool,
+    ) -> anyhow::Result<PathBuf> {
+        let instances = self.instances.read().await;
+        let instance = instances
+            .get(&instance_id)
+            .ok_or(InvalidInstanceIdError(instance_id))?;
+
+        if modify && matches!(instance.data()?.state, LaunchState::Running(_)) {
+            bail!("cannot modify worlds while the instance is running");
+        }
+
+        Ok(self
+            .app
+            .settings_manager()
+            .runtime_path
+            .get_instances()
+            .get_instance_path(&instance.shortpath)
+            .get_saves_path())
+    }
+
+    pub async fn list_worlds(self, instance_id: InstanceId) -> anyhow::Result<Vec<World>> {
+        let saves_path = self.saves_path(instance_id, false).await?;
+
+        if !saves_path.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut dir = tokio::fs::read_dir(&saves_path)
+            .await
+            .context(format!("Reading saves path: {saves_path:?}"))?;
+
+        let mut worlds = Vec::new();
+        while let Some(entry) = dir.next_entry().await? {
+            let world_path = entry.path();
+            let level_dat = world_path.join("level.dat");
+
+            if !level_dat.is_file() {
+                continue;
+            }
+
+            let folder = entry.file_name().to_string_lossy().to_string();
+
+            let world = tokio::fs::read(&level_dat)
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|data| parse_level_dat(folder, &data));
+
+            match world {
+                Ok(mut world) => {
+                    world.has_icon = world_path.join("icon.png").is_file();
+                    worlds.push(world);
+                }
+                Err(e) => warn!({ error = ?e }, "Skipping unreadable world at {world_path:?}"),
+            }
+        }
+
+        worlds.sort_by(|a, b| b.last_played.cmp(&a.last_played));
+
+        Ok(worlds)
+    }
+
+    pub async fn get_world_icon(
+        self,
+        instance_id: InstanceId,
+        folder: String,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let saves_path = self.saves_path(instance_id, false).await?;
+        let icon_path = world_path(&saves_path, &folder)?.join("icon.png");
+
+        match tokio::fs::read(icon_path).await {
+            Ok(icon) => Ok(Some(icon)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Changes the display name of a world, its folder is left as is.
+    pub async fn rename_world(
+        self,
+        instance_id: InstanceId,
+        folder: String,
+        name: String,
+    ) -> anyhow::Result<()> {
+        let saves_path = self.saves_path(instance_id, true).await?;
+        let world_path = world_path(&saves_path, &folder)?;
+
+        self.set_level_name(&world_path, name).await?;
+
+        self.app.invalidate(GET_WORLDS, Some(instance_id.0.into()));
+        Ok(())
+    }
+
+    /// Copies a world into a new folder, returning the folder name.
+    pub async fn duplicate_world(
+        self,
+        instance_id: InstanceId,
+        folder: String,
+    ) -> anyhow::Result<String> {
+        let saves_path = self.saves_path(instance_id, false).await?;
+        let world_path = world_path(&saves_path, &folder)?;
+
+        let level_dat = tokio::fs::read(world_path.join("level.dat")).await?;
+        let world = parse_level_dat(folder.clone(), &level_dat)?;
+
+        let new_folder = next_world_folder(&saves_path, &folder);
+        let new_path = saves_path.join(&new_folder);
+
+        // the lock belongs to whichever game has the original world open
+        copy_dir_filter(&world_path, &new_path, |path| {
+            path != Path::new("session.lock")
+        })
+        .await?;
+
+        self.set_level_name(&new_path, format!("{} - Copy", world.name))
+            .await?;
+
+        self.app.invalidate(GET_WORLDS, Some(instance_id.0.into()));
+        Ok(new_folder)
+    }
+
+    pub async fn delete_world(self, instance_id: InstanceId, folder: String) -> anyhow::Result<()> {
+        let saves_path = self.saves_path(instance_id, true).await?;
+        let world_path = world_path(&saves_path, &folder)?;
+
+        let should_go_to_trash = self
+            .app
+            .settings_manager()
+            .get_settings()
+            .await?
+            .deletion_through_recycle_bin;
+
+        tokio::task::spawn_blocking(move || {
+            if should_go_to_trash {
+                trash::delete(&world_path)?;
+            } else {
+                std::fs::remove_dir_all(&world_path)?;
+            }
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .await??;
+
+        self.app.invalidate(GET_WORLDS, Some(instance_id.0.into()));
+        Ok(())
+    }
+
+    /// Extracts a world zip into the saves folder, returning the new folder name.
+    pub async fn import_world(
+        self,
+        instance_id: InstanceId,
+        zip_path: PathBuf,
+    ) -> anyhow::Result<String> {
+        let saves_path = self.saves_path(instance_id, false).await?;
+
+        let tmpdir = self
+            .app
+            .settings_manager()
+            .runtime_path
+            .get_temp()
+            .maketmpdir()
+            .await?;
+
+        let extract_path = tmpdir.to_path_buf();
+        let world_name =
+            tokio::task::spawn_blocking(move || extract_world_zip(&zip_path, &extract_path))
+                .await??;
+
+        tokio::fs::create_dir_all(&saves_path).await?;
+        let folder = next_world_folder(&saves_path, &world_name);
+        tokio::fs::rename(&*tmpdir, saves_path.join(&folder)).await?;
+
+        self.app.invalidate(GET_WORLDS, Some(instance_id.0.into()));
+        Ok(folder)
+    }
+
+    async fn set_level_name(self, world_path: &Path, name: String) -> anyhow::Result<()> {
+        self.edit_level_data(world_path, |data| {
+            data.insert("LevelName", Tag::String(name.into()));
+            Ok(())
+        })
+        .await
+    }
+
+    /// Rewrites the `Data` compound of a world's level.dat.
+    pub(super) async fn edit_level_data(
+        self,
+        world_path: &Path,
+        edit: impl FnOnce(&mut Compound<'static>) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let level_dat_path = world_path.join("level.dat");
+        let data = tokio::fs::read(&level_dat_path).await?;
+        let raw = nbt::decompress(&data)?;
+
+        let (_, (root_name, root)) =
+            nbt::parse_nbt(&raw).map_err(|_| anyhow!("level.dat is not valid NBT"))?;
+        let mut root = root.into_owned();
+
+        edit(
+            root.as_compound_mut()
+                .and_then(|root| root.get_mut("Data"))
+                .and_then(Tag::as_compound_mut)
+                .ok_or_else(|| anyhow!("level.dat is missing its Data compound"))?,
+        )?;
+
+        let bytes = nbt::to_bytes(&root_name, &root, Compression::Gzip)?;
+
+        // keep the previous version around the same way the game does
+        tokio::fs::copy(&level_dat_path, world_path.join("level.dat_old")).await?;
+        self.app
+            .settings_manager()
+            .runtime_path
+            .get_temp()
+            .write_file_atomic(&level_dat_path, bytes)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Resolves a world folder, rejecting names that would escape the saves folder.
+pub(super) fn world_path(saves_path: &Path, folder: &str) -> anyhow::Result<PathBuf> {
+    check_file_name(folder)?;
+
+    let path = saves_path.join(folder);
+
+    if !path.is_dir() {
+        bail!("world {folder} does not exist");
+    }
+
+    Ok(path)
+}
+
+/// Rejects names that are not a single path component.
+pub(super) fn check_file_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        bail!("invalid file name: {name}");
+    }
+
+    Ok(())
+}
+
+/// Picks an unused folder name based on `name`, the same way the game does.
+fn next_world_folder(saves_path: &Path, name: &str) -> String {
+    let base = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>();
+
+    let base = match base.trim_matches(['.', ' ']) {
+        "" => "World",
+        base => base,
+    };
+
+    let mut folder = base.to_string();
+    let mut i = 1;
+
+    while saves_path.join(&folder).exists() {
+        folder = format!("{base} ({i})");
+        i += 1;
+    }
+
+    folder
+}
+
+/// Reads the world summary out of a (possibly compressed) `level.dat`.
+fn parse_level_dat(folder: String, data: &[u8]) -> anyhow::Result<World> {
+    let raw = nbt::decompress(data)?;
+    let (_, (_, root)) = nbt::parse_nbt(&raw).map_err(|_| anyhow!("level.dat is not valid NBT"))?;
+
+    let data = root
+        .as_compound()
+        .and_then(|root| root.get("Data"))
+        .and_then(Tag::as_compound)
+        .ok_or_else(|| anyhow!("level.dat is missing its Data compound"))?;
+
+    let int = |name: &str| data.get(name).and_then(Tag::as_i64);
+
+    // newer versions group the difficulty settings together
+    let difficulty_settings = data.get("difficulty_settings").and_then(Tag::as_compound);
+
+    let difficulty = match difficulty_settings
+        .and_then(|settings| settings.get("difficulty"))
+        .and_then(Tag::as_str)
+    {
+        Some("peaceful") => Some(Difficulty::Peaceful),
+        Some("easy") => Some(Difficulty::Easy),
+        Some("normal") => Some(Difficulty::Normal),
+        Some("hard") => Some(Difficulty::Hard),
+        _ => match int("Difficulty") {
+            Some(0) => Some(Difficulty::Peaceful),
+            Some(1) => Some(Difficulty::Easy),
+            Some(2) => Some(Difficulty::Normal),
+            Some(3) => Some(Difficulty::Hard),
+            _ => None,
+        },
+    };
+
+    let hardcore = difficulty_settings
+        .and_then(|settings| settings.get("hardcore"))
+        .and_then(Tag::as_i64)
+        .or_else(|| int("hardcore"))
+        .unwrap_or(0)
+        != 0;
+
+    Ok(World {
+        name: data
+            .get("LevelName")
+            .and_then(Tag::as_str)
+            .map(String::from)
+            .unwrap_or_else(|| folder.clone()),
+        folder,
+        game_mode: match int("GameType") {
+            Some(0) => Some(GameMode::Survival),
+            Some(1) => Some(GameMode::Creative),
+            Some(2) => Some(GameMode::Adventure),
+            Some(3) => Some(GameMode::Spectator),
+            _ => None,
+        },
+        hardcore,
+        difficulty,
+        last_played: int("LastPlayed").and_then(|ms| Utc.timestamp_millis_opt(ms).single()),
+        data_version: int("DataVersion").and_then(|v| i32::try_from(v).ok()),
+        version_name: data
+            .get("Version")
+            .and_then(Tag::as_compound)
+            .and_then(|version| version.get("Name"))
+            .and_then(Tag::as_str)
+            .map(String::from),
+        has_icon: false,
+    })
+}
+
+/// Extracts the world inside a zip into `target`, returning the world's name.
+///
+/// The world may be at the root of the zip or inside a folder.
+pub(super) fn extract_world_zip(zip_path: &Path, target: &Path) -> anyhow::Result<String> {
+    let mut zip = zip::ZipArchive::new(File::open(zip_path)?)?;
+
+    // the shallowest level.dat marks the world root
+    let root = zip
+        .file_names()
+        .filter_map(|name| name.strip_suffix("level.dat"))
+        .filter(|prefix| prefix.is_empty() || prefix.ends_with('/'))
+        .min_by_key(|prefix| prefix.len())
+        .map(String::from)
+        .ok_or_else(|| anyhow!("zip does not contain a world"))?;
+
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+
+        let Some(path) = file.enclosed_name().map(Path::to_path_buf) else {
+            continue;
+        };
+
+        let Ok(relpath) = path.strip_prefix(&root) else {
+            continue;
+        };
+
+        let dest = target.join(relpath);
+
+        if file.is_dir() {
+            std::fs::create_dir_all(&dest)?;
+        } else {
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            std::io::copy(&mut file, &mut File::create(&dest)?)?;
+        }
+    }
+
+    let name = Path::new(root.trim_end_matches('/'))
+        .file_name()
+        .or_else(|| zip_path.file_stem())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| String::from("World"));
+
+    Ok(name)
+}
+
+#[cfg(test)]
+mod test {
+    use carbon_parsing::nbt::{self, Compound, Compression, Tag};
+
+    use crate::domain::instance::{Difficulty, GameMode};
+
+    use super::parse_level_dat;
+
+    #[test]
+    fn read_level_dat() {
+        let mut data = Compound::new();
+        data.insert("LevelName", Tag::String("My World".into()));
+        data.insert("GameType", Tag::Int(1));
+        data.insert("Difficulty", Tag::Byte(3));
+        data.insert("hardcore", Tag::Byte(0));
+        data.insert("LastPlayed", Tag::Long(1_700_000_000_000));
+        data.insert("DataVersion", Tag::Int(3465));
+
+        let mut version = Compound::new();
+        version.insert("Name", Tag::String("1.20.1".into()));
+        data.insert("Version", Tag::Compound(version));
+
+        let mut root = Compound::new();
+        root.insert("Data", Tag::Compound(data));
+
+        let bytes = nbt::to_bytes("", &Tag::Compound(root), Compression::Gzip).unwrap();
+        let world = parse_level_dat(String::from("world"), &bytes).unwrap();
+
+        assert_eq!(world.folder, "world");
+        assert_eq!(world.name, "My World");
+        assert_eq!(world.game_mode, Some(GameMode::Creative));
+        assert_eq!(world.difficulty, Some(Difficulty::Hard));
+        assert!(!world.hardcore);
+        assert_eq!(
+            world.last_played.map(|t| t.timestamp()),
+            Some(1_700_000_000)
+        );
+        assert_eq!(world.data_version, Some(3465));
+        assert_eq!(world.version_name.as_deref(), Some("1.20.1"));
+    }
+}
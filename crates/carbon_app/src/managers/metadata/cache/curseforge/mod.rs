@@ -1,3 +1,4 @@
+use anyhow::bail;
 use itertools::Itertools;
 use std::collections::HashMap;
 use std::collections::VecDeque;
@@ -13,6 +14,7 @@ use crate::db::read_filters::DateTimeFilter;
 use crate::db::read_filters::IntFilter;
 use crate::domain::instance::info::ModLoaderType;
 use crate::domain::instance::InstanceId;
+use crate::domain::modplatforms::curseforge::filters::ModFileParameters;
 use crate::domain::modplatforms::curseforge::filters::ModFilesParameters;
 use crate::domain::modplatforms::curseforge::filters::ModFilesParametersQuery;
 use crate::domain::modplatforms::curseforge::filters::ModParameters;
@@ -30,7 +32,7 @@ use super::ModplatformCacher;
 use super::UpdateNotifier;
 use crate::db::{
     curse_forge_mod_cache as cfdb, curse_forge_mod_image_cache as cfimgdb, mod_file_cache as fcdb,
-    mod_metadata as metadb,
+    mod_metadata as metadb, pack_file_cache as pcdb,
 };
 
 pub mod modpack;
@@ -69,16 +71,37 @@ impl ModplatformCacher for CurseforgeModCacher {
             .await?
             .into_iter()
             .map(|m| {
-                let metadata = m
-                    .metadata
-                    .expect("metadata was queried with mod cache yet is not present");
+                m.metadata
+                    .expect("metadata was queried with mod cache yet is not present")
+            });
 
-                (
-                    metadata.murmur_2 as u32,
-                    (metadata.id, metadata.murmur_2 as u32),
-                )
+        let packlist = app
+            .prisma_client
+            .pack_file_cache()
+            .find_many(vec![
+                pcdb::instance_id::equals(*instance_id),
+                pcdb::metadata::is(vec![metadb::WhereParam::CurseforgeIsNot(vec![
+                    cfdb::WhereParam::CachedAt(DateTimeFilter::Gt(
+                        (chrono::Utc::now() - chrono::Duration::days(1)).into(),
+                    )),
+                ])]),
+            ])
+            .with(pcdb::metadata::fetch())
+            .exec()
+            .await?
+            .into_iter()
+            .map(|p| {
+                p.metadata
+                    .expect("metadata was queried with pack cache yet is not present")
             });
 
+        let modlist = modlist.chain(packlist).map(|metadata| {
+            (
+                metadata.murmur_2 as u32,
+                (metadata.id, metadata.murmur_2 as u32),
+            )
+        });
+
         let mcm = app.meta_cache_manager();
         let ignored_hashes = mcm.ignored_remote_cf_hashes.read().await;
 
@@ -240,29 +263,53 @@ impl ModplatformCacher for CurseforgeModCacher {
             .exec()
             .await;
 
-        let modlist = match modlist {
-            Ok(modlist) => modlist,
-            Err(e) => {
+        let packlist = app
+            .prisma_client
+            .pack_file_cache()
+            .find_many(vec![
+                pcdb::instance_id::equals(*instance_id),
+                pcdb::metadata::is(vec![metadb::WhereParam::CurseforgeIs(vec![
+                    cfdb::WhereParam::LogoImageIs(vec![cfimgdb::WhereParam::UpToDate(
+                        IntFilter::Equals(0),
+                    )]),
+                ])]),
+            ])
+            .with(
+                pcdb::metadata::fetch()
+                    .with(metadb::curseforge::fetch().with(cfdb::logo_image::fetch())),
+            )
+            .exec()
+            .await;
+
+        let (modlist, packlist) = match (modlist, packlist) {
+            (Ok(modlist), Ok(packlist)) => (modlist, packlist),
+            (Err(e), _) | (_, Err(e)) => {
                 error!({ error = ?e }, "error querying database for updated curseforge mod icons list");
                 return;
             }
         };
 
-        let modlist = modlist.into_iter().map(|file| {
-            let meta = file
-                .metadata
-                .expect("metadata was ensured present but not returned");
-            let cf = meta
-                .curseforge
-                .flatten()
-                .expect("curseforge was ensured present but not returned");
-            let row = cf
-                .logo_image
-                .flatten()
-                .expect("mod image was ensured present but not returned");
-
-            (file.filename, cf.project_id, cf.file_id, row)
-        });
+        let modlist = modlist
+            .into_iter()
+            .map(|file| (file.filename, file.metadata))
+            .chain(
+                packlist
+                    .into_iter()
+                    .map(|file| (file.filename, file.metadata)),
+            )
+            .map(|(filename, metadata)| {
+                let meta = metadata.expect("metadata was ensured present but not returned");
+                let cf = meta
+                    .curseforge
+                    .flatten()
+                    .expect("curseforge was ensured present but not returned");
+                let row = cf
+                    .logo_image
+                    .flatten()
+                    .expect("mod image was ensured present but not returned");
+
+                (filename, cf.project_id, cf.file_id, row)
+            });
 
         let app = &app;
         let futures = modlist
@@ -352,6 +399,56 @@ impl ModplatformCacher for CurseforgeModCacher {
     }
 }
 
+/// Cache curseforge metadata for a mod known to be the given file, without a fingerprint lookup
+pub(super) async fn seed_curseforge_meta(
+    app: &App,
+    metadata_id: String,
+    murmur2: u32,
+    project_id: u32,
+    file_id: u32,
+) -> anyhow::Result<()> {
+    let mpm = app.modplatforms_manager();
+
+    let file = mpm
+        .curseforge
+        .get_mod_file(ModFileParameters {
+            mod_id: project_id as i32,
+            file_id: file_id as i32,
+        })
+        .await?
+        .data;
+
+    // the file on disk may have been replaced since it was recorded
+    if file.file_fingerprint != murmur2 {
+        bail!("mod file does not match curseforge file {project_id}/{file_id}");
+    }
+
+    let cfmod = mpm
+        .curseforge
+        .get_mod(ModParameters {
+            mod_id: project_id as i32,
+        })
+        .await?
+        .data;
+
+    let files = mpm
+        .curseforge
+        .get_mod_files(ModFilesParameters {
+            mod_id: project_id as i32,
+            query: ModFilesParametersQuery {
+                game_version: None,
+                mod_loader_type: None,
+                game_version_type_id: None,
+                index: None,
+                page_size: None,
+            },
+        })
+        .await?
+        .data;
+
+    cache_curseforge_meta_unchecked(app, metadata_id, &file, murmur2, &cfmod, &files).await
+}
+
 // Cache curseforge metadata for a mod without downloading the icon
 async fn cache_curseforge_meta_unchecked(
     app: &App,
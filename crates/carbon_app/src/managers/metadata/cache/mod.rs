@@ -34,12 +34,15 @@ use tracing::trace;
 use tracing::warn;
 use uuid::Uuid;
 
-use crate::api::keys::instance::INSTANCE_MODS;
+use crate::api::keys::instance::{
+    INSTANCE_MODS, INSTANCE_RESOURCE_PACKS, INSTANCE_SHADER_PACKS, MISSING_SHADER_LOADER,
+};
 use crate::db::read_filters::BytesFilter;
 use crate::db::read_filters::IntFilter;
 use crate::db::read_filters::StringFilter;
-use crate::db::{mod_file_cache as fcdb, mod_metadata as metadb};
-use crate::domain::instance::InstanceId;
+use crate::db::{mod_file_cache as fcdb, mod_metadata as metadb, pack_file_cache as pcdb};
+use crate::domain::instance::{InstanceId, PackType};
+use crate::domain::modplatforms::ModFileSource;
 
 use crate::domain::runtime_path::InstancesPath;
 use crate::managers::App;
@@ -47,6 +50,7 @@ use crate::managers::ManagerRef;
 
 pub mod curseforge;
 pub mod modrinth;
+mod packs;
 
 use curseforge::CurseforgeModCacher;
 use modrinth::ModrinthModCacher;
@@ -668,6 +672,14 @@ impl ManagerRef<'_, MetaCacheManager> {
             .exec()
             .await;
 
+        let _ = self
+            .app
+            .prisma_client
+            .pack_file_cache()
+            .delete_many(vec![pcdb::instance_id::equals(*instance_id)])
+            .exec()
+            .await;
+
         self.gc_mod_metadata().await;
     }
 
@@ -676,7 +688,10 @@ impl ManagerRef<'_, MetaCacheManager> {
             .app
             .prisma_client
             .mod_metadata()
-            .delete_many(vec![metadb::WhereParam::CachedFilesNone(Vec::new())])
+            .delete_many(vec![
+                metadb::WhereParam::CachedFilesNone(Vec::new()),
+                metadb::WhereParam::CachedPacksNone(Vec::new()),
+            ])
             .exec()
             .await;
     }
@@ -806,6 +821,70 @@ impl ManagerRef<'_, MetaCacheManager> {
         Ok(())
     }
 
+    /// Links mod files to the platform files they are known to be from, by filename.
+    ///
+    /// Files that no longer match their platform file are left to the fingerprint lookup.
+    pub async fn seed_mod_sources(
+        self,
+        instance_id: InstanceId,
+        sources: Vec<(String, ModFileSource)>,
+    ) -> anyhow::Result<()> {
+        self.override_caching_and_wait(instance_id, false, false)
+            .await?;
+
+        let cached_files = self
+            .app
+            .prisma_client
+            .mod_file_cache()
+            .find_many(vec![fcdb::instance_id::equals(*instance_id)])
+            .with(fcdb::metadata::fetch())
+            .exec()
+            .await?;
+
+        for (filename, source) in sources {
+            let Some(metadata) = cached_files
+                .iter()
+                .find(|file| file.filename == filename)
+                .and_then(|file| file.metadata.as_deref())
+            else {
+                continue;
+            };
+
+            let r = match source {
+                ModFileSource::Curseforge {
+                    project_id,
+                    file_id,
+                } => {
+                    curseforge::seed_curseforge_meta(
+                        self.app,
+                        metadata.id.clone(),
+                        metadata.murmur_2 as u32,
+                        project_id,
+                        file_id,
+                    )
+                    .await
+                }
+                ModFileSource::Modrinth { version_id } => {
+                    modrinth::seed_modrinth_meta(
+                        self.app,
+                        metadata.id.clone(),
+                        hex::encode(&metadata.sha_512),
+                        version_id,
+                    )
+                    .await
+                }
+            };
+
+            if let Err(e) = r {
+                warn!({ error = ?e }, "could not link {filename} to its platform file");
+            }
+        }
+
+        self.app
+            .invalidate(INSTANCE_MODS, Some(instance_id.0.into()));
+        Ok(())
+    }
+
     pub async fn watch_and_prioritize(self, instance_id: Option<InstanceId>) {
         let _ = self.watched_instance.send(instance_id);
 
@@ -881,6 +960,9 @@ impl ManagerRef<'_, MetaCacheManager> {
                 tokio::select! {
                     _ = list_debounce_rx.changed() => {
                         app_debounce.invalidate(INSTANCE_MODS, Some(watched.0.into()));
+                        app_debounce.invalidate(INSTANCE_RESOURCE_PACKS, Some(watched.0.into()));
+                        app_debounce.invalidate(INSTANCE_SHADER_PACKS, Some(watched.0.into()));
+                        app_debounce.invalidate(MISSING_SHADER_LOADER, Some(watched.0.into()));
                         tokio::time::sleep(std::time::Duration::from_secs(2)).await;
                     },
                     r = debounce_watch_rx.changed() => {
@@ -930,26 +1012,13 @@ impl ManagerRef<'_, MetaCacheManager> {
 
         let mut file = tokio::fs::File::open(path).await?;
 
-        let mut sha512 = Sha512::new();
-        let mut sha1 = Sha1::new();
-        let mut murmur_len = 0;
-        let mut content_len = 0;
-
-        carbon_scheduler::buffered_digest(&mut file, |chunk| {
-            sha512.update(&chunk);
-            sha1.update(&chunk);
-            murmur_len += chunk
-                .iter()
-                .filter(|&&x| x != 9 && x != 10 && x != 13 && x != 32)
-                .count();
-            content_len += chunk.len();
-        })
-        .await?;
-
-        let sha512: [u8; 64] = sha512.finalize().into();
-        let sha1: [u8; 20] = sha1.finalize().into();
+        let FileDigest {
+            sha512,
+            sha1,
+            murmur2,
+            len: content_len,
+        } = digest_file(&mut file).await?;
 
-        file.seek(SeekFrom::Start(0)).await?;
         let mut file = file.into_std().await;
 
         let (file, meta, image_data) = tokio::task::spawn_blocking(|| {
@@ -980,21 +1049,6 @@ impl ManagerRef<'_, MetaCacheManager> {
         })
         .await??;
 
-        let mut file = tokio::fs::File::from_std(file);
-        file.seek(SeekFrom::Start(0)).await?;
-        let mut murmur2 = Murmur2Digest::new(murmur_len as u32);
-
-        let mut workbuf = Vec::<u8>::with_capacity(carbon_scheduler::BUFSIZE);
-
-        carbon_scheduler::buffered_digest(&mut file, |chunk| {
-            workbuf.splice(.., chunk.iter().map(|&b| b));
-            workbuf.retain(|&x| x != 9 && x != 10 && x != 13 && x != 32);
-            murmur2.update(&workbuf[..]);
-        })
-        .await?;
-
-        let murmur2 = murmur2.finalize();
-
         drop(file);
 
         let meta = match meta {
@@ -1123,6 +1177,54 @@ impl ManagerRef<'_, MetaCacheManager> {
     }
 }
 
+struct FileDigest {
+    sha512: [u8; 64],
+    sha1: [u8; 20],
+    murmur2: u32,
+    len: usize,
+}
+
+/// Hash a file the way local and remote metadata is looked up,
+/// leaving the file at its start.
+async fn digest_file(file: &mut tokio::fs::File) -> anyhow::Result<FileDigest> {
+    let mut sha512 = Sha512::new();
+    let mut sha1 = Sha1::new();
+    let mut murmur_len = 0;
+    let mut content_len = 0;
+
+    carbon_scheduler::buffered_digest(&mut *file, |chunk| {
+        sha512.update(&chunk);
+        sha1.update(&chunk);
+        murmur_len += chunk
+            .iter()
+            .filter(|&&x| x != 9 && x != 10 && x != 13 && x != 32)
+            .count();
+        content_len += chunk.len();
+    })
+    .await?;
+
+    file.seek(SeekFrom::Start(0)).await?;
+    let mut murmur2 = Murmur2Digest::new(murmur_len as u32);
+
+    let mut workbuf = Vec::<u8>::with_capacity(carbon_scheduler::BUFSIZE);
+
+    carbon_scheduler::buffered_digest(&mut *file, |chunk| {
+        workbuf.splice(.., chunk.iter().map(|&b| b));
+        workbuf.retain(|&x| x != 9 && x != 10 && x != 13 && x != 32);
+        murmur2.update(&workbuf[..]);
+    })
+    .await?;
+
+    file.seek(SeekFrom::Start(0)).await?;
+
+    Ok(FileDigest {
+        sha512: sha512.finalize().into(),
+        sha1: sha1.finalize().into(),
+        murmur2: murmur2.finalize(),
+        len: content_len,
+    })
+}
+
 fn scale_mod_image(image: &[u8]) -> anyhow::Result<Vec<u8>> {
     use image::imageops::*;
 
@@ -1192,8 +1294,30 @@ fn cache_local(app: App, rx: LockNotify<CacheTargets>, update_notifier: UpdateNo
                 .get_instance_path(&instance.shortpath)
                 .get_mods_path();
 
+            let instance_path = app
+                .settings_manager()
+                .runtime_path
+                .get_instances()
+                .get_instance_path(&instance.shortpath);
+
             drop(instances);
 
+            for pack_type in PackType::ALL {
+                let r = app
+                    .meta_cache_manager()
+                    .cache_pack_folder(
+                        instance_id,
+                        pack_type,
+                        &pack_type.path(&instance_path),
+                        update_notifier,
+                    )
+                    .await;
+
+                if let Err(e) = r {
+                    error!({ error = ?e }, "could not scan {} folder for instance {instance_id}", pack_type.to_string());
+                }
+            }
+
             let mut pathbuf = PathBuf::new();
             pathbuf.push(app.settings_manager().runtime_path.get_root().to_path());
             pathbuf.push(&subpath);
@@ -1,18 +1,18 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::{Duration, Instant};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use itertools::Itertools;
 use tracing::{debug, error, trace, warn};
 
 use crate::db::{
     mod_file_cache as fcdb, mod_metadata as metadb, modrinth_mod_cache as mrdb,
-    modrinth_mod_image_cache as mrimgdb,
+    modrinth_mod_image_cache as mrimgdb, pack_file_cache as pcdb,
 };
 use crate::domain::instance::info::ModLoaderType;
 use crate::domain::modplatforms::modrinth::project::ProjectVersionsFilters;
 use crate::domain::modplatforms::modrinth::responses::VersionsResponse;
-use crate::domain::modplatforms::modrinth::search::{ProjectID, VersionIDs};
+use crate::domain::modplatforms::modrinth::search::{ProjectID, TeamID, VersionID, VersionIDs};
 use crate::domain::modplatforms::modrinth::version::Version;
 use crate::domain::modplatforms::ModChannel;
 use crate::{
@@ -68,14 +68,36 @@ impl ModplatformCacher for ModrinthModCacher {
             .await?
             .into_iter()
             .map(|m| {
-                let metadata = m
-                    .metadata
-                    .expect("metadata was queried with mod cache yet is not present");
-                let sha512 = hex::encode(&metadata.sha_512);
+                m.metadata
+                    .expect("metadata was queried with mod cache yet is not present")
+            });
 
-                (sha512.clone(), (metadata.id, sha512))
+        let packlist = app
+            .prisma_client
+            .pack_file_cache()
+            .find_many(vec![
+                pcdb::instance_id::equals(*instance_id),
+                pcdb::metadata::is(vec![metadb::WhereParam::ModrinthIsNot(vec![
+                    mrdb::WhereParam::CachedAt(DateTimeFilter::Gt(
+                        (chrono::Utc::now() - chrono::Duration::days(1)).into(),
+                    )),
+                ])]),
+            ])
+            .with(pcdb::metadata::fetch())
+            .exec()
+            .await?
+            .into_iter()
+            .map(|p| {
+                p.metadata
+                    .expect("metadata was queried with pack cache yet is not present")
             });
 
+        let modlist = modlist.chain(packlist).map(|metadata| {
+            let sha512 = hex::encode(&metadata.sha_512);
+
+            (sha512.clone(), (metadata.id, sha512))
+        });
+
         let mcm = app.meta_cache_manager();
         let ignored_hashes = mcm.ignored_remote_mr_hashes.read().await;
 
@@ -289,29 +311,53 @@ impl ModplatformCacher for ModrinthModCacher {
             .exec()
             .await;
 
-        let modlist = match modlist {
-            Ok(modlist) => modlist,
-            Err(e) => {
+        let packlist = app
+            .prisma_client
+            .pack_file_cache()
+            .find_many(vec![
+                pcdb::instance_id::equals(*instance_id),
+                pcdb::metadata::is(vec![metadb::WhereParam::ModrinthIs(vec![
+                    mrdb::WhereParam::LogoImageIs(vec![mrimgdb::WhereParam::UpToDate(
+                        IntFilter::Equals(0),
+                    )]),
+                ])]),
+            ])
+            .with(
+                pcdb::metadata::fetch()
+                    .with(metadb::modrinth::fetch().with(mrdb::logo_image::fetch())),
+            )
+            .exec()
+            .await;
+
+        let (modlist, packlist) = match (modlist, packlist) {
+            (Ok(modlist), Ok(packlist)) => (modlist, packlist),
+            (Err(e), _) | (_, Err(e)) => {
                 error!({ error = ?e }, "error querying database for updated curseforge mod icons list");
                 return;
             }
         };
 
-        let modlist = modlist.into_iter().map(|file| {
-            let meta = file
-                .metadata
-                .expect("metadata was ensured present but not returned");
-            let mr = meta
-                .modrinth
-                .flatten()
-                .expect("modrinth was ensured present but not returned");
-            let row = mr
-                .logo_image
-                .flatten()
-                .expect("mod image was ensured present but not returned");
-
-            (file.filename, mr.project_id, mr.version_id, row)
-        });
+        let modlist = modlist
+            .into_iter()
+            .map(|file| (file.filename, file.metadata))
+            .chain(
+                packlist
+                    .into_iter()
+                    .map(|file| (file.filename, file.metadata)),
+            )
+            .map(|(filename, metadata)| {
+                let meta = metadata.expect("metadata was ensured present but not returned");
+                let mr = meta
+                    .modrinth
+                    .flatten()
+                    .expect("modrinth was ensured present but not returned");
+                let row = mr
+                    .logo_image
+                    .flatten()
+                    .expect("mod image was ensured present but not returned");
+
+                (filename, mr.project_id, mr.version_id, row)
+            });
 
         let app = &app;
         let futures = modlist
@@ -401,6 +447,74 @@ impl ModplatformCacher for ModrinthModCacher {
     }
 }
 
+/// Cache modrinth metadata for a mod known to be from the given version, without a hash lookup
+pub(super) async fn seed_modrinth_meta(
+    app: &App,
+    metadata_id: String,
+    sha512: String,
+    version_id: String,
+) -> anyhow::Result<()> {
+    let mpm = app.modplatforms_manager();
+
+    let version = mpm
+        .modrinth
+        .get_version(VersionID(version_id.clone()))
+        .await?;
+
+    // the file on disk may have been replaced since it was recorded
+    let Some(file) = version
+        .files
+        .iter()
+        .find(|file| file.hashes.sha512 == sha512)
+    else {
+        bail!("mod file does not match any file of modrinth version {version_id}");
+    };
+
+    let project = mpm
+        .modrinth
+        .get_project(ProjectID(version.project_id.clone()))
+        .await?;
+
+    let authors = mpm
+        .modrinth
+        .get_team(TeamID(project.team.clone()))
+        .await?
+        .iter()
+        .map(|member| {
+            member
+                .user
+                .name
+                .clone()
+                .unwrap_or_else(|| member.user.username.clone())
+        })
+        .join(", ");
+
+    let mut versions = Vec::new();
+    for chunk in project.versions.chunks(1000) {
+        versions.extend(
+            mpm.modrinth
+                .get_versions(VersionIDs {
+                    ids: chunk.to_vec(),
+                })
+                .await?
+                .0,
+        );
+    }
+
+    cache_modrinth_meta_unchecked(
+        app,
+        metadata_id,
+        &version,
+        sha512,
+        file.filename.clone(),
+        file.url.clone(),
+        project,
+        authors,
+        &versions,
+    )
+    .await
+}
+
 // Cache modrinth metadata for a mod without downloading the icon
 async fn cache_modrinth_meta_unchecked(
     app: &App,
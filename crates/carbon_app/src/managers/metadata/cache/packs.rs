@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use tracing::{debug, error, trace, warn};
+use uuid::Uuid;
+
+use crate::db::read_filters::{BytesFilter, IntFilter};
+use crate::db::{mod_metadata as metadb, pack_file_cache as pcdb};
+use crate::domain::instance::{InstanceId, PackType};
+use crate::managers::metadata::packs::{parse_pack_metadata, read_pack_icon};
+use crate::managers::ManagerRef;
+
+use super::{digest_file, scale_mod_image, FileDigest, MetaCacheManager, UpdateNotifier};
+
+impl ManagerRef<'_, MetaCacheManager> {
+    /// Bring the cached entries for the packs in `pack_dir` up to date with the files on disk.
+    pub(super) async fn cache_pack_folder(
+        self,
+        instance_id: InstanceId,
+        pack_type: PackType,
+        pack_dir: &Path,
+        update_notifier: &UpdateNotifier,
+    ) -> anyhow::Result<()> {
+        let cached_entries = self
+            .app
+            .prisma_client
+            .pack_file_cache()
+            .find_many(vec![
+                pcdb::instance_id::equals(*instance_id),
+                pcdb::pack_type::equals(pack_type.to_string()),
+            ])
+            .exec()
+            .await?;
+
+        let mut packpaths = HashMap::<String, (bool, u64)>::new();
+
+        if pack_dir.is_dir() {
+            let mut entries = tokio::fs::read_dir(pack_dir).await?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                let file_name = entry.file_name();
+                let Some(utf8_name) = file_name.to_str() else {
+                    continue;
+                };
+
+                let (utf8_name, enabled) = match utf8_name.strip_suffix(".disabled") {
+                    Some(name) => (name, false),
+                    None => (utf8_name, true),
+                };
+
+                // unpacked folders can't be hashed or matched against a platform
+                if !utf8_name.ends_with(".zip") {
+                    continue;
+                }
+
+                let Ok(metadata) = entry.metadata().await else {
+                    continue;
+                };
+
+                if metadata.is_dir() {
+                    continue;
+                }
+
+                packpaths.insert(utf8_name.to_string(), (enabled, metadata.len()));
+            }
+        }
+
+        let mut has_outdated_entries = false;
+
+        for entry in cached_entries {
+            match packpaths.get(&entry.filename) {
+                Some((enabled, real_size))
+                    if *real_size == entry.filesize as u64 && *enabled == entry.enabled =>
+                {
+                    packpaths.remove(&entry.filename);
+                    continue;
+                }
+                Some(_) => {
+                    trace!(
+                        "outdated metadata entry for pack `{}`, adding to update list",
+                        &entry.filename
+                    );
+                }
+                None => {
+                    trace!(
+                        "removed metadata entry for pack `{}`, removing",
+                        &entry.filename
+                    );
+
+                    self.app
+                        .prisma_client
+                        .pack_file_cache()
+                        .delete(pcdb::UniqueWhereParam::IdEquals(entry.id))
+                        .exec()
+                        .await?;
+                }
+            }
+
+            has_outdated_entries = true;
+        }
+
+        for (filename, (enabled, _)) in packpaths {
+            let r = self
+                .cache_pack_file_unchecked(
+                    instance_id,
+                    pack_type,
+                    pack_dir,
+                    filename.clone(),
+                    enabled,
+                )
+                .await;
+
+            match r {
+                Ok(()) => update_notifier.send(instance_id),
+                Err(e) => {
+                    error!({ error = ?e }, "could not cache pack {filename} for instance {instance_id}")
+                }
+            }
+        }
+
+        if has_outdated_entries {
+            update_notifier.send(instance_id);
+        }
+
+        Ok(())
+    }
+
+    /// Cache a pack file without first checking the validity of the instance
+    async fn cache_pack_file_unchecked(
+        self,
+        instance_id: InstanceId,
+        pack_type: PackType,
+        pack_dir: &Path,
+        filename: String,
+        enabled: bool,
+    ) -> anyhow::Result<()> {
+        let path = match enabled {
+            true => pack_dir.join(&filename),
+            false => pack_dir.join(format!("{filename}.disabled")),
+        };
+
+        let mut file = tokio::fs::File::open(path).await?;
+
+        let FileDigest {
+            sha512,
+            sha1,
+            murmur2,
+            len,
+        } = digest_file(&mut file).await?;
+
+        let mut file = file.into_std().await;
+
+        let (meta, image_data) = tokio::task::spawn_blocking(move || {
+            (parse_pack_metadata(&mut file), read_pack_icon(&mut file))
+        })
+        .await?;
+
+        let meta = meta.unwrap_or_else(|e| {
+            warn!({ error = ?e }, "could not parse pack metadata for {}", filename);
+            None
+        });
+
+        let image_data = image_data.unwrap_or_else(|e| {
+            warn!({ error = ?e }, "could not read pack icon for {}", filename);
+            None
+        });
+
+        let dbmeta = self
+            .app
+            .prisma_client
+            .mod_metadata()
+            .find_first(vec![
+                metadb::WhereParam::Sha512(BytesFilter::Equals(Vec::from(sha512))),
+                metadb::WhereParam::Murmur2(IntFilter::Equals(murmur2 as i32)),
+            ])
+            .exec()
+            .await?;
+
+        let (meta_id, meta_insert, logo_insert) = match dbmeta {
+            Some(meta) => (meta.id, None, None),
+            None => {
+                let meta_id = Uuid::new_v4().to_string();
+
+                let logo_insert = match image_data {
+                    Some(image_data) => {
+                        let permit = self
+                            .image_scale_semaphore
+                            .acquire()
+                            .await
+                            .expect("the image scale semaphore is never closed");
+
+                        let logo =
+                            carbon_scheduler::cpu_block(|| scale_mod_image(&image_data[..])).await;
+
+                        drop(permit);
+
+                        match logo {
+                            Ok(data) => {
+                                Some(self.app.prisma_client.local_mod_image_cache().create(
+                                    data,
+                                    metadb::UniqueWhereParam::IdEquals(meta_id.clone()),
+                                    Vec::new(),
+                                ))
+                            }
+                            Err(e) => {
+                                error!({ error = ?e }, "could not scale pack icon for {}", filename);
+                                None
+                            }
+                        }
+                    }
+                    None => None,
+                };
+
+                // packs have no loaders and only describe themselves in pack.mcmeta
+                let meta_insert = self.app.prisma_client.mod_metadata().create(
+                    meta_id.clone(),
+                    murmur2 as i32,
+                    Vec::from(sha512),
+                    Vec::from(sha1),
+                    String::new(),
+                    vec![
+                        metadb::SetParam::SetName(None),
+                        metadb::SetParam::SetModid(None),
+                        metadb::SetParam::SetVersion(None),
+                        metadb::SetParam::SetDescription(
+                            meta.as_ref().and_then(|meta| meta.description.clone()),
+                        ),
+                        metadb::SetParam::SetAuthors(None),
+                    ],
+                );
+
+                (meta_id, Some(meta_insert), logo_insert)
+            }
+        };
+
+        let filecache_delete = self.app.prisma_client.pack_file_cache().delete_many(vec![
+            pcdb::instance_id::equals(*instance_id),
+            pcdb::pack_type::equals(pack_type.to_string()),
+            pcdb::filename::equals(filename.clone()),
+        ]);
+
+        let filecache_insert = self.app.prisma_client.pack_file_cache().create(
+            crate::db::instance::UniqueWhereParam::IdEquals(*instance_id),
+            pack_type.to_string(),
+            filename.clone(),
+            len as i32,
+            enabled,
+            metadb::UniqueWhereParam::IdEquals(meta_id),
+            vec![pcdb::SetParam::SetPackFormat(
+                meta.and_then(|meta| meta.pack_format),
+            )],
+        );
+
+        debug!(
+            "updating pack metadata entries for {}/{filename}",
+            *instance_id
+        );
+
+        self.app
+            .prisma_client
+            ._batch((
+                meta_insert.into_iter().collect::<Vec<_>>(),
+                logo_insert.into_iter().collect::<Vec<_>>(),
+                filecache_delete,
+                filecache_insert,
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -94,6 +94,8 @@ impl TryFrom<McModInfoContainer> for McModInfo {
 // https://github.com/MinecraftForge/Documentation/blob/5ab4ba6cf9abc0ac4c0abd96ad187461aefd72af/docs/gettingstarted/structuring.md
 struct ModsToml {
     mods: Vec<ModsTomlEntry>,
+    #[serde(rename = "clientSideOnly", default)]
+    client_side_only: bool,
 }
 
 #[derive(Deserialize)]
@@ -494,6 +496,49 @@ struct QuiltModJson {
     minecraft: Option<QuiltMinecraftSection>,
 }
 
+/// Side a mod file declares it runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModEnvironment {
+    Both,
+    Client,
+    Server,
+}
+
+impl From<&FabricEnvironment> for ModEnvironment {
+    fn from(value: &FabricEnvironment) -> Self {
+        let entries = match value {
+            FabricEnvironment::Single(entry) => std::slice::from_ref(entry),
+            FabricEnvironment::List(entries) => &entries[..],
+        };
+
+        let client = entries
+            .iter()
+            .any(|e| matches!(e, FabricEnvironmentEntry::Client));
+        let server = entries
+            .iter()
+            .any(|e| matches!(e, FabricEnvironmentEntry::Server));
+        let everywhere = entries
+            .iter()
+            .any(|e| matches!(e, FabricEnvironmentEntry::Everywhere));
+
+        match (client, server) {
+            (true, false) if !everywhere => Self::Client,
+            (false, true) if !everywhere => Self::Server,
+            _ => Self::Both,
+        }
+    }
+}
+
+impl From<&QuiltMinecraftEnvironment> for ModEnvironment {
+    fn from(value: &QuiltMinecraftEnvironment) -> Self {
+        match value {
+            QuiltMinecraftEnvironment::All => Self::Both,
+            QuiltMinecraftEnvironment::Client => Self::Client,
+            QuiltMinecraftEnvironment::DedicatedServer => Self::Server,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ModFileMetadata {
     pub modid: Option<String>,
@@ -737,6 +782,52 @@ pub fn parse_metadata(reader: &mut (impl Read + Seek)) -> anyhow::Result<Option<
     Ok(mod_metadata)
 }
 
+/// Reads the environment a mod jar declares in its loader metadata.
+///
+/// Mods without any environment information are assumed to run on both sides.
+pub fn parse_environment(reader: &mut (impl Read + Seek)) -> anyhow::Result<ModEnvironment> {
+    let mut zip = zip::ZipArchive::new(reader)?;
+
+    if let Ok(mut file) = zip.by_name("fabric.mod.json") {
+        let mut content = String::with_capacity(file.size() as usize);
+        file.read_to_string(&mut content)?;
+
+        let environment = match serde_json::from_str::<FabricModJson>(&content)? {
+            FabricModJson::Single(info) => info.environment,
+            FabricModJson::List(list) => list.into_iter().next().and_then(|info| info.environment),
+        };
+
+        if let Some(environment) = environment {
+            return Ok((&environment).into());
+        }
+    }
+
+    if let Ok(mut file) = zip.by_name("quilt.mod.json") {
+        let mut content = String::with_capacity(file.size() as usize);
+        file.read_to_string(&mut content)?;
+
+        let quilt_mod_json = serde_json::from_str::<QuiltModJson>(&content)?;
+
+        if let Some(environment) = quilt_mod_json.minecraft.and_then(|m| m.environment) {
+            return Ok((&environment).into());
+        }
+    }
+
+    // neoforge renamed the file in 20.5
+    for path in ["META-INF/mods.toml", "META-INF/neoforge.mods.toml"] {
+        if let Ok(mut file) = zip.by_name(path) {
+            let mut content = String::with_capacity(file.size() as usize);
+            file.read_to_string(&mut content)?;
+
+            if toml::from_str::<ModsToml>(&content)?.client_side_only {
+                return Ok(ModEnvironment::Client);
+            }
+        }
+    }
+
+    Ok(ModEnvironment::Both)
+}
+
 #[cfg(test)]
 mod test {
     use std::io::{Cursor, Write};
@@ -745,7 +836,19 @@ mod test {
 
     use crate::domain::instance::info::ModLoaderType;
 
-    use super::{parse_metadata, ModFileMetadata};
+    use super::{parse_environment, parse_metadata, ModEnvironment, ModFileMetadata};
+
+    pub fn parseenv(path: &str, content: &str) -> anyhow::Result<ModEnvironment> {
+        let mut vec = Vec::<u8>::new();
+        let mut zip = ZipWriter::new(Cursor::new(&mut vec));
+        let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+        zip.start_file(path, options)?;
+        zip.write_all(content.as_bytes())?;
+        zip.finish()?;
+        drop(zip);
+
+        parse_environment(&mut Cursor::new(&vec))
+    }
 
     pub fn parsemeta(path: &str, content: &str) -> anyhow::Result<Option<ModFileMetadata>> {
         // write meta zip
@@ -1109,4 +1212,58 @@ displayName = "TestMod"
 
         Ok(())
     }
+
+    #[test]
+    pub fn fabric_environment() -> anyhow::Result<()> {
+        let client =
+            r#"{ "schemaVersion": 1, "id": "test", "version": "1.0.0", "environment": "client" }"#;
+        let everywhere =
+            r#"{ "schemaVersion": 1, "id": "test", "version": "1.0.0", "environment": "*" }"#;
+        let unset = r#"{ "schemaVersion": 1, "id": "test", "version": "1.0.0" }"#;
+
+        assert_eq!(parseenv("fabric.mod.json", client)?, ModEnvironment::Client);
+        assert_eq!(
+            parseenv("fabric.mod.json", everywhere)?,
+            ModEnvironment::Both
+        );
+        assert_eq!(parseenv("fabric.mod.json", unset)?, ModEnvironment::Both);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn quilt_environment() -> anyhow::Result<()> {
+        let modjson = r#"{
+            "schema_version": 1,
+            "quilt_loader": { "group": "com.test", "id": "test", "version": "1.0.0" },
+            "minecraft": { "environment": "dedicated_server" }
+        }"#;
+
+        assert_eq!(parseenv("quilt.mod.json", modjson)?, ModEnvironment::Server);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn forge_environment() -> anyhow::Result<()> {
+        let modstoml = r#"
+clientSideOnly = true
+
+[[mods]]
+modId = "test"
+version = "1.0.0"
+displayName = "Test"
+        "#;
+
+        assert_eq!(
+            parseenv("META-INF/mods.toml", modstoml)?,
+            ModEnvironment::Client
+        );
+        assert_eq!(
+            parseenv("META-INF/neoforge.mods.toml", modstoml)?,
+            ModEnvironment::Client
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,176 @@
+use std::io::{Read, Seek};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackMetadata {
+    pub pack_format: Option<i32>,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PackMcmeta {
+    pack: PackMcmetaSection,
+}
+
+#[derive(Deserialize)]
+struct PackMcmetaSection {
+    pack_format: Option<i32>,
+    description: Option<Value>,
+}
+
+/// Reads `pack.mcmeta` out of a resource pack or data pack archive.
+pub fn parse_pack_metadata(
+    reader: &mut (impl Read + Seek),
+) -> anyhow::Result<Option<PackMetadata>> {
+    let mut zip = zip::ZipArchive::new(reader)?;
+
+    let Ok(mut file) = zip.by_name("pack.mcmeta") else {
+        return Ok(None);
+    };
+
+    let mut content = Vec::with_capacity(file.size() as usize);
+    file.read_to_end(&mut content)?;
+
+    parse_pack_mcmeta(&content).map(Some)
+}
+
+/// Parses the contents of a `pack.mcmeta` file.
+pub fn parse_pack_mcmeta(content: &[u8]) -> anyhow::Result<PackMetadata> {
+    // some editors save the file with a byte order mark, which serde rejects
+    let content = content.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(content);
+    let mcmeta = serde_json::from_slice::<PackMcmeta>(content)?;
+
+    let description = mcmeta.pack.description.map(|description| {
+        let mut text = String::new();
+        flatten_text_component(&description, &mut text);
+        strip_formatting_codes(&text).trim().to_string()
+    });
+
+    Ok(PackMetadata {
+        pack_format: mcmeta.pack.pack_format,
+        description: description.filter(|description| !description.is_empty()),
+    })
+}
+
+/// Reads `pack.png` out of a pack archive.
+pub fn read_pack_icon(reader: &mut (impl Read + Seek)) -> anyhow::Result<Option<Vec<u8>>> {
+    let mut zip = zip::ZipArchive::new(reader)?;
+
+    let Ok(mut file) = zip.by_name("pack.png") else {
+        return Ok(None);
+    };
+
+    let mut image = Vec::with_capacity(file.size() as usize);
+    file.read_to_end(&mut image)?;
+
+    Ok(Some(image))
+}
+
+/// Appends the plain text of a json text component, which may be a string,
+/// an object with `text` and `extra` or a list of components.
+fn flatten_text_component(component: &Value, text: &mut String) {
+    match component {
+        Value::String(s) => text.push_str(s),
+        Value::Number(n) => text.push_str(&n.to_string()),
+        Value::Bool(b) => text.push_str(&b.to_string()),
+        Value::Array(components) => {
+            for component in components {
+                flatten_text_component(component, text);
+            }
+        }
+        Value::Object(object) => {
+            // translated components can't be resolved here, so show the fallback or the key
+            let own_text = object
+                .get("text")
+                .or_else(|| object.get("fallback"))
+                .or_else(|| object.get("translate"));
+
+            if let Some(component) = own_text {
+                flatten_text_component(component, text);
+            }
+
+            if let Some(extra) = object.get("extra") {
+                flatten_text_component(extra, text);
+            }
+        }
+        Value::Null => {}
+    }
+}
+
+/// Removes legacy `§` color and style codes.
+fn strip_formatting_codes(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Cursor, Write};
+
+    use zip::{write::FileOptions, ZipWriter};
+
+    use super::{parse_pack_mcmeta, parse_pack_metadata, PackMetadata};
+
+    #[test]
+    fn plain_description() -> anyhow::Result<()> {
+        let meta = parse_pack_mcmeta(
+            r#"{"pack": {"pack_format": 15, "description": "§6Faithful §r32x"}}"#.as_bytes(),
+        )?;
+
+        assert_eq!(
+            meta,
+            PackMetadata {
+                pack_format: Some(15),
+                description: Some(String::from("Faithful 32x")),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn text_component_description() -> anyhow::Result<()> {
+        let meta = parse_pack_mcmeta(
+            "\u{feff}{\"pack\": {\"pack_format\": 34, \"description\": [\
+                {\"text\": \"Fresh \", \"color\": \"green\", \"extra\": [{\"text\": \"Animations\"}]},\
+                \" v1\"\
+            ]}}"
+            .as_bytes(),
+        )?;
+
+        assert_eq!(meta.pack_format, Some(34));
+        assert_eq!(meta.description.as_deref(), Some("Fresh Animations v1"));
+
+        let meta = parse_pack_mcmeta(br#"{"pack": {"pack_format": 1, "description": ""}}"#)?;
+        assert_eq!(meta.description, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pack_archive() -> anyhow::Result<()> {
+        let mut vec = Vec::<u8>::new();
+        let mut zip = ZipWriter::new(Cursor::new(&mut vec));
+        zip.start_file("pack.mcmeta", FileOptions::default())?;
+        zip.write_all(br#"{"pack": {"pack_format": 8, "description": "test"}}"#)?;
+        zip.finish()?;
+        drop(zip);
+
+        let meta = parse_pack_metadata(&mut Cursor::new(&vec))?;
+        assert_eq!(meta.and_then(|meta| meta.pack_format), Some(8));
+
+        Ok(())
+    }
+}
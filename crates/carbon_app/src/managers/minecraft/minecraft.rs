@@ -8,6 +8,7 @@ use crate::{
     app_version::APP_VERSION,
     db::{app_configuration::pre_launch_hook, PrismaClient},
     domain::{
+        instance::QuickPlay,
         java::{JavaArch, JavaComponent},
         minecraft::minecraft::{
             chain_lwjgl_libs_with_base_libs, get_default_jvm_args, is_rule_allowed,
@@ -15,7 +16,7 @@ use crate::{
         },
     },
 };
-use anyhow::Context;
+use anyhow::{bail, Context};
 use daedalus::minecraft::{
     Argument, ArgumentType, ArgumentValue, Library, LibraryGroup, Os, Version, VersionInfo,
     VersionManifest,
@@ -422,6 +423,7 @@ pub async fn generate_startup_command(
     lwjgl_group: &LibraryGroup,
     instance_path: InstancePath,
     assets_dir: super::assets::AssetsDir,
+    quick_play: Option<&QuickPlay>,
 ) -> anyhow::Result<Vec<String>> {
     let mut libraries = chain_lwjgl_libs_with_base_libs(
         &version.libraries,
@@ -443,8 +445,6 @@ pub async fn generate_startup_command(
         Regex::new(r"--(?P<arg>\S+)\s+\$\{(?P<value>[^}]+)\}|(\$\{(?P<standalone>[^}]+)\})")
             .unwrap();
 
-    let extra_args_regex = Regex::new(r#"("(?P<quoted>(\\"|[^"])*)"|(?P<raw>([^ ]+)))"#).unwrap();
-
     let player_token = match full_account.type_ {
         FullAccountType::Offline => "offline".to_owned(),
         FullAccountType::Microsoft { access_token, .. } => access_token,
@@ -624,12 +624,7 @@ pub async fn generate_startup_command(
         substitute_arguments(&mut command, jvm_arguments);
     }
 
-    for cap in extra_args_regex.captures_iter(extra_java_args) {
-        let ((Some(arg), _) | (_, Some(arg))) = (cap.name("quoted"), cap.name("raw")) else {
-            continue;
-        };
-        command.push(arg.as_str().replace("\\\"", "\"").replace("\\\\", "\\"));
-    }
+    command.extend(split_java_args(extra_java_args));
 
     if Os::native() == Os::Osx {
         let lwjgl_3 = version
@@ -657,9 +652,97 @@ pub async fn generate_startup_command(
         command.push(format!("--height={}", game_resolution.1));
     }
 
+    if let Some(quick_play) = quick_play {
+        // quick play arguments are only listed, behind feature rules, by versions that support them
+        let supports_quick_play = arguments
+            .get(&ArgumentType::Game)
+            .unwrap()
+            .iter()
+            .any(|arg| match arg {
+                Argument::Normal(arg) => arg.starts_with("--quickPlay"),
+                Argument::Ruled { value, .. } => match value {
+                    ArgumentValue::Single(arg) => arg.starts_with("--quickPlay"),
+                    ArgumentValue::Many(args) => {
+                        args.iter().any(|arg| arg.starts_with("--quickPlay"))
+                    }
+                },
+            });
+
+        command.extend(quick_play_arguments(
+            quick_play,
+            supports_quick_play,
+            &replacer_args.version_name,
+        )?);
+    }
+
     Ok(command)
 }
 
+/// Splits user provided java arguments on spaces, keeping double quoted arguments whole.
+pub fn split_java_args(args: &str) -> Vec<String> {
+    let regex = Regex::new(r#"("(?P<quoted>(\\"|[^"])*)"|(?P<raw>([^ ]+)))"#).unwrap();
+
+    regex
+        .captures_iter(args)
+        .filter_map(|cap| cap.name("quoted").or_else(|| cap.name("raw")))
+        .map(|arg| arg.as_str().replace("\\\"", "\"").replace("\\\\", "\\"))
+        .collect()
+}
+
+/// Arguments that join a world or server once the game has started.
+///
+/// Versions without quick play can only join servers, through the legacy
+/// `--server` and `--port` arguments.
+fn quick_play_arguments(
+    quick_play: &QuickPlay,
+    supports_quick_play: bool,
+    release: &str,
+) -> anyhow::Result<Vec<String>> {
+    Ok(match (quick_play, supports_quick_play) {
+        (QuickPlay::Singleplayer(world), true) => {
+            vec![String::from("--quickPlaySingleplayer"), world.clone()]
+        }
+        (QuickPlay::Multiplayer(address), true) => {
+            vec![String::from("--quickPlayMultiplayer"), address.clone()]
+        }
+        (QuickPlay::Singleplayer(_), false) => {
+            bail!("Minecraft {release} cannot launch directly into a world")
+        }
+        (QuickPlay::Multiplayer(address), false) => {
+            let (host, port) = split_server_address(address);
+            let mut args = vec![String::from("--server"), host.to_string()];
+
+            if let Some(port) = port {
+                args.push(String::from("--port"));
+                args.push(port.to_string());
+            }
+
+            args
+        }
+    })
+}
+
+/// Splits a server address into its host and optional port.
+fn split_server_address(address: &str) -> (&str, Option<u16>) {
+    let address = address.trim();
+
+    // bracketed ipv6 addresses, `[::1]:25565`
+    if let Some(rest) = address.strip_prefix('[') {
+        if let Some((host, port)) = rest.split_once(']') {
+            let port = port.strip_prefix(':').and_then(|port| port.parse().ok());
+            return (host, port);
+        }
+    }
+
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => match port.parse() {
+            Ok(port) => (host, Some(port)),
+            Err(_) => (address, None),
+        },
+        _ => (address, None),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn launch_minecraft(
     java_component: JavaComponent,
@@ -674,6 +757,7 @@ pub async fn launch_minecraft(
     instance_path: InstancePath,
     assets_dir: super::assets::AssetsDir,
     wrapper_command: Option<String>,
+    quick_play: Option<&QuickPlay>,
 ) -> anyhow::Result<Child> {
     let mut startup_command = generate_startup_command(
         java_component.clone(),
@@ -687,6 +771,7 @@ pub async fn launch_minecraft(
         lwjgl_group,
         instance_path.clone(),
         assets_dir,
+        quick_play,
     )
     .await?;
 
@@ -882,6 +967,7 @@ mod tests {
             &lwjgl_group,
             instance_id,
             assets_dir,
+            None,
         )
         .await
         .unwrap();
@@ -955,4 +1041,35 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[test]
+    fn test_quick_play_arguments() {
+        let world = QuickPlay::Singleplayer(String::from("New World"));
+        let server = QuickPlay::Multiplayer(String::from("play.example.com:25566"));
+
+        assert_eq!(
+            quick_play_arguments(&world, true, "1.20.1").unwrap(),
+            ["--quickPlaySingleplayer", "New World"]
+        );
+        assert_eq!(
+            quick_play_arguments(&server, true, "1.20.1").unwrap(),
+            ["--quickPlayMultiplayer", "play.example.com:25566"]
+        );
+        assert_eq!(
+            quick_play_arguments(&server, false, "1.16.5").unwrap(),
+            ["--server", "play.example.com", "--port", "25566"]
+        );
+        assert!(quick_play_arguments(&world, false, "1.16.5").is_err());
+    }
+
+    #[test]
+    fn test_split_server_address() {
+        assert_eq!(split_server_address("example.com"), ("example.com", None));
+        assert_eq!(
+            split_server_address("example.com:25565"),
+            ("example.com", Some(25565))
+        );
+        assert_eq!(split_server_address("[::1]:25565"), ("::1", Some(25565)));
+        assert_eq!(split_server_address("::1"), ("::1", None));
+    }
 }
@@ -29,6 +29,7 @@ pub mod minecraft;
 pub mod modrinth;
 pub mod neoforge;
 pub mod quilt;
+pub mod server;
 
 const META_VERSION: &str = formatcp!("v{}", CURRENT_FORMAT_VERSION);
 
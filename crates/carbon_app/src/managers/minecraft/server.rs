@@ -0,0 +1,460 @@
+//! Dedicated server installation and launch
+
+use std::path::Path;
+
+use anyhow::{anyhow, bail};
+use serde::Deserialize;
+use tokio::process::Child;
+use tracing::info;
+
+use crate::{
+    domain::{
+        instance::info::{ModLoader, ModLoaderType},
+        java::JavaComponent,
+        runtime_path::InstancePath,
+    },
+    managers::{instance::export::short_loader_version, minecraft::minecraft::split_java_args},
+};
+
+const FABRIC_INSTALLERS_URL: &str = "https://meta.fabricmc.net/v2/versions/installer";
+const QUILT_INSTALLERS_URL: &str = "https://meta.quiltmc.org/v3/versions/installer";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum InstallerSource {
+    Url(String),
+    /// Installer versions listed by the loader meta, of which the latest stable one is used
+    LoaderMeta(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ServerInstaller {
+    pub source: InstallerSource,
+    /// File name of the installer jar
+    pub jar: String,
+    pub args: String,
+}
+
+impl ServerInstaller {
+    /// Url of the installer jar, looking up the latest installer in the loader meta if needed.
+    pub async fn resolve_url(
+        &self,
+        reqwest_client: &reqwest_middleware::ClientWithMiddleware,
+    ) -> anyhow::Result<String> {
+        let meta_url = match &self.source {
+            InstallerSource::Url(url) => return Ok(url.clone()),
+            InstallerSource::LoaderMeta(meta_url) => meta_url,
+        };
+
+        let versions = reqwest_client
+            .get(meta_url.as_str())
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<InstallerVersion>>()
+            .await?;
+
+        latest_installer(versions).ok_or_else(|| anyhow!("No installer is listed at {meta_url}"))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct InstallerVersion {
+    url: String,
+    /// Only listed by fabric
+    stable: Option<bool>,
+}
+
+/// Loader metas list installers from newest to oldest.
+fn latest_installer(versions: Vec<InstallerVersion>) -> Option<String> {
+    versions
+        .into_iter()
+        .find(|version| version.stable != Some(false))
+        .map(|version| version.url)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ServerLaunch {
+    Jar(String),
+    /// Forge style `@args` files, which differ between unix and windows
+    ArgsFile {
+        unix: String,
+        windows: String,
+    },
+}
+
+impl ServerLaunch {
+    /// File that exists once the server has been installed.
+    pub fn marker(&self, windows: bool) -> &str {
+        match (self, windows) {
+            (Self::Jar(jar), _) => jar,
+            (Self::ArgsFile { unix, .. }, false) => unix,
+            (Self::ArgsFile { windows, .. }, true) => windows,
+        }
+    }
+
+    pub fn args(&self, windows: bool) -> String {
+        match self {
+            Self::Jar(jar) => format!("-jar {jar}"),
+            Self::ArgsFile { .. } => format!("@{}", self.marker(windows)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ServerSetup {
+    /// Where the loader expects the vanilla server jar, relative to the server root
+    pub server_jar: String,
+    /// Loader installer, vanilla servers launch the server jar directly
+    pub installer: Option<ServerInstaller>,
+    pub launch: ServerLaunch,
+}
+
+/// Picks the installer and launch command of a dedicated server.
+///
+/// When `download_server` is set the installer fetches the vanilla server jar
+/// itself, otherwise it is expected to already be at `server_jar`.
+pub fn server_setup(
+    release: &str,
+    modloader: Option<&ModLoader>,
+    download_server: bool,
+) -> ServerSetup {
+    let Some(modloader) = modloader else {
+        return ServerSetup {
+            server_jar: String::from("server.jar"),
+            installer: None,
+            launch: ServerLaunch::Jar(String::from("server.jar")),
+        };
+    };
+
+    let short_version = short_loader_version(release, modloader);
+
+    // forge switched to launching through argument files in 1.17
+    let minor = release
+        .split('.')
+        .nth(1)
+        .and_then(|minor| minor.parse::<u32>().ok());
+    let modern = !matches!(minor, Some(minor) if minor < 17);
+
+    let args_file = |libraries: String| ServerLaunch::ArgsFile {
+        unix: format!("libraries/{libraries}/unix_args.txt"),
+        windows: format!("libraries/{libraries}/win_args.txt"),
+    };
+
+    let library_server_jar =
+        format!("libraries/net/minecraft/server/{release}/server-{release}.jar");
+
+    match modloader.type_ {
+        ModLoaderType::Forge => {
+            let coordinate = match modloader.version.starts_with(&format!("{release}-")) {
+                true => modloader.version.clone(),
+                false => format!("{release}-{short_version}"),
+            };
+
+            ServerSetup {
+                server_jar: match modern {
+                    true => library_server_jar,
+                    false => format!("minecraft_server.{release}.jar"),
+                },
+                installer: Some(ServerInstaller {
+                    source: InstallerSource::Url(format!("https://maven.minecraftforge.net/net/minecraftforge/forge/{coordinate}/forge-{coordinate}-installer.jar")),
+                    jar: String::from("forge-installer.jar"),
+                    args: String::from("--installServer"),
+                }),
+                launch: match modern {
+                    true => args_file(format!("net/minecraftforge/forge/{coordinate}")),
+                    false => ServerLaunch::Jar(format!("forge-{coordinate}.jar")),
+                },
+            }
+        }
+        ModLoaderType::Neoforge => {
+            // the first neoforge releases for 1.20.1 kept the forge artifact name
+            let (artifact, coordinate) = match release {
+                "1.20.1" => ("forge", format!("{release}-{short_version}")),
+                _ => ("neoforge", short_version.to_string()),
+            };
+
+            ServerSetup {
+                server_jar: library_server_jar,
+                installer: Some(ServerInstaller {
+                    source: InstallerSource::Url(format!("https://maven.neoforged.net/releases/net/neoforged/{artifact}/{coordinate}/{artifact}-{coordinate}-installer.jar")),
+                    jar: String::from("neoforge-installer.jar"),
+                    args: String::from("--installServer"),
+                }),
+                launch: args_file(format!("net/neoforged/{artifact}/{coordinate}")),
+            }
+        }
+        ModLoaderType::Fabric => ServerSetup {
+            server_jar: String::from("server.jar"),
+            installer: Some(ServerInstaller {
+                source: InstallerSource::LoaderMeta(String::from(FABRIC_INSTALLERS_URL)),
+                jar: String::from("fabric-installer.jar"),
+                args: match download_server {
+                    true => format!("server -mcversion {release} -loader {short_version} -downloadMinecraft"),
+                    false => format!("server -mcversion {release} -loader {short_version}"),
+                },
+            }),
+            launch: ServerLaunch::Jar(String::from("fabric-server-launch.jar")),
+        },
+        ModLoaderType::Quilt => ServerSetup {
+            server_jar: String::from("server.jar"),
+            installer: Some(ServerInstaller {
+                source: InstallerSource::LoaderMeta(String::from(QUILT_INSTALLERS_URL)),
+                jar: String::from("quilt-installer.jar"),
+                args: match download_server {
+                    true => format!("install server {release} {short_version} --download-server --install-dir=."),
+                    false => format!("install server {release} {short_version} --install-dir=."),
+                },
+            }),
+            launch: ServerLaunch::Jar(String::from("quilt-server-launch.jar")),
+        },
+    }
+}
+
+/// Runs the loader installer inside `server_root`.
+pub async fn run_installer(
+    java_component: &JavaComponent,
+    installer: &ServerInstaller,
+    installer_path: &Path,
+    server_root: &Path,
+) -> anyhow::Result<()> {
+    info!("Installing server with {}", installer.jar);
+
+    let output = tokio::process::Command::new(&java_component.path)
+        .arg("-jar")
+        .arg(installer_path)
+        .args(installer.args.split_whitespace())
+        .current_dir(server_root)
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        bail!(
+            "Server installer {} failed with status {:?}\n{}\n{}",
+            installer.jar,
+            output.status,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks if an `eula.txt` file records the EULA as accepted.
+pub fn eula_accepted(eula: &str) -> bool {
+    eula.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .any(|(key, value)| key.trim() == "eula" && value.trim().eq_ignore_ascii_case("true"))
+}
+
+/// Returns whether the EULA of the server in `server_root` is accepted,
+/// recording the acceptance in `eula.txt` first if `accept` is set.
+pub async fn check_eula(server_root: &Path, accept: bool) -> anyhow::Result<bool> {
+    let eula_path = server_root.join("eula.txt");
+    let eula = tokio::fs::read_to_string(&eula_path)
+        .await
+        .unwrap_or_default();
+
+    if eula_accepted(&eula) {
+        return Ok(true);
+    }
+
+    if accept {
+        tokio::fs::write(
+            &eula_path,
+            "#By changing the setting below to TRUE you are indicating your agreement to our EULA (https://aka.ms/MinecraftEULA).\neula=true\n",
+        )
+        .await?;
+    }
+
+    Ok(accept)
+}
+
+/// Builds the java arguments that start a dedicated server without its gui.
+pub fn server_command(
+    setup: &ServerSetup,
+    xmx_memory: u16,
+    xms_memory: u16,
+    extra_java_args: &str,
+    windows: bool,
+) -> Vec<String> {
+    let mut command = vec![format!("-Xmx{xmx_memory}m"), format!("-Xms{xms_memory}m")];
+    command.extend(split_java_args(extra_java_args));
+    command.extend(split_java_args(&setup.launch.args(windows)));
+    command.push(String::from("nogui"));
+
+    command
+}
+
+pub async fn launch_server(
+    java_component: JavaComponent,
+    xmx_memory: u16,
+    xms_memory: u16,
+    extra_java_args: &str,
+    setup: &ServerSetup,
+    instance_path: InstancePath,
+    wrapper_command: Option<String>,
+) -> anyhow::Result<Child> {
+    let mut startup_command = server_command(
+        setup,
+        xmx_memory,
+        xms_memory,
+        extra_java_args,
+        cfg!(windows),
+    );
+
+    let main_command = wrapper_command
+        .as_ref()
+        .filter(|v| !v.is_empty())
+        .map(|s| s.as_str())
+        .unwrap_or_else(|| java_component.path.as_str());
+
+    if wrapper_command.is_some() {
+        startup_command.insert(0, java_component.path.clone());
+    }
+
+    info!(
+        "Starting Minecraft server with command: {} {}",
+        main_command,
+        startup_command.join(" ")
+    );
+
+    let mut command_exec = tokio::process::Command::new(main_command);
+    command_exec.current_dir(instance_path.get_data_path());
+
+    command_exec.stdin(std::process::Stdio::piped());
+    command_exec.stdout(std::process::Stdio::piped());
+    command_exec.stderr(std::process::Stdio::piped());
+
+    let child = command_exec.args(startup_command);
+
+    Ok(child.spawn()?)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::instance::info::{ModLoader, ModLoaderType};
+
+    use super::{
+        eula_accepted, latest_installer, server_command, server_setup, InstallerSource,
+        InstallerVersion, ServerLaunch,
+    };
+
+    #[test]
+    fn forge_server_setup() {
+        let modern = server_setup(
+            "1.20.1",
+            Some(&ModLoader {
+                type_: ModLoaderType::Forge,
+                version: String::from("1.20.1-47.2.0"),
+            }),
+            false,
+        );
+
+        assert_eq!(
+            modern.installer.unwrap().source,
+            InstallerSource::Url(String::from("https://maven.minecraftforge.net/net/minecraftforge/forge/1.20.1-47.2.0/forge-1.20.1-47.2.0-installer.jar"))
+        );
+        assert_eq!(
+            modern.server_jar,
+            "libraries/net/minecraft/server/1.20.1/server-1.20.1.jar"
+        );
+        assert_eq!(
+            modern.launch,
+            ServerLaunch::ArgsFile {
+                unix: String::from(
+                    "libraries/net/minecraftforge/forge/1.20.1-47.2.0/unix_args.txt"
+                ),
+                windows: String::from(
+                    "libraries/net/minecraftforge/forge/1.20.1-47.2.0/win_args.txt"
+                ),
+            }
+        );
+
+        let legacy = server_setup(
+            "1.12.2",
+            Some(&ModLoader {
+                type_: ModLoaderType::Forge,
+                version: String::from("1.12.2-14.23.5.2860"),
+            }),
+            false,
+        );
+
+        assert_eq!(legacy.server_jar, "minecraft_server.1.12.2.jar");
+        assert_eq!(
+            legacy.launch,
+            ServerLaunch::Jar(String::from("forge-1.12.2-14.23.5.2860.jar"))
+        );
+    }
+
+    #[test]
+    fn fabric_server_command() {
+        let setup = server_setup(
+            "1.20.1",
+            Some(&ModLoader {
+                type_: ModLoaderType::Fabric,
+                version: String::from("0.15.7"),
+            }),
+            false,
+        );
+
+        assert_eq!(
+            setup.installer.as_ref().unwrap().args,
+            "server -mcversion 1.20.1 -loader 0.15.7"
+        );
+        assert_eq!(
+            server_command(&setup, 4096, 1024, "-XX:+UseG1GC \"-Dname=a b\"", false),
+            [
+                "-Xmx4096m",
+                "-Xms1024m",
+                "-XX:+UseG1GC",
+                "-Dname=a b",
+                "-jar",
+                "fabric-server-launch.jar",
+                "nogui"
+            ]
+        );
+    }
+
+    #[test]
+    fn pick_latest_installer() {
+        let fabric = serde_json::from_str::<Vec<InstallerVersion>>(
+            r#"[
+                {"url": "https://maven.fabricmc.net/net/fabricmc/fabric-installer/1.1.0/fabric-installer-1.1.0.jar", "maven": "net.fabricmc:fabric-installer:1.1.0", "version": "1.1.0", "stable": false},
+                {"url": "https://maven.fabricmc.net/net/fabricmc/fabric-installer/1.0.1/fabric-installer-1.0.1.jar", "maven": "net.fabricmc:fabric-installer:1.0.1", "version": "1.0.1", "stable": true}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            latest_installer(fabric).unwrap(),
+            "https://maven.fabricmc.net/net/fabricmc/fabric-installer/1.0.1/fabric-installer-1.0.1.jar"
+        );
+
+        let quilt = serde_json::from_str::<Vec<InstallerVersion>>(
+            r#"[
+                {"url": "https://maven.quiltmc.org/repository/release/org/quiltmc/quilt-installer/0.9.2/quilt-installer-0.9.2.jar", "maven": "org.quiltmc:quilt-installer:0.9.2", "version": "0.9.2"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            latest_installer(quilt).unwrap(),
+            "https://maven.quiltmc.org/repository/release/org/quiltmc/quilt-installer/0.9.2/quilt-installer-0.9.2.jar"
+        );
+        assert_eq!(latest_installer(Vec::new()), None);
+    }
+
+    #[test]
+    fn read_eula() {
+        assert!(!eula_accepted(
+            "#By changing the setting below to TRUE you are indicating your agreement to our EULA (https://aka.ms/MinecraftEULA).\n\
+             #Mon Jan 01 00:00:00 UTC 2024\n\
+             eula=false\n"
+        ));
+        assert!(eula_accepted("#eula=false\neula = TRUE\n"));
+        assert!(!eula_accepted(""));
+    }
+}
@@ -84,6 +84,18 @@ pub(super) async fn load_and_migrate(runtime_path: PathBuf) -> Result<PrismaClie
             env!("CARGO_MANIFEST_DIR"),
             "/prisma/migrations/20240410205605_add_last_app_version_and_updated_at/migration.sql"
         ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/prisma/migrations/20240501120000_add_shared_servers/migration.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/prisma/migrations/20240515120000_add_pack_file_cache/migration.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/prisma/migrations/20240520120000_add_shared_game_options/migration.sql"
+        ))),
     ];
 
     let migrations = Migrations::new(migrations);
@@ -3,3 +3,4 @@
 //! This crate contains parsers and helpers for parsing data.
 
 pub mod log;
+pub mod nbt;
@@ -0,0 +1,697 @@
+//! This module provides support for reading and writing Java Edition NBT.
+//!
+//! Files such as `level.dat` are usually gzip compressed, while region chunks
+//! use zlib. Use [`decompress`] to get the raw NBT bytes, then [`parse_nbt`]
+//! to read them without copying strings or byte arrays.
+
+use std::{
+    borrow::Cow,
+    io::{self, Read, Write},
+};
+
+use flate2::{
+    read::{GzDecoder, ZlibDecoder},
+    write::{GzEncoder, ZlibEncoder},
+};
+use nom::{
+    bytes::streaming::take,
+    combinator::{map, map_res},
+    error::{Error, ErrorKind},
+    multi::count,
+    number::streaming::{be_f32, be_f64, be_i16, be_i32, be_i64, be_i8, be_u16, be_u8},
+    IResult,
+};
+
+/// Maximum nesting of lists and compounds, matching the game's own limit.
+const MAX_DEPTH: usize = 512;
+
+/// The type of an NBT tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TagKind {
+    /// Marks the end of a compound, or the type of an empty list.
+    End,
+    /// A signed 8 bit integer.
+    Byte,
+    /// A signed 16 bit integer.
+    Short,
+    /// A signed 32 bit integer.
+    Int,
+    /// A signed 64 bit integer.
+    Long,
+    /// A 32 bit float.
+    Float,
+    /// A 64 bit float.
+    Double,
+    /// An array of bytes.
+    ByteArray,
+    /// A modified UTF-8 string.
+    String,
+    /// A list of unnamed tags of the same type.
+    List,
+    /// A set of named tags.
+    Compound,
+    /// An array of 32 bit integers.
+    IntArray,
+    /// An array of 64 bit integers.
+    LongArray,
+}
+
+impl TagKind {
+    /// The id of the tag type in encoded NBT.
+    pub fn id(self) -> u8 {
+        match self {
+            Self::End => 0,
+            Self::Byte => 1,
+            Self::Short => 2,
+            Self::Int => 3,
+            Self::Long => 4,
+            Self::Float => 5,
+            Self::Double => 6,
+            Self::ByteArray => 7,
+            Self::String => 8,
+            Self::List => 9,
+            Self::Compound => 10,
+            Self::IntArray => 11,
+            Self::LongArray => 12,
+        }
+    }
+
+    /// Gets the tag type with the given id.
+    pub fn from_id(id: u8) -> Option<Self> {
+        Some(match id {
+            0 => Self::End,
+            1 => Self::Byte,
+            2 => Self::Short,
+            3 => Self::Int,
+            4 => Self::Long,
+            5 => Self::Float,
+            6 => Self::Double,
+            7 => Self::ByteArray,
+            8 => Self::String,
+            9 => Self::List,
+            10 => Self::Compound,
+            11 => Self::IntArray,
+            12 => Self::LongArray,
+            _ => return None,
+        })
+    }
+}
+
+/// A tag, borrowing strings and byte arrays from the parsed input.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tag<'a> {
+    /// `TAG_Byte`, also used for booleans.
+    Byte(i8),
+    /// `TAG_Short`.
+    Short(i16),
+    /// `TAG_Int`.
+    Int(i32),
+    /// `TAG_Long`.
+    Long(i64),
+    /// `TAG_Float`.
+    Float(f32),
+    /// `TAG_Double`.
+    Double(f64),
+    /// `TAG_Byte_Array`.
+    ByteArray(Cow<'a, [u8]>),
+    /// `TAG_String`.
+    String(Cow<'a, str>),
+    /// `TAG_List`.
+    List(List<'a>),
+    /// `TAG_Compound`.
+    Compound(Compound<'a>),
+    /// `TAG_Int_Array`.
+    IntArray(Vec<i32>),
+    /// `TAG_Long_Array`.
+    LongArray(Vec<i64>),
+}
+
+/// A list of tags of the same type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct List<'a> {
+    /// The type of every item, kept for empty lists.
+    pub kind: TagKind,
+    /// The items of the list.
+    pub items: Vec<Tag<'a>>,
+}
+
+/// Named tags, in the order they were read or inserted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Compound<'a>(pub Vec<(Cow<'a, str>, Tag<'a>)>);
+
+impl<'a> Tag<'a> {
+    /// The type of this tag.
+    pub fn kind(&self) -> TagKind {
+        match self {
+            Self::Byte(_) => TagKind::Byte,
+            Self::Short(_) => TagKind::Short,
+            Self::Int(_) => TagKind::Int,
+            Self::Long(_) => TagKind::Long,
+            Self::Float(_) => TagKind::Float,
+            Self::Double(_) => TagKind::Double,
+            Self::ByteArray(_) => TagKind::ByteArray,
+            Self::String(_) => TagKind::String,
+            Self::List(_) => TagKind::List,
+            Self::Compound(_) => TagKind::Compound,
+            Self::IntArray(_) => TagKind::IntArray,
+            Self::LongArray(_) => TagKind::LongArray,
+        }
+    }
+
+    /// Copies any borrowed data so the tag outlives its input.
+    pub fn into_owned(self) -> Tag<'static> {
+        match self {
+            Self::Byte(v) => Tag::Byte(v),
+            Self::Short(v) => Tag::Short(v),
+            Self::Int(v) => Tag::Int(v),
+            Self::Long(v) => Tag::Long(v),
+            Self::Float(v) => Tag::Float(v),
+            Self::Double(v) => Tag::Double(v),
+            Self::ByteArray(v) => Tag::ByteArray(Cow::Owned(v.into_owned())),
+            Self::String(v) => Tag::String(Cow::Owned(v.into_owned())),
+            Self::List(v) => Tag::List(List {
+                kind: v.kind,
+                items: v.items.into_iter().map(Tag::into_owned).collect(),
+            }),
+            Self::Compound(v) => Tag::Compound(v.into_owned()),
+            Self::IntArray(v) => Tag::IntArray(v),
+            Self::LongArray(v) => Tag::LongArray(v),
+        }
+    }
+
+    /// The value of a byte, short, int or long tag.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Self::Byte(v) => Some(v as i64),
+            Self::Short(v) => Some(v as i64),
+            Self::Int(v) => Some(v as i64),
+            Self::Long(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The value of a float or double tag.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::Float(v) => Some(v as f64),
+            Self::Double(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The value of a string tag.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The value of a list tag.
+    pub fn as_list(&self) -> Option<&List<'a>> {
+        match self {
+            Self::List(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The value of a compound tag.
+    pub fn as_compound(&self) -> Option<&Compound<'a>> {
+        match self {
+            Self::Compound(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The value of a compound tag, mutably.
+    pub fn as_compound_mut(&mut self) -> Option<&mut Compound<'a>> {
+        match self {
+            Self::Compound(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Compound<'a> {
+    /// Creates an empty compound.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the tag with the given name.
+    pub fn get(&self, name: &str) -> Option<&Tag<'a>> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, tag)| tag)
+    }
+
+    /// Gets the tag with the given name, mutably.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Tag<'a>> {
+        self.0
+            .iter_mut()
+            .find(|(key, _)| key == name)
+            .map(|(_, tag)| tag)
+    }
+
+    /// Sets a tag, replacing any tag with the same name in place.
+    pub fn insert(&mut self, name: impl Into<Cow<'a, str>>, tag: Tag<'a>) {
+        let name = name.into();
+
+        match self.get_mut(&name) {
+            Some(existing) => *existing = tag,
+            None => self.0.push((name, tag)),
+        }
+    }
+
+    /// Removes the tag with the given name.
+    pub fn remove(&mut self, name: &str) -> Option<Tag<'a>> {
+        let index = self.0.iter().position(|(key, _)| key == name)?;
+        Some(self.0.remove(index).1)
+    }
+
+    /// Copies any borrowed data so the compound outlives its input.
+    pub fn into_owned(self) -> Compound<'static> {
+        Compound(
+            self.0
+                .into_iter()
+                .map(|(name, tag)| (Cow::Owned(name.into_owned()), tag.into_owned()))
+                .collect(),
+        )
+    }
+}
+
+/// How an NBT file is compressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// Raw NBT.
+    None,
+    /// Gzip, used by `level.dat` and player data.
+    Gzip,
+    /// Zlib, used by region file chunks.
+    Zlib,
+}
+
+impl Compression {
+    /// Detects the compression of NBT data from its first bytes.
+    pub fn detect(data: &[u8]) -> Self {
+        match data {
+            [0x1f, 0x8b, ..] => Self::Gzip,
+            // deflate with a 32K window, at any compression level
+            [0x78, 0x01 | 0x5e | 0x9c | 0xda, ..] => Self::Zlib,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Decompresses NBT data, borrowing it when it is not compressed.
+pub fn decompress(data: &[u8]) -> io::Result<Cow<'_, [u8]>> {
+    let mut out = Vec::new();
+
+    match Compression::detect(data) {
+        Compression::None => return Ok(Cow::Borrowed(data)),
+        Compression::Gzip => GzDecoder::new(data).read_to_end(&mut out)?,
+        Compression::Zlib => ZlibDecoder::new(data).read_to_end(&mut out)?,
+    };
+
+    Ok(Cow::Owned(out))
+}
+
+/// Parses a root tag and its name.
+///
+/// The input must already be decompressed, see [`decompress`].
+pub fn parse_nbt(input: &[u8]) -> IResult<&[u8], (Cow<'_, str>, Tag<'_>)> {
+    let (input, kind) = tag_kind(input)?;
+
+    if kind == TagKind::End {
+        return Err(nom::Err::Failure(Error::new(input, ErrorKind::Tag)));
+    }
+
+    let (input, name) = string(input)?;
+    let (input, tag) = payload(input, kind, 0)?;
+
+    Ok((input, (name, tag)))
+}
+
+/// Parses a tag type id.
+fn tag_kind(input: &[u8]) -> IResult<&[u8], TagKind> {
+    let (o, id) = be_u8(input)?;
+
+    match TagKind::from_id(id) {
+        Some(kind) => Ok((o, kind)),
+        None => Err(nom::Err::Failure(Error::new(input, ErrorKind::Switch))),
+    }
+}
+
+/// Parses a length prefixed modified UTF-8 string.
+fn string(input: &[u8]) -> IResult<&[u8], Cow<'_, str>> {
+    let (o, len) = be_u16(input)?;
+    map_res(take(len), |bytes| decode_mutf8(bytes).ok_or(()))(o)
+}
+
+/// Parses an `i32` array length.
+fn array_len(input: &[u8]) -> IResult<&[u8], usize> {
+    let (o, len) = be_i32(input)?;
+
+    match usize::try_from(len) {
+        Ok(len) => Ok((o, len)),
+        Err(_) => Err(nom::Err::Failure(Error::new(input, ErrorKind::LengthValue))),
+    }
+}
+
+/// Parses the payload of a tag of the given type.
+fn payload(input: &[u8], kind: TagKind, depth: usize) -> IResult<&[u8], Tag<'_>> {
+    if depth > MAX_DEPTH {
+        return Err(nom::Err::Failure(Error::new(input, ErrorKind::TooLarge)));
+    }
+
+    match kind {
+        TagKind::End => Err(nom::Err::Failure(Error::new(input, ErrorKind::Tag))),
+        TagKind::Byte => map(be_i8, Tag::Byte)(input),
+        TagKind::Short => map(be_i16, Tag::Short)(input),
+        TagKind::Int => map(be_i32, Tag::Int)(input),
+        TagKind::Long => map(be_i64, Tag::Long)(input),
+        TagKind::Float => map(be_f32, Tag::Float)(input),
+        TagKind::Double => map(be_f64, Tag::Double)(input),
+        TagKind::ByteArray => {
+            let (o, len) = array_len(input)?;
+            map(take(len), |bytes| Tag::ByteArray(Cow::Borrowed(bytes)))(o)
+        }
+        TagKind::String => map(string, Tag::String)(input),
+        TagKind::List => {
+            let (o, item_kind) = tag_kind(input)?;
+            let (mut o, len) = array_len(o)?;
+
+            if len > 0 && item_kind == TagKind::End {
+                return Err(nom::Err::Failure(Error::new(input, ErrorKind::Tag)));
+            }
+
+            // not preallocated, the length is untrusted
+            let mut items = Vec::new();
+            for _ in 0..len {
+                let (rest, item) = payload(o, item_kind, depth + 1)?;
+                items.push(item);
+                o = rest;
+            }
+
+            Ok((
+                o,
+                Tag::List(List {
+                    kind: item_kind,
+                    items,
+                }),
+            ))
+        }
+        TagKind::Compound => {
+            let mut compound = Compound::new();
+            let mut o = input;
+
+            loop {
+                let (rest, kind) = tag_kind(o)?;
+
+                if kind == TagKind::End {
+                    return Ok((rest, Tag::Compound(compound)));
+                }
+
+                let (rest, name) = string(rest)?;
+                let (rest, tag) = payload(rest, kind, depth + 1)?;
+                compound.0.push((name, tag));
+                o = rest;
+            }
+        }
+        TagKind::IntArray => {
+            let (o, len) = array_len(input)?;
+            map(count(be_i32, len), Tag::IntArray)(o)
+        }
+        TagKind::LongArray => {
+            let (o, len) = array_len(input)?;
+            map(count(be_i64, len), Tag::LongArray)(o)
+        }
+    }
+}
+
+/// Decodes Java's modified UTF-8, borrowing the input when it is plain UTF-8.
+fn decode_mutf8(bytes: &[u8]) -> Option<Cow<'_, str>> {
+    if let Ok(s) = std::str::from_utf8(bytes) {
+        return Some(Cow::Borrowed(s));
+    }
+
+    // modified UTF-8 encodes UTF-16 code units, with nul as two bytes
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i] as u16;
+
+        let (unit, len) = match bytes[i] {
+            0x00..=0x7f => (b, 1),
+            0xc0..=0xdf => {
+                let b1 = *bytes.get(i + 1)? as u16;
+                (((b & 0x1f) << 6) | (b1 & 0x3f), 2)
+            }
+            0xe0..=0xef => {
+                let b1 = *bytes.get(i + 1)? as u16;
+                let b2 = *bytes.get(i + 2)? as u16;
+                (((b & 0x0f) << 12) | ((b1 & 0x3f) << 6) | (b2 & 0x3f), 3)
+            }
+            _ => return None,
+        };
+
+        units.push(unit);
+        i += len;
+    }
+
+    String::from_utf16(&units).ok().map(Cow::Owned)
+}
+
+/// Encodes a string as Java's modified UTF-8.
+fn encode_mutf8(s: &str) -> Cow<'_, [u8]> {
+    if !s.chars().any(|c| c == '\0' || c as u32 > 0xffff) {
+        return Cow::Borrowed(s.as_bytes());
+    }
+
+    let mut out = Vec::with_capacity(s.len() + 8);
+
+    for unit in s.encode_utf16() {
+        match unit {
+            0x01..=0x7f => out.push(unit as u8),
+            0x00 | 0x80..=0x7ff => {
+                out.push(0xc0 | (unit >> 6) as u8);
+                out.push(0x80 | (unit & 0x3f) as u8);
+            }
+            _ => {
+                out.push(0xe0 | (unit >> 12) as u8);
+                out.push(0x80 | ((unit >> 6) & 0x3f) as u8);
+                out.push(0x80 | (unit & 0x3f) as u8);
+            }
+        }
+    }
+
+    Cow::Owned(out)
+}
+
+/// Writes a root tag and its name as uncompressed NBT.
+pub fn write_nbt(writer: &mut impl Write, name: &str, tag: &Tag) -> io::Result<()> {
+    writer.write_all(&[tag.kind().id()])?;
+    write_string(writer, name)?;
+    write_payload(writer, tag)
+}
+
+/// Encodes a root tag and its name, compressing it as requested.
+pub fn to_bytes(name: &str, tag: &Tag, compression: Compression) -> io::Result<Vec<u8>> {
+    match compression {
+        Compression::None => {
+            let mut out = Vec::new();
+            write_nbt(&mut out, name, tag)?;
+            Ok(out)
+        }
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            write_nbt(&mut encoder, name, tag)?;
+            encoder.finish()
+        }
+        Compression::Zlib => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            write_nbt(&mut encoder, name, tag)?;
+            encoder.finish()
+        }
+    }
+}
+
+fn write_string(writer: &mut impl Write, s: &str) -> io::Result<()> {
+    let bytes = encode_mutf8(s);
+    let len = u16::try_from(bytes.len()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "NBT strings cannot be longer than 65535 bytes",
+        )
+    })?;
+
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(&bytes)
+}
+
+fn write_len(writer: &mut impl Write, len: usize) -> io::Result<()> {
+    let len = i32::try_from(len)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "NBT array is too long"))?;
+
+    writer.write_all(&len.to_be_bytes())
+}
+
+fn write_payload(writer: &mut impl Write, tag: &Tag) -> io::Result<()> {
+    match tag {
+        Tag::Byte(v) => writer.write_all(&v.to_be_bytes()),
+        Tag::Short(v) => writer.write_all(&v.to_be_bytes()),
+        Tag::Int(v) => writer.write_all(&v.to_be_bytes()),
+        Tag::Long(v) => writer.write_all(&v.to_be_bytes()),
+        Tag::Float(v) => writer.write_all(&v.to_be_bytes()),
+        Tag::Double(v) => writer.write_all(&v.to_be_bytes()),
+        Tag::ByteArray(v) => {
+            write_len(writer, v.len())?;
+            writer.write_all(v)
+        }
+        Tag::String(v) => write_string(writer, v),
+        Tag::List(list) => {
+            if list.items.iter().any(|item| item.kind() != list.kind) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "NBT list items must all have the list's type",
+                ));
+            }
+
+            writer.write_all(&[list.kind.id()])?;
+            write_len(writer, list.items.len())?;
+
+            for item in &list.items {
+                write_payload(writer, item)?;
+            }
+
+            Ok(())
+        }
+        Tag::Compound(compound) => {
+            for (name, tag) in &compound.0 {
+                writer.write_all(&[tag.kind().id()])?;
+                write_string(writer, name)?;
+                write_payload(writer, tag)?;
+            }
+
+            writer.write_all(&[TagKind::End.id()])
+        }
+        Tag::IntArray(v) => {
+            write_len(writer, v.len())?;
+            v.iter()
+                .try_for_each(|v| writer.write_all(&v.to_be_bytes()))
+        }
+        Tag::LongArray(v) => {
+            write_len(writer, v.len())?;
+            v.iter()
+                .try_for_each(|v| writer.write_all(&v.to_be_bytes()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Tag<'static> {
+        let mut data = Compound::new();
+        data.insert("LevelName", Tag::String("New World".into()));
+        data.insert("hardcore", Tag::Byte(1));
+        data.insert("Difficulty", Tag::Short(2));
+        data.insert("GameType", Tag::Int(0));
+        data.insert("LastPlayed", Tag::Long(1_700_000_000_000));
+        data.insert("BorderSize", Tag::Double(5.9999968e7));
+        data.insert("SpawnAngle", Tag::Float(90.5));
+        data.insert("Bytes", Tag::ByteArray(vec![0, 1, 255].into()));
+        data.insert("UUID", Tag::IntArray(vec![-1, 2, i32::MAX, i32::MIN]));
+        data.insert("Longs", Tag::LongArray(vec![i64::MIN, 0, i64::MAX]));
+        data.insert(
+            "Empty",
+            Tag::List(List {
+                kind: TagKind::End,
+                items: Vec::new(),
+            }),
+        );
+        data.insert(
+            "Names",
+            Tag::List(List {
+                kind: TagKind::String,
+                items: vec![Tag::String("a\0b".into()), Tag::String("emoji 🎉".into())],
+            }),
+        );
+
+        let mut root = Compound::new();
+        root.insert("Data", Tag::Compound(data));
+
+        Tag::Compound(root)
+    }
+
+    #[test]
+    fn parse_hello_world() {
+        let input = b"\x0a\x00\x0bhello world\x08\x00\x04name\x00\x09Bananrama\x00";
+
+        let (rest, (name, tag)) = parse_nbt(input).unwrap();
+
+        assert!(rest.is_empty());
+        assert_eq!(name, "hello world");
+        assert_eq!(
+            tag.as_compound().unwrap().get("name"),
+            Some(&Tag::String("Bananrama".into()))
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let tag = sample();
+
+        for compression in [Compression::None, Compression::Gzip, Compression::Zlib] {
+            let bytes = to_bytes("", &tag, compression).unwrap();
+            assert_eq!(Compression::detect(&bytes), compression);
+
+            let raw = decompress(&bytes).unwrap();
+            let (rest, (name, parsed)) = parse_nbt(&raw).unwrap();
+
+            assert!(rest.is_empty());
+            assert_eq!(name, "");
+            assert_eq!(parsed, tag);
+        }
+    }
+
+    #[test]
+    fn modified_utf8() {
+        assert_eq!(&*encode_mutf8("a\0b"), b"a\xc0\x80b");
+        assert_eq!(&*encode_mutf8("🎉"), b"\xed\xa0\xbc\xed\xbe\x89");
+        assert_eq!(decode_mutf8(b"a\xc0\x80b").unwrap(), "a\0b");
+        assert_eq!(decode_mutf8(b"\xed\xa0\xbc\xed\xbe\x89").unwrap(), "🎉");
+        assert!(matches!(
+            decode_mutf8(b"plain"),
+            Some(Cow::Borrowed("plain"))
+        ));
+    }
+
+    #[test]
+    fn incomplete_input() {
+        let bytes = to_bytes("root", &sample(), Compression::None).unwrap();
+
+        assert!(matches!(
+            parse_nbt(&bytes[..bytes.len() - 1]),
+            Err(nom::Err::Incomplete(_))
+        ));
+    }
+
+    #[test]
+    fn reject_invalid_tags() {
+        // unknown tag type inside a compound
+        assert!(matches!(
+            parse_nbt(b"\x0a\x00\x00\x0d\x00\x00"),
+            Err(nom::Err::Failure(_))
+        ));
+
+        // negative array length
+        assert!(matches!(
+            parse_nbt(b"\x07\x00\x00\xff\xff\xff\xff"),
+            Err(nom::Err::Failure(_))
+        ));
+    }
+}